@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS `aircraft_sim_titles`(
+	`id` INTEGER NOT NULL PRIMARY KEY,
+	`aircraft` INTEGER NOT NULL,
+	`simulator` TEXT NOT NULL,
+	`title` TEXT NOT NULL
+);
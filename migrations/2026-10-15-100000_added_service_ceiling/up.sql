@@ -0,0 +1,43 @@
+ALTER TABLE aircraft ADD COLUMN service_ceiling INTEGER;
+
+-- Set service ceilings in feet for each aircraft variant
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant IN ('42-600', '42-600S', '72-600', '72-600F');
+UPDATE aircraft SET service_ceiling = 20000 WHERE variant = 'Baron G53';
+UPDATE aircraft SET service_ceiling = 18500 WHERE variant = 'Bonanza G36';
+UPDATE aircraft SET service_ceiling = 26000 WHERE variant = 'D18S Twin Beech';
+UPDATE aircraft SET service_ceiling = 35000 WHERE variant = 'King Air 350i';
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant = 'Model 17 Staggerwing';
+UPDATE aircraft SET service_ceiling = 26000 WHERE variant = '307 Stratoliner';
+UPDATE aircraft SET service_ceiling = 45000 WHERE variant = 'MB-339A';
+UPDATE aircraft SET service_ceiling = 41000 WHERE variant = 'A310';
+UPDATE aircraft SET service_ceiling = 39000 WHERE variant IN ('A319 CFM', 'A319 IAE', 'A320 CFM', 'A320 IAE', 'A320N', 'A321 CFM', 'A321 IAE');
+UPDATE aircraft SET service_ceiling = 43000 WHERE variant = 'A380';
+UPDATE aircraft SET service_ceiling = 36000 WHERE variant = 'An-225 Mriya';
+UPDATE aircraft SET service_ceiling = 20000 WHERE variant = 'Husky A-1C';
+UPDATE aircraft SET service_ceiling = 19000 WHERE variant IN ('Pitts Special S-1S', 'Pitts Special S-2S');
+UPDATE aircraft SET service_ceiling = 60000 WHERE variant = 'Concorde';
+UPDATE aircraft SET service_ceiling = 41000 WHERE variant IN ('737 MAX 8', '737-600', '737-700', '737-700 BBJ', '737-700 BDSF', '737-700 BDFS', '737-800', '737-800 BBJ2', '737-800 BCF', '737-800 BDSF', '737-900', '737-900ER');
+UPDATE aircraft SET service_ceiling = 43100 WHERE variant IN ('747-800', '777-300ER', '787-10');
+UPDATE aircraft SET service_ceiling = 41000 WHERE variant IN ('CRJ550ER', 'CRJ700ER', 'ERJ-170LR', 'ERJ-175LR');
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant = '208 B Grand Caravan EX';
+UPDATE aircraft SET service_ceiling = 14000 WHERE variant IN ('C172 Skyhawk', 'Cessna 152');
+UPDATE aircraft SET service_ceiling = 45000 WHERE variant IN ('Citation CJ4', 'Citation Longitude');
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant = 'SR22T';
+UPDATE aircraft SET service_ceiling = 17000 WHERE variant IN ('NXCub', 'XCub');
+UPDATE aircraft SET service_ceiling = 31000 WHERE variant = 'TBM 930';
+UPDATE aircraft SET service_ceiling = 18000 WHERE variant = 'DHC-2 Beaver';
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant = 'DHC-6 Twin Otter';
+UPDATE aircraft SET service_ceiling = 16400 WHERE variant IN ('DA-40 NG', 'DA-40 TDI');
+UPDATE aircraft SET service_ceiling = 20000 WHERE variant = 'DA-62';
+UPDATE aircraft SET service_ceiling = 13100 WHERE variant = 'DV20';
+UPDATE aircraft SET service_ceiling = 24000 WHERE variant IN ('C-47', 'DC-3');
+UPDATE aircraft SET service_ceiling = 25000 WHERE variant IN ('DC-6A', 'DC-6B');
+UPDATE aircraft SET service_ceiling = 14000 WHERE variant = 'Optica';
+UPDATE aircraft SET service_ceiling = 15000 WHERE variant = '330LT';
+UPDATE aircraft SET service_ceiling = 14000 WHERE variant IN ('CTSl', 'VL-3', 'Freedomfox');
+UPDATE aircraft SET service_ceiling = 16000 WHERE variant IN ('F.VII', 'Trimotor', 'JU-52');
+UPDATE aircraft SET service_ceiling = 35000 WHERE variant IN ('F28-1000', 'F28-2000', 'F28-3000', 'F28-4000');
+UPDATE aircraft SET service_ceiling = 21000 WHERE variant = 'G-21A goose';
+UPDATE aircraft SET service_ceiling = 43000 WHERE variant = 'HA420';
+UPDATE aircraft SET service_ceiling = 42000 WHERE variant IN ('MD-11', 'MD-11F');
+UPDATE aircraft SET service_ceiling = 9000 WHERE variant = 'A5';
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS `airport_icao_aliases`(
+	`alias` TEXT NOT NULL PRIMARY KEY,
+	`icao` TEXT NOT NULL
+);
+
+-- Codes replaced by a new ICAO code for the same airport
+INSERT OR IGNORE INTO `airport_icao_aliases` (`alias`, `icao`) VALUES
+	('LYPR', 'BKPR');
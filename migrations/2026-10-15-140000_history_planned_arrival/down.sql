@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE history DROP COLUMN planned_arrival_icao;
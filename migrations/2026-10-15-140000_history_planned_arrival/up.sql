@@ -0,0 +1 @@
+ALTER TABLE history ADD COLUMN planned_arrival_icao TEXT;
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+UPDATE aircraft SET flown = MIN(flown, 1);
@@ -0,0 +1,6 @@
+-- `flown` counts the flights of an aircraft. Aircraft marked as flown without
+-- a history entry keep a count of one.
+UPDATE aircraft SET flown = MAX(
+    flown,
+    (SELECT COUNT(*) FROM history WHERE history.aircraft = aircraft.id)
+);
@@ -0,0 +1,8 @@
+ALTER TABLE aircraft ADD COLUMN helicopter BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE aircraft ADD COLUMN amphibian BOOLEAN NOT NULL DEFAULT 0;
+
+-- Helicopters need a helipad or a runway of any length
+UPDATE aircraft SET helicopter = 1 WHERE category = 'H';
+
+-- Amphibians can land on water as well as on runways
+UPDATE aircraft SET amphibian = 1 WHERE variant IN ('G-21A goose', 'A5');
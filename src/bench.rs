@@ -0,0 +1,126 @@
+use crate::models::{Aircraft, Airport, Runway};
+use crate::modules::routes::{RouteConstraints, RouteGenerator};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const AIRPORT_COUNTS: [usize; 3] = [1_000, 10_000, 80_000];
+const ROUTES_PER_RUN: usize = 50;
+const RUNS: u32 = 20;
+const SEED: u64 = 42;
+
+/// Benchmarks route generation against synthetic airport databases of increasing size.
+pub fn bench_routes() {
+    let aircraft = bench_aircraft();
+
+    println!(
+        "{:>8} {:>10} {:>12} {:>12} {:>12}",
+        "airports", "runways", "build", "avg/run", "routes/run"
+    );
+
+    for airport_count in AIRPORT_COUNTS {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let (airports, runways) = synthetic_airports(&mut rng, airport_count);
+        let runway_count = runways.len();
+
+        let start_time = Instant::now();
+        let route_generator = RouteGenerator::new(airports, runways);
+        let build_time = start_time.elapsed();
+
+        let mut total_time = Duration::ZERO;
+        let mut total_routes = 0;
+        for _ in 0..RUNS {
+            let start_time = Instant::now();
+            total_routes += route_generator
+                .generate_random_routes(
+                    &aircraft,
+                    ROUTES_PER_RUN,
+                    &RouteConstraints::default(),
+                    &[],
+                )
+                .len();
+            total_time += start_time.elapsed();
+        }
+
+        println!(
+            "{:>8} {:>10} {:>12.2?} {:>12.2?} {:>12}",
+            airport_count,
+            runway_count,
+            build_time,
+            total_time / RUNS,
+            total_routes / RUNS as usize
+        );
+    }
+}
+
+/// Returns a small fleet covering short, medium and long range aircraft.
+fn bench_aircraft() -> Vec<Arc<Aircraft>> {
+    let aircraft = |id: i32, variant: &str, range: i32, takeoff_distance: Option<i32>| {
+        Arc::new(Aircraft {
+            id,
+            manufacturer: "Bench".to_string(),
+            variant: variant.to_string(),
+            icao_code: String::new(),
+            flown: 0,
+            aircraft_range: range,
+            category: "A".to_string(),
+            cruise_speed: 250,
+            date_flown: None,
+            takeoff_distance,
+            service_ceiling: None,
+            helicopter: false,
+            amphibian: false,
+        })
+    };
+
+    vec![
+        aircraft(1, "Short", 400, Some(300)),
+        aircraft(2, "Medium", 1500, Some(1500)),
+        aircraft(3, "Long", 6000, Some(2800)),
+        aircraft(4, "Unknown", 800, None),
+    ]
+}
+
+/// Generates randomly placed airports with zero to three runways each.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator.
+/// * `count` - The number of airports to generate.
+fn synthetic_airports(rng: &mut StdRng, count: usize) -> (Vec<Airport>, Vec<Runway>) {
+    let mut airports = Vec::with_capacity(count);
+    let mut runways = Vec::new();
+
+    for index in 0..count {
+        let airport_id = index as i32 + 1;
+        let latitude = rng.gen_range(-60.0..70.0);
+        let longitude = rng.gen_range(-180.0..180.0);
+
+        for _ in 0..rng.gen_range(0..=3) {
+            runways.push(Runway {
+                ID: runways.len() as i32 + 1,
+                AirportID: airport_id,
+                Ident: "09".to_string(),
+                TrueHeading: rng.gen_range(0.0..360.0),
+                Length: rng.gen_range(1_000..13_000),
+                Width: 100,
+                Surface: "ASP".to_string(),
+                Latitude: latitude,
+                Longtitude: longitude,
+                Elevation: 0,
+            });
+        }
+
+        airports.push(Airport {
+            ID: airport_id,
+            Name: format!("Airport {}", airport_id),
+            ICAO: format!("X{:03}", index % 1000),
+            Latitude: latitude,
+            Longtitude: longitude,
+            ..Default::default()
+        });
+    }
+
+    (airports, runways)
+}
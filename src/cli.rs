@@ -0,0 +1,1129 @@
+use chrono::NaiveDate;
+use std::io::Write;
+
+use crate::errors::ValidationError;
+use crate::models::{Aircraft, AirportAlias, FlightDetails, History};
+use crate::modules::airac::{stale_cycle_warning, AiracCycle};
+use crate::modules::airport::airport_by_code;
+use crate::modules::frequency::{format_frequency, parse_frequency_csv};
+use crate::modules::history_export::{json_string, HistoryExportFormat};
+use crate::modules::iata::parse_airport_csv;
+use crate::modules::routes::Route;
+use crate::modules::sync::{sync_files, KeepSide, SyncOutcome};
+use crate::modules::validation;
+use crate::traits::{
+    AircraftOperations, AirportOperations, DataSourceOperations, FrequencyOperations,
+    HistoryOperations,
+};
+
+/// Errors reported by the command line, each with its own exit code.
+#[derive(Debug)]
+pub enum CliError {
+    /// The arguments could not be understood.
+    Usage(String),
+    /// A database file does not exist.
+    DatabaseMissing(String),
+    /// An airport, aircraft or route matching the arguments was not found.
+    NotFound(String),
+    /// A database could not be opened, migrated or queried.
+    Database(Box<dyn std::error::Error + Send + Sync>),
+    /// Reading from or writing to the terminal or a file failed.
+    Io(std::io::Error),
+    /// A file changed in two places and neither copy was chosen.
+    Conflict(String),
+    /// Another instance is running, which would lose what it writes to replaced files.
+    InstanceRunning,
+}
+
+impl CliError {
+    /// Returns the process exit code for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Database(_) | CliError::Io(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::DatabaseMissing(_) => 3,
+            CliError::NotFound(_) => 4,
+            CliError::Conflict(_) => 5,
+            CliError::InstanceRunning => 6,
+        }
+    }
+
+    /// Returns a stable name for the error, for scripts parsing the error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Usage(_) => "usage",
+            CliError::DatabaseMissing(_) => "database-missing",
+            CliError::NotFound(_) => "not-found",
+            CliError::Database(_) => "database",
+            CliError::Io(_) => "io",
+            CliError::Conflict(_) => "conflict",
+            CliError::InstanceRunning => "instance-running",
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::DatabaseMissing(path) => write!(f, "Database not found at {}", path),
+            CliError::NotFound(msg) => write!(f, "{}", msg),
+            CliError::Conflict(msg) => write!(f, "{}", msg),
+            CliError::InstanceRunning => {
+                write!(f, "Flight planner is running; close it before syncing")
+            }
+            CliError::Database(e) => write!(f, "Database error: {}", e),
+            CliError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<ValidationError> for CliError {
+    fn from(error: ValidationError) -> Self {
+        CliError::Usage(error.to_string())
+    }
+}
+
+impl From<diesel::result::Error> for CliError {
+    fn from(error: diesel::result::Error) -> Self {
+        CliError::Database(Box::new(error))
+    }
+}
+
+impl From<diesel::ConnectionError> for CliError {
+    fn from(error: diesel::ConnectionError) -> Self {
+        CliError::Database(Box::new(error))
+    }
+}
+
+impl From<r2d2::Error> for CliError {
+    fn from(error: r2d2::Error) -> Self {
+        CliError::Database(Box::new(error))
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+const HISTORY_USAGE: &str = "Usage:\n  \
+    history list [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--aircraft ICAO|ID] \
+    [--airport ICAO] [--json]\n  \
+    history add [--from ICAO|IATA] [--to ICAO|IATA] [--aircraft ICAO|ID] [--date YYYY-MM-DD] \
+    [--note TEXT] [--planned-arrival ICAO|IATA] [--airline NAME] [--allow-duplicate]\n  \
+    history recount\n  \
+    history merge <other aircraft database>\n  \
+    history export <file.csv|file.json>";
+
+const ALIAS_USAGE: &str = "Usage:\n  \
+    alias list\n  \
+    alias add <former ICAO> <current ICAO>";
+
+/// Subcommands of `alias`.
+pub const ALIAS_SUBCOMMANDS: [&str; 2] = ["list", "add"];
+
+const SOURCES_USAGE: &str = "Usage:\n  \
+    sources list\n  \
+    sources add <name> <version> [--date YYYY-MM-DD]";
+
+/// Subcommands of `sources`.
+pub const SOURCES_SUBCOMMANDS: [&str; 2] = ["list", "add"];
+
+const FREQUENCIES_USAGE: &str = "Usage:\n  \
+    frequencies import <airport-frequencies.csv>\n  \
+    frequencies list <ICAO|IATA>";
+
+/// Subcommands of `frequencies`.
+pub const FREQUENCIES_SUBCOMMANDS: [&str; 2] = ["import", "list"];
+
+pub const ROUTE_USAGE: &str = "Usage:\n  \
+    route random [--count N] [--aircraft ICAO|ID] [--min-distance NM] [--max-distance NM] \
+    [--json]";
+
+/// Subcommands of `route`.
+pub const ROUTE_SUBCOMMANDS: [&str; 1] = ["random"];
+/// Flags of `route random` and `--export` that take a value.
+pub const ROUTE_VALUE_FLAGS: [&str; 4] =
+    ["--count", "--aircraft", "--min-distance", "--max-distance"];
+
+const AIRCRAFT_USAGE: &str = "Usage:\n  \
+    aircraft list [--json]";
+
+/// Subcommands of `aircraft`.
+pub const AIRCRAFT_SUBCOMMANDS: [&str; 1] = ["list"];
+
+const AIRPORT_USAGE: &str = "Usage:\n  \
+    airport info <ICAO|IATA> [--json]\n  \
+    airport import-codes <airports.csv>";
+
+/// Subcommands of `airport`.
+pub const AIRPORT_SUBCOMMANDS: [&str; 2] = ["info", "import-codes"];
+const AIRAC_USAGE: &str = "Usage:\n  \
+    airac show\n  \
+    airac set <cycle, such as 2401>";
+
+/// Subcommands of `airac`.
+pub const AIRAC_SUBCOMMANDS: [&str; 2] = ["show", "set"];
+const SYNC_USAGE: &str = "Usage:\n  \
+    sync <folder> [--keep-local|--keep-remote]";
+
+/// Flags of `sources add`, which all take a value.
+const SOURCES_ADD_FLAGS: [&str; 1] = ["--date"];
+
+/// Flags of `history list` that take a value.
+pub const HISTORY_LIST_VALUE_FLAGS: [&str; 4] = ["--since", "--until", "--aircraft", "--airport"];
+/// Flags of `history list` without a value.
+pub const HISTORY_LIST_SWITCHES: [&str; 1] = ["--json"];
+/// Flags of `history add`, which all take a value.
+pub const HISTORY_ADD_FLAGS: [&str; 7] = [
+    "--from",
+    "--to",
+    "--aircraft",
+    "--date",
+    "--note",
+    "--planned-arrival",
+    "--airline",
+];
+/// Flags of `history add` without a value.
+pub const HISTORY_ADD_SWITCHES: [&str; 1] = ["--allow-duplicate"];
+
+/// Runs a `history` subcommand.
+///
+/// # Arguments
+///
+/// * `database_connections` - The database to read and write history in.
+/// * `args` - The arguments after `history`.
+/// * `quiet` - Whether to leave out messages and prompts, for use in scripts.
+pub fn history_command<T: HistoryOperations + AirportOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            check_flags(
+                &args[1..],
+                &HISTORY_LIST_VALUE_FLAGS,
+                &HISTORY_LIST_SWITCHES,
+            )?;
+            history_list(database_connections, &args[1..], quiet)
+        }
+        Some("add") => {
+            check_flags(&args[1..], &HISTORY_ADD_FLAGS, &HISTORY_ADD_SWITCHES)?;
+            history_add(database_connections, &args[1..], quiet)
+        }
+        Some("merge") if args.len() == 2 => {
+            let other = std::path::Path::new(&args[1]);
+            if !other.exists() {
+                return Err(CliError::DatabaseMissing(args[1].clone()));
+            }
+            let summary = database_connections.merge_history(other)?;
+            if !quiet {
+                println!(
+                    "Added {} flights, skipped {} already in the history",
+                    summary.added, summary.duplicates
+                );
+                if summary.unmatched > 0 {
+                    println!(
+                        "Skipped {} flights of aircraft not in this database: {}",
+                        summary.unmatched,
+                        summary
+                            .unknown_aircraft
+                            .iter()
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+            }
+            Ok(())
+        }
+        Some("export") if args.len() == 2 => {
+            let output = std::path::Path::new(&args[1]);
+            let format = HistoryExportFormat::from_path(output).ok_or_else(|| {
+                CliError::Usage(format!(
+                    "Unknown export format of {}, expected a .csv or .json file",
+                    output.display()
+                ))
+            })?;
+            std::fs::write(output, database_connections.export_history(format)?)?;
+            if !quiet {
+                println!("Exported the history to {}", output.display());
+            }
+            Ok(())
+        }
+        Some("recount") if args.len() == 1 => {
+            let changed = database_connections.recount_flights()?;
+            if !quiet {
+                println!(
+                    "Recounted the flights of every aircraft, {} changed",
+                    changed
+                );
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(HISTORY_USAGE.to_string())),
+    }
+}
+
+/// Runs an `alias` subcommand, which manages former ICAO codes of airports.
+///
+/// # Arguments
+///
+/// * `database_connections` - The database to read and write aliases in.
+/// * `args` - The arguments after `alias`.
+/// * `quiet` - Whether to leave out messages.
+pub fn alias_command<T: AirportOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    match args {
+        [command] if command == "list" => {
+            let aliases = database_connections.get_airport_aliases()?;
+            if aliases.is_empty() && !quiet {
+                println!("No aliases found");
+            }
+            for alias in aliases {
+                println!("{} -> {}", alias.alias, alias.icao);
+            }
+            Ok(())
+        }
+        [command, alias, icao] if command == "add" => {
+            let airport = find_airport(database_connections, icao)?;
+            if database_connections.get_airport_by_icao(alias).is_ok() {
+                return Err(CliError::Usage(format!(
+                    "{} is the code of an airport in the database",
+                    alias.to_uppercase()
+                )));
+            }
+            database_connections.add_airport_alias(&AirportAlias {
+                alias: alias.to_uppercase(),
+                icao: airport.ICAO.clone(),
+            })?;
+            if !quiet {
+                println!(
+                    "{} now resolves to {} ({})",
+                    alias.to_uppercase(),
+                    airport.ICAO,
+                    airport.Name
+                );
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(ALIAS_USAGE.to_string())),
+    }
+}
+
+/// Runs a `sources` subcommand, which records where the airport data came from.
+///
+/// # Arguments
+///
+/// * `database_connections` - The airport database to read and write sources in.
+/// * `args` - The arguments after `sources`.
+/// * `quiet` - Whether to leave out messages.
+pub fn sources_command<T: DataSourceOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    match args.first().map(String::as_str) {
+        Some("list") if args.len() == 1 => {
+            let sources = database_connections.get_data_sources()?;
+            if sources.is_empty() && !quiet {
+                println!("No data sources recorded");
+            }
+            for source in sources {
+                println!(
+                    "{} {} ({}): {} airports",
+                    source.name, source.version, source.date, source.airports
+                );
+            }
+            Ok(())
+        }
+        Some("add") if args.len() >= 3 => {
+            check_flags(&args[3..], &SOURCES_ADD_FLAGS, &[])?;
+            let date = match flag_value(&args[3..], "--date") {
+                Some(date) => parse_date(date)?,
+                None => chrono::Local::now().date_naive(),
+            };
+            let airports = database_connections.add_data_source(
+                &args[1],
+                &args[2],
+                &date.format("%Y-%m-%d").to_string(),
+            )?;
+            if !quiet {
+                println!(
+                    "Recorded {} {} ({}) as the source of {} airports",
+                    args[1], args[2], date, airports
+                );
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(SOURCES_USAGE.to_string())),
+    }
+}
+
+/// Runs a `frequencies` subcommand, which imports and lists airport radio frequencies.
+///
+/// # Arguments
+///
+/// * `database_connections` - The airport database to store the frequencies in.
+/// * `args` - The arguments after `frequencies`.
+/// * `quiet` - Whether to leave out messages.
+pub fn frequencies_command<T: AirportOperations + FrequencyOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    match args {
+        [command, path] if command == "import" => {
+            let contents = std::fs::read_to_string(path)?;
+            let records = parse_frequency_csv(&contents)?;
+            let imported = database_connections.import_frequencies(&records)?;
+            if !quiet {
+                println!(
+                    "Imported {} of {} frequencies; the others belong to airports not in the database",
+                    imported,
+                    records.len()
+                );
+            }
+            Ok(())
+        }
+        [command, code] if command == "list" => {
+            let airport = find_airport(database_connections, code)?;
+            let frequencies = database_connections.get_frequencies(&airport)?;
+            if frequencies.is_empty() && !quiet {
+                println!("No frequencies known for {}", airport.ICAO);
+            }
+            for frequency in frequencies {
+                println!(
+                    "{:<6} {:>7}  {}",
+                    frequency.kind,
+                    format_frequency(frequency.frequency_khz),
+                    frequency.description
+                );
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(FREQUENCIES_USAGE.to_string())),
+    }
+}
+
+/// Runs an `airac` subcommand, which tags the airport database with an AIRAC cycle.
+///
+/// # Arguments
+///
+/// * `database_connections` - The airport database to read and write the cycle in.
+/// * `args` - The arguments after `airac`.
+/// * `quiet` - Whether to leave out messages.
+pub fn airac_command<T: DataSourceOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    let today = chrono::Local::now().date_naive();
+
+    match args {
+        [command] if command == "show" => {
+            match database_connections.get_airac_cycle()? {
+                Some(cycle) => {
+                    println!("{} (effective {})", cycle, cycle.effective_date());
+                    if let Some(warning) = stale_cycle_warning(cycle) {
+                        eprintln!("{}", warning);
+                    }
+                }
+                None if !quiet => println!("No AIRAC cycle set"),
+                None => {}
+            }
+            if !quiet {
+                println!("Current cycle: {}", AiracCycle::at(today));
+            }
+            Ok(())
+        }
+        [command, cycle] if command == "set" => {
+            let cycle = AiracCycle::parse(cycle)?;
+            database_connections.set_airac_cycle(cycle)?;
+            if !quiet {
+                println!(
+                    "Tagged the airport database with AIRAC {} (effective {})",
+                    cycle,
+                    cycle.effective_date()
+                );
+                if let Some(warning) = stale_cycle_warning(cycle) {
+                    eprintln!("{}", warning);
+                }
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(AIRAC_USAGE.to_string())),
+    }
+}
+
+/// Runs an `aircraft` subcommand, which lists the aircraft.
+///
+/// # Arguments
+///
+/// * `database_connections` - The database to read the aircraft from.
+/// * `args` - The arguments after `aircraft`.
+/// * `quiet` - Whether to leave out messages.
+pub fn aircraft_command<T: AircraftOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    let Some((command, flags)) = args.split_first() else {
+        return Err(CliError::Usage(AIRCRAFT_USAGE.to_string()));
+    };
+    if command != "list" {
+        return Err(CliError::Usage(AIRCRAFT_USAGE.to_string()));
+    }
+    check_flags(flags, &[], &["--json"])?;
+
+    let all_aircraft = database_connections.get_all_aircraft()?;
+    if has_flag(flags, "--json") {
+        println!("{}", aircraft_json(&all_aircraft));
+    } else if all_aircraft.is_empty() {
+        if !quiet {
+            println!("No aircraft found");
+        }
+    } else {
+        let rows: Vec<[String; 6]> = all_aircraft
+            .iter()
+            .map(|aircraft| {
+                [
+                    aircraft.id.to_string(),
+                    aircraft.icao_code.clone(),
+                    format!("{} {}", aircraft.manufacturer, aircraft.variant),
+                    format!("{} NM", aircraft.aircraft_range),
+                    aircraft.flown.to_string(),
+                    aircraft.date_flown.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        print_table(
+            ["ID", "ICAO", "Aircraft", "Range", "Flown", "Last flown"],
+            &rows,
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs an `airport` subcommand, which shows an airport with its runways and
+/// frequencies, or imports the IATA codes of the airports.
+///
+/// # Arguments
+///
+/// * `database_connections` - The database to look the airport up in.
+/// * `args` - The arguments after `airport`.
+/// * `quiet` - Whether to leave out messages.
+pub fn airport_command<T: AirportOperations + FrequencyOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    if let [command, path] = args {
+        if command == "import-codes" {
+            let contents = std::fs::read_to_string(path)?;
+            let records = parse_airport_csv(&contents)?;
+            let imported = database_connections.import_iata_codes(&records)?;
+            if !quiet {
+                println!(
+                    "Imported {} of {} IATA codes; the others belong to airports not in the database",
+                    imported,
+                    records.len()
+                );
+            }
+            return Ok(());
+        }
+    }
+
+    let [command, code, flags @ ..] = args else {
+        return Err(CliError::Usage(AIRPORT_USAGE.to_string()));
+    };
+    if command != "info" {
+        return Err(CliError::Usage(AIRPORT_USAGE.to_string()));
+    }
+    check_flags(flags, &[], &["--json"])?;
+
+    let airport = find_airport(database_connections, code)?;
+    let iata = database_connections
+        .get_iata_codes()?
+        .into_iter()
+        .find(|code| code.icao == airport.ICAO)
+        .map(|code| code.iata);
+    let runways = database_connections.get_runways_for_airport(&airport)?;
+    let frequencies = database_connections.get_frequencies(&airport)?;
+
+    if has_flag(flags, "--json") {
+        let runways: Vec<String> = runways
+            .iter()
+            .map(|runway| {
+                format!(
+                    "{{\"ident\": {}, \"length_ft\": {}, \"width_ft\": {}, \"surface\": {}}}",
+                    json_string(&runway.Ident),
+                    runway.Length,
+                    runway.Width,
+                    json_string(&runway.Surface)
+                )
+            })
+            .collect();
+        let frequencies: Vec<String> = frequencies
+            .iter()
+            .map(|frequency| {
+                format!(
+                    "{{\"type\": {}, \"frequency_mhz\": {}, \"description\": {}}}",
+                    json_string(&frequency.kind),
+                    format_frequency(frequency.frequency_khz),
+                    json_string(&frequency.description)
+                )
+            })
+            .collect();
+        println!(
+            "{{\"icao\": {}, \"iata\": {}, \"name\": {}, \"latitude\": {}, \"longitude\": {}, \
+             \"elevation_ft\": {}, \"runways\": [{}], \"frequencies\": [{}]}}",
+            json_string(&airport.ICAO),
+            iata.as_deref()
+                .map_or_else(|| "null".to_string(), json_string),
+            json_string(&airport.Name),
+            airport.Latitude,
+            airport.Longtitude,
+            airport.Elevation,
+            runways.join(", "),
+            frequencies.join(", ")
+        );
+        return Ok(());
+    }
+
+    match &iata {
+        Some(iata) => println!("{} ({})  {}", airport.ICAO, iata, airport.Name),
+        None => println!("{}  {}", airport.ICAO, airport.Name),
+    }
+    println!(
+        "Position   {:.4}, {:.4}",
+        airport.Latitude, airport.Longtitude
+    );
+    println!("Elevation  {} ft", airport.Elevation);
+    if !runways.is_empty() {
+        println!("\nRunways");
+        for runway in &runways {
+            println!(
+                "  {:<8} {:>6} x {:>3} ft  {}",
+                runway.Ident, runway.Length, runway.Width, runway.Surface
+            );
+        }
+    }
+    if !frequencies.is_empty() {
+        println!("\nFrequencies");
+        for frequency in &frequencies {
+            println!(
+                "  {:<6} {:>7}  {}",
+                frequency.kind,
+                format_frequency(frequency.frequency_khz),
+                frequency.description
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints generated routes as a table or as JSON.
+///
+/// # Arguments
+///
+/// * `routes` - The routes to print.
+/// * `json` - Whether to print JSON.
+pub fn print_routes(routes: &[Route], json: bool) {
+    if json {
+        let entries: Vec<String> = routes
+            .iter()
+            .map(|route| {
+                format!(
+                    "  {{\"departure\": {}, \"departure_name\": {}, \"destination\": {}, \
+                     \"destination_name\": {}, \"aircraft_id\": {}, \"aircraft\": {}, \
+                     \"distance_nm\": {:.0}}}",
+                    json_string(&route.departure.ICAO),
+                    json_string(&route.departure.Name),
+                    json_string(&route.destination.ICAO),
+                    json_string(&route.destination.Name),
+                    route.aircraft.id,
+                    json_string(&format!(
+                        "{} {}",
+                        route.aircraft.manufacturer, route.aircraft.variant
+                    )),
+                    route.distance_nm()
+                )
+            })
+            .collect();
+        if entries.is_empty() {
+            println!("[]");
+        } else {
+            println!("[\n{}\n]", entries.join(",\n"));
+        }
+        return;
+    }
+
+    let rows: Vec<[String; 4]> = routes
+        .iter()
+        .map(|route| {
+            [
+                route.departure.ICAO.clone(),
+                route.destination.ICAO.clone(),
+                format!("{} {}", route.aircraft.manufacturer, route.aircraft.variant),
+                format!("{:.0} NM", route.distance_nm()),
+            ]
+        })
+        .collect();
+    print_table(["Departure", "Destination", "Aircraft", "Distance"], &rows);
+}
+
+/// Prints the history entries matching the filters as a table or as JSON.
+fn history_list<T: HistoryOperations + AircraftOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    let since = flag_value(args, "--since").map(parse_date).transpose()?;
+    let until = flag_value(args, "--until").map(parse_date).transpose()?;
+    let aircraft_filter = flag_value(args, "--aircraft");
+    let airport_filter = flag_value(args, "--airport");
+
+    let all_aircraft = database_connections.get_all_aircraft()?;
+    let mut records: Vec<History> = database_connections
+        .get_history()?
+        .into_iter()
+        .filter(|record| {
+            let record_date = NaiveDate::parse_from_str(&record.date, "%Y-%m-%d").ok();
+            let after_since = since.is_none_or(|since| record_date.is_some_and(|d| d >= since));
+            let before_until = until.is_none_or(|until| record_date.is_some_and(|d| d <= until));
+            let aircraft_matches = aircraft_filter.is_none_or(|filter| {
+                all_aircraft
+                    .iter()
+                    .find(|a| a.id == record.aircraft)
+                    .is_some_and(|a| aircraft_matches(a, filter))
+            });
+            let airport_matches = airport_filter.is_none_or(|filter| {
+                record.departure_icao.eq_ignore_ascii_case(filter)
+                    || record.arrival_icao.eq_ignore_ascii_case(filter)
+            });
+
+            after_since && before_until && aircraft_matches && airport_matches
+        })
+        .collect();
+    records.sort_by(|a, b| b.date.cmp(&a.date).then(b.id.cmp(&a.id)));
+
+    if has_flag(args, "--json") {
+        println!("{}", history_json(&records, &all_aircraft));
+    } else if records.is_empty() {
+        if !quiet {
+            println!("No history found");
+        }
+    } else {
+        print_history_table(&records, &all_aircraft);
+    }
+
+    Ok(())
+}
+
+/// Adds a history entry, prompting for any value not given as a flag unless quiet.
+fn history_add<T: HistoryOperations + AirportOperations>(
+    database_connections: &mut T,
+    args: &[String],
+    quiet: bool,
+) -> Result<(), CliError> {
+    let departure_icao = flag_or_prompt(args, "--from", "Departure ICAO or IATA code", quiet)?;
+    let arrival_icao = flag_or_prompt(args, "--to", "Arrival ICAO or IATA code", quiet)?;
+    let aircraft_query = flag_or_prompt(args, "--aircraft", "Aircraft (ICAO code or ID)", quiet)?;
+    let date = match flag_value(args, "--date") {
+        Some(date) => parse_date(date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+
+    let departure = find_airport(database_connections, &departure_icao)?;
+    let arrival = find_airport(database_connections, &arrival_icao)?;
+    // A diversion is recorded with the actual arrival and the planned one.
+    let planned_arrival = flag_value(args, "--planned-arrival")
+        .map(|code| find_airport(database_connections, code))
+        .transpose()?
+        .filter(|planned| planned.ICAO != arrival.ICAO);
+
+    let all_aircraft = database_connections.get_all_aircraft()?;
+    let candidates: Vec<&Aircraft> = all_aircraft
+        .iter()
+        .filter(|a| aircraft_matches(a, &aircraft_query))
+        .collect();
+    let aircraft = match candidates.as_slice() {
+        [aircraft] => *aircraft,
+        [] => {
+            return Err(CliError::NotFound(format!(
+                "No aircraft matches {}",
+                aircraft_query
+            )))
+        }
+        _ => {
+            let ids: Vec<String> = candidates.iter().map(|a| a.id.to_string()).collect();
+            return Err(CliError::Usage(format!(
+                "{} matches several aircraft, use one of the IDs {}",
+                aircraft_query,
+                ids.join(", ")
+            )));
+        }
+    };
+
+    let date_string = date.format("%Y-%m-%d").to_string();
+    // Guards against adding the same flight twice, for example by running a script again.
+    if !has_flag(args, "--allow-duplicate")
+        && database_connections
+            .find_history_entry(&departure, &arrival, aircraft, &date_string)?
+            .is_some()
+    {
+        let message = format!(
+            "{} - {} with the {} {} on {} is already in the history",
+            departure.ICAO, arrival.ICAO, aircraft.manufacturer, aircraft.variant, date_string
+        );
+        if quiet {
+            return Err(CliError::Usage(format!(
+                "{}, pass --allow-duplicate to add it anyway",
+                message
+            )));
+        }
+        if !confirm(&format!("{}. Add anyway?", message))? {
+            return Ok(());
+        }
+    }
+
+    database_connections.add_to_history_on(
+        &departure,
+        &arrival,
+        aircraft,
+        &date_string,
+        FlightDetails {
+            notes: flag_value(args, "--note").filter(|note| !note.is_empty()),
+            planned_arrival: planned_arrival.as_ref(),
+            airline: flag_value(args, "--airline").filter(|airline| !airline.is_empty()),
+        },
+    )?;
+    if !quiet {
+        println!(
+            "Added {} - {} with the {} {} on {}",
+            departure.ICAO,
+            arrival.ICAO,
+            aircraft.manufacturer,
+            aircraft.variant,
+            date.format("%Y-%m-%d")
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints history entries as an aligned table.
+///
+/// # Arguments
+///
+/// * `records` - The history entries to print.
+/// * `all_aircraft` - All aircraft, to show the aircraft names.
+pub fn print_history_table(records: &[History], all_aircraft: &[Aircraft]) {
+    let rows: Vec<[String; 6]> = records
+        .iter()
+        .map(|record| {
+            [
+                record.date.clone(),
+                record.departure_icao.clone(),
+                arrival_label(record),
+                aircraft_name(all_aircraft, record.aircraft),
+                record.airline.clone().unwrap_or_default(),
+                record.notes.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    print_table(
+        [
+            "Date",
+            "Departure",
+            "Arrival",
+            "Aircraft",
+            "Airline",
+            "Notes",
+        ],
+        &rows,
+    );
+}
+
+/// Prints rows as a table with aligned columns, below a header.
+///
+/// # Arguments
+///
+/// * `headers` - The titles of the columns.
+/// * `rows` - The cells of each row.
+fn print_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; N]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", format_row(headers));
+    println!("{}", widths.map(|width| "-".repeat(width)).join("  "));
+    for row in rows {
+        println!("{}", format_row(row.each_ref().map(String::as_str)));
+    }
+}
+
+/// Returns the arrival of a history entry, noting the planned arrival of a diversion.
+///
+/// # Arguments
+///
+/// * `record` - The history entry.
+pub fn arrival_label(record: &History) -> String {
+    match &record.planned_arrival_icao {
+        Some(planned) => format!("{} (diverted, planned {})", record.arrival_icao, planned),
+        None => record.arrival_icao.clone(),
+    }
+}
+
+/// Formats history entries as a JSON array.
+fn history_json(records: &[History], all_aircraft: &[Aircraft]) -> String {
+    let entries: Vec<String> = records
+        .iter()
+        .map(|record| {
+            format!(
+                "  {{\"id\": {}, \"date\": {}, \"departure\": {}, \"arrival\": {}, \
+                 \"planned_arrival\": {}, \"aircraft_id\": {}, \"aircraft\": {}, \"airline\": {}, \
+                 \"notes\": {}}}",
+                record.id,
+                json_string(&record.date),
+                json_string(&record.departure_icao),
+                json_string(&record.arrival_icao),
+                record
+                    .planned_arrival_icao
+                    .as_deref()
+                    .map_or_else(|| "null".to_string(), json_string),
+                record.aircraft,
+                json_string(&aircraft_name(all_aircraft, record.aircraft)),
+                record
+                    .airline
+                    .as_deref()
+                    .map_or_else(|| "null".to_string(), json_string),
+                record
+                    .notes
+                    .as_deref()
+                    .map_or_else(|| "null".to_string(), json_string),
+            )
+        })
+        .collect();
+
+    if entries.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n]", entries.join(",\n"))
+    }
+}
+
+fn aircraft_json(all_aircraft: &[Aircraft]) -> String {
+    let entries: Vec<String> = all_aircraft
+        .iter()
+        .map(|aircraft| {
+            format!(
+                "  {{\"id\": {}, \"icao\": {}, \"manufacturer\": {}, \"variant\": {}, \
+                 \"category\": {}, \"range_nm\": {}, \"cruise_speed_kt\": {}, \"flown\": {}, \
+                 \"date_flown\": {}}}",
+                aircraft.id,
+                json_string(&aircraft.icao_code),
+                json_string(&aircraft.manufacturer),
+                json_string(&aircraft.variant),
+                json_string(&aircraft.category),
+                aircraft.aircraft_range,
+                aircraft.cruise_speed,
+                aircraft.flown,
+                aircraft
+                    .date_flown
+                    .as_deref()
+                    .map_or_else(|| "null".to_string(), json_string),
+            )
+        })
+        .collect();
+
+    if entries.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n]", entries.join(",\n"))
+    }
+}
+
+fn aircraft_name(all_aircraft: &[Aircraft], aircraft_id: i32) -> String {
+    all_aircraft
+        .iter()
+        .find(|a| a.id == aircraft_id)
+        .map_or_else(
+            || format!("Unknown aircraft ({})", aircraft_id),
+            |a| format!("{} {} ({})", a.manufacturer, a.variant, a.icao_code),
+        )
+}
+
+/// Returns whether an aircraft has the ID or ICAO type code given on the command line.
+pub fn aircraft_matches(aircraft: &Aircraft, query: &str) -> bool {
+    aircraft.id.to_string() == query || aircraft.icao_code.eq_ignore_ascii_case(query)
+}
+
+fn find_airport<T: AirportOperations>(
+    database_connections: &mut T,
+    icao: &str,
+) -> Result<crate::models::Airport, CliError> {
+    match airport_by_code(database_connections, icao) {
+        Ok(airport) => Ok(airport),
+        Err(diesel::result::Error::NotFound) => {
+            Err(CliError::NotFound(format!("Unknown airport {}", icao)))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, ValidationError> {
+    validation::parse_date(value).map_err(|_| {
+        ValidationError::InvalidData(format!("Invalid date {}, expected YYYY-MM-DD", value))
+    })
+}
+
+/// Returns the value following a flag, for example `EHAM` for `--airport EHAM`.
+pub fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|position| args.get(position + 1))
+        .map(String::as_str)
+}
+
+pub fn has_flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|arg| arg == name)
+}
+
+/// Rejects unknown flags and value flags that are missing their value.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to check.
+/// * `value_flags` - Flags followed by a value.
+/// * `switches` - Flags without a value.
+pub fn check_flags(
+    args: &[String],
+    value_flags: &[&str],
+    switches: &[&str],
+) -> Result<(), ValidationError> {
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        if value_flags.contains(&arg.as_str()) {
+            if remaining.next().is_none() {
+                return Err(ValidationError::InvalidData(format!(
+                    "{} needs a value",
+                    arg
+                )));
+            }
+        } else if !switches.contains(&arg.as_str()) {
+            return Err(ValidationError::InvalidData(format!(
+                "Unexpected argument {}",
+                arg
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Asks a yes or no question on the terminal, defaulting to no.
+///
+/// # Arguments
+///
+/// * `question` - The question, without the answer hint.
+fn confirm(question: &str) -> Result<bool, CliError> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
+
+/// Returns the value of a flag, asking for it on the terminal if it was not given.
+fn flag_or_prompt(
+    args: &[String],
+    name: &str,
+    label: &str,
+    quiet: bool,
+) -> Result<String, CliError> {
+    if let Some(value) = flag_value(args, name) {
+        return Ok(value.to_string());
+    }
+    if quiet {
+        return Err(CliError::Usage(format!(
+            "{} is required with --quiet",
+            name
+        )));
+    }
+
+    print!("{}: ", label);
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Runs `sync`, which mirrors the aircraft database and the settings with a
+/// sync folder shared between computers.
+///
+/// # Arguments
+///
+/// * `args` - The arguments after `sync`.
+/// * `quiet` - Whether to leave out messages.
+pub fn sync_command(args: &[String], quiet: bool) -> Result<(), CliError> {
+    let (folder, keep) = match args {
+        [folder] => (folder, None),
+        [folder, flag] if flag == "--keep-local" => (folder, Some(KeepSide::Local)),
+        [folder, flag] if flag == "--keep-remote" => (folder, Some(KeepSide::Remote)),
+        _ => return Err(CliError::Usage(SYNC_USAGE.to_string())),
+    };
+
+    let files = [crate::AIRCRAFT_DB_FILENAME, crate::SETTINGS_FILENAME];
+    let outcomes = sync_files(
+        std::path::Path::new(folder),
+        &files,
+        std::path::Path::new(crate::SYNC_STATE_FILENAME),
+        keep,
+    )?;
+
+    let mut conflicts = Vec::new();
+    for (name, outcome) in files.iter().zip(&outcomes) {
+        if *outcome == SyncOutcome::Conflict {
+            conflicts.push(*name);
+        } else if !quiet {
+            println!("{}: {}", name, outcome.description());
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Conflict(format!(
+            "{} changed both here and in {} since the last sync; \
+             run sync again with --keep-local or --keep-remote",
+            conflicts.join(" and "),
+            folder
+        )))
+    }
+}
@@ -0,0 +1,386 @@
+use diesel::prelude::*;
+use std::path::Path;
+
+use crate::cli::{
+    AIRAC_SUBCOMMANDS, AIRCRAFT_SUBCOMMANDS, AIRPORT_SUBCOMMANDS, ALIAS_SUBCOMMANDS,
+    FREQUENCIES_SUBCOMMANDS, HISTORY_ADD_FLAGS, HISTORY_ADD_SWITCHES, HISTORY_LIST_SWITCHES,
+    HISTORY_LIST_VALUE_FLAGS, ROUTE_SUBCOMMANDS, SOURCES_SUBCOMMANDS,
+};
+use crate::errors::ValidationError;
+
+const COMMAND: &str = "flight-planner";
+/// Options accepted as the first argument.
+const TOP_LEVEL: [&str; 15] = [
+    "--gui",
+    "--bench-routes",
+    "--export",
+    "--trim-airports",
+    "--merge-airports",
+    "route",
+    "aircraft",
+    "airport",
+    "history",
+    "alias",
+    "sources",
+    "frequencies",
+    "airac",
+    "sync",
+    "completions",
+];
+const HISTORY_SUBCOMMANDS: [&str; 5] = ["list", "add", "recount", "merge", "export"];
+const SHELLS: [&str; 4] = ["bash", "zsh", "fish", "powershell"];
+
+/// Returns a completion script for the shell.
+///
+/// The aircraft ICAO codes in the aircraft database are written into the
+/// script, so it should be regenerated after adding aircraft.
+///
+/// # Arguments
+///
+/// * `shell` - One of `bash`, `zsh`, `fish` or `powershell`.
+/// * `aircraft_db` - The aircraft database to read the ICAO codes from.
+pub fn completion_script(shell: &str, aircraft_db: &Path) -> Result<String, ValidationError> {
+    let codes = aircraft_codes(aircraft_db);
+    let history_list_flags: Vec<&str> = HISTORY_LIST_VALUE_FLAGS
+        .iter()
+        .chain(&HISTORY_LIST_SWITCHES)
+        .copied()
+        .collect();
+    let history_add_flags: Vec<&str> = HISTORY_ADD_FLAGS
+        .iter()
+        .chain(&HISTORY_ADD_SWITCHES)
+        .copied()
+        .collect();
+
+    let script = match shell {
+        "bash" => bash_script(&codes, &history_list_flags, &history_add_flags),
+        "zsh" => zsh_script(&codes, &history_list_flags, &history_add_flags),
+        "fish" => fish_script(&codes),
+        "powershell" => powershell_script(&codes, &history_list_flags, &history_add_flags),
+        _ => {
+            return Err(ValidationError::InvalidData(format!(
+                "Unknown shell {}, expected one of {}",
+                shell,
+                SHELLS.join(", ")
+            )))
+        }
+    };
+
+    Ok(script)
+}
+
+/// Reads the distinct aircraft ICAO codes, or nothing if the database cannot be read.
+fn aircraft_codes(aircraft_db: &Path) -> Vec<String> {
+    use crate::schema::aircraft::dsl::*;
+
+    if !aircraft_db.exists() {
+        return Vec::new();
+    }
+
+    SqliteConnection::establish(&aircraft_db.to_string_lossy())
+        .ok()
+        .and_then(|mut conn| {
+            aircraft
+                .select(icao_code)
+                .distinct()
+                .order(icao_code)
+                .load::<String>(&mut conn)
+                .ok()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        // Codes end up unquoted in the scripts.
+        .filter(|code| !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric()))
+        .collect()
+}
+
+fn bash_script(
+    codes: &[String],
+    history_list_flags: &[&str],
+    history_add_flags: &[&str],
+) -> String {
+    format!(
+        r#"_flight_planner() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    local opts=""
+
+    case "$prev" in
+        --aircraft) COMPREPLY=($(compgen -W "{codes}" -- "$cur")); return ;;
+        completions) COMPREPLY=($(compgen -W "{shells}" -- "$cur")); return ;;
+        route) COMPREPLY=($(compgen -W "{route}" -- "$cur")); return ;;
+        aircraft) COMPREPLY=($(compgen -W "{aircraft}" -- "$cur")); return ;;
+        airport) COMPREPLY=($(compgen -W "{airport}" -- "$cur")); return ;;
+        history) COMPREPLY=($(compgen -W "{history}" -- "$cur")); return ;;
+        alias) COMPREPLY=($(compgen -W "{alias}" -- "$cur")); return ;;
+        sources) COMPREPLY=($(compgen -W "{sources}" -- "$cur")); return ;;
+        frequencies) COMPREPLY=($(compgen -W "{frequencies}" -- "$cur")); return ;;
+        airac) COMPREPLY=($(compgen -W "{airac}" -- "$cur")); return ;;
+    esac
+
+    if [[ "${{COMP_WORDS[1]}}" == history ]]; then
+        case "${{COMP_WORDS[2]}}" in
+            list) opts="{list}" ;;
+            add) opts="{add}" ;;
+        esac
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        opts="{top}"
+    fi
+
+    COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+}}
+complete -F _flight_planner {command}
+"#,
+        codes = codes.join(" "),
+        shells = SHELLS.join(" "),
+        route = ROUTE_SUBCOMMANDS.join(" "),
+        aircraft = AIRCRAFT_SUBCOMMANDS.join(" "),
+        airport = AIRPORT_SUBCOMMANDS.join(" "),
+        history = HISTORY_SUBCOMMANDS.join(" "),
+        alias = ALIAS_SUBCOMMANDS.join(" "),
+        sources = SOURCES_SUBCOMMANDS.join(" "),
+        frequencies = FREQUENCIES_SUBCOMMANDS.join(" "),
+        airac = AIRAC_SUBCOMMANDS.join(" "),
+        list = history_list_flags.join(" "),
+        add = history_add_flags.join(" "),
+        top = TOP_LEVEL.join(" "),
+        command = COMMAND,
+    )
+}
+
+fn zsh_script(codes: &[String], history_list_flags: &[&str], history_add_flags: &[&str]) -> String {
+    format!(
+        r#"#compdef {command}
+
+_flight_planner() {{
+    local -a opts
+
+    case "$words[CURRENT-1]" in
+        --aircraft) compadd -- {codes}; return ;;
+        completions) compadd -- {shells}; return ;;
+        route) compadd -- {route}; return ;;
+        aircraft) compadd -- {aircraft}; return ;;
+        airport) compadd -- {airport}; return ;;
+        history) compadd -- {history}; return ;;
+        alias) compadd -- {alias}; return ;;
+        sources) compadd -- {sources}; return ;;
+        frequencies) compadd -- {frequencies}; return ;;
+        airac) compadd -- {airac}; return ;;
+    esac
+
+    if [[ "$words[2]" == history ]]; then
+        case "$words[3]" in
+            list) opts=({list}) ;;
+            add) opts=({add}) ;;
+        esac
+    elif (( CURRENT == 2 )); then
+        opts=({top})
+    fi
+
+    compadd -- $opts
+}}
+
+compdef _flight_planner {command}
+"#,
+        codes = codes.join(" "),
+        shells = SHELLS.join(" "),
+        route = ROUTE_SUBCOMMANDS.join(" "),
+        aircraft = AIRCRAFT_SUBCOMMANDS.join(" "),
+        airport = AIRPORT_SUBCOMMANDS.join(" "),
+        history = HISTORY_SUBCOMMANDS.join(" "),
+        alias = ALIAS_SUBCOMMANDS.join(" "),
+        sources = SOURCES_SUBCOMMANDS.join(" "),
+        frequencies = FREQUENCIES_SUBCOMMANDS.join(" "),
+        airac = AIRAC_SUBCOMMANDS.join(" "),
+        list = history_list_flags.join(" "),
+        add = history_add_flags.join(" "),
+        top = TOP_LEVEL.join(" "),
+        command = COMMAND,
+    )
+}
+
+fn fish_script(codes: &[String]) -> String {
+    let long = |flags: &[&str]| {
+        flags
+            .iter()
+            .map(|flag| format!("-l {}", flag.trim_start_matches("--")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let top_flags: Vec<&str> = TOP_LEVEL
+        .iter()
+        .filter(|option| option.starts_with("--"))
+        .copied()
+        .collect();
+    let top_commands: Vec<&str> = TOP_LEVEL
+        .iter()
+        .filter(|option| !option.starts_with("--"))
+        .copied()
+        .collect();
+    let list_value_flags: Vec<&str> = HISTORY_LIST_VALUE_FLAGS
+        .iter()
+        .filter(|flag| **flag != "--aircraft")
+        .copied()
+        .collect();
+    let add_flags: Vec<&str> = HISTORY_ADD_FLAGS
+        .iter()
+        .filter(|flag| **flag != "--aircraft")
+        .copied()
+        .collect();
+
+    [
+        format!("complete -c {} -f", COMMAND),
+        format!(
+            "complete -c {} -n __fish_use_subcommand {}",
+            COMMAND,
+            long(&top_flags)
+        ),
+        format!(
+            "complete -c {} -n __fish_use_subcommand -a \"{}\"",
+            COMMAND,
+            top_commands.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from route; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            ROUTE_SUBCOMMANDS.join(" "),
+            ROUTE_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from aircraft; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            AIRCRAFT_SUBCOMMANDS.join(" "),
+            AIRCRAFT_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from airport; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            AIRPORT_SUBCOMMANDS.join(" "),
+            AIRPORT_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from history; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            HISTORY_SUBCOMMANDS.join(" "),
+            HISTORY_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from alias; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            ALIAS_SUBCOMMANDS.join(" "),
+            ALIAS_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from sources; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            SOURCES_SUBCOMMANDS.join(" "),
+            SOURCES_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from frequencies; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            FREQUENCIES_SUBCOMMANDS.join(" "),
+            FREQUENCIES_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from airac; and not __fish_seen_subcommand_from {}\" -a \"{}\"",
+            COMMAND,
+            AIRAC_SUBCOMMANDS.join(" "),
+            AIRAC_SUBCOMMANDS.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from list\" -x {}",
+            COMMAND,
+            long(&list_value_flags)
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from list\" {}",
+            COMMAND,
+            long(&HISTORY_LIST_SWITCHES)
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from add\" -x {}",
+            COMMAND,
+            long(&add_flags)
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from add\" {}",
+            COMMAND,
+            long(&HISTORY_ADD_SWITCHES)
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from list add\" -l aircraft -xa \"{}\"",
+            COMMAND,
+            codes.join(" ")
+        ),
+        format!(
+            "complete -c {} -n \"__fish_seen_subcommand_from completions\" -a \"{}\"",
+            COMMAND,
+            SHELLS.join(" ")
+        ),
+    ]
+    .join("\n")
+        + "\n"
+}
+
+fn powershell_script(
+    codes: &[String],
+    history_list_flags: &[&str],
+    history_add_flags: &[&str],
+) -> String {
+    let list = |items: &[&str]| {
+        items
+            .iter()
+            .map(|item| format!("'{}'", item))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let codes: Vec<&str> = codes.iter().map(String::as_str).collect();
+
+    format!(
+        r#"Register-ArgumentCompleter -Native -CommandName {command} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | ForEach-Object {{ $_.ToString() }})
+    $previous = if ($wordToComplete) {{ $words[-2] }} else {{ $words[-1] }}
+
+    $candidates = switch ($previous) {{
+        '--aircraft' {{ @({codes}) }}
+        'completions' {{ @({shells}) }}
+        'route' {{ @({route}) }}
+        'aircraft' {{ @({aircraft}) }}
+        'airport' {{ @({airport}) }}
+        'history' {{ @({history}) }}
+        'alias' {{ @({alias}) }}
+        'sources' {{ @({sources}) }}
+        'frequencies' {{ @({frequencies}) }}
+        'airac' {{ @({airac}) }}
+        default {{
+            if ($words.Count -gt 2 -and $words[1] -eq 'history' -and $words[2] -eq 'list') {{ @({list}) }}
+            elseif ($words.Count -gt 2 -and $words[1] -eq 'history' -and $words[2] -eq 'add') {{ @({add}) }}
+            elseif ($words.Count -le 2) {{ @({top}) }}
+            else {{ @() }}
+        }}
+    }}
+
+    $candidates | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+"#,
+        command = COMMAND,
+        codes = list(&codes),
+        shells = list(&SHELLS),
+        route = list(&ROUTE_SUBCOMMANDS),
+        aircraft = list(&AIRCRAFT_SUBCOMMANDS),
+        airport = list(&AIRPORT_SUBCOMMANDS),
+        history = list(&HISTORY_SUBCOMMANDS),
+        alias = list(&ALIAS_SUBCOMMANDS),
+        sources = list(&SOURCES_SUBCOMMANDS),
+        frequencies = list(&FREQUENCIES_SUBCOMMANDS),
+        airac = list(&AIRAC_SUBCOMMANDS),
+        list = list(history_list_flags),
+        add = list(history_add_flags),
+        top = list(&TOP_LEVEL),
+    )
+}
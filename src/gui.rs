@@ -1,25 +1,93 @@
-use crate::models::History;
+mod achievements;
+mod actions;
+mod airport_compare;
+mod airport_details;
+mod airport_list;
+mod command_palette;
+mod compass;
+mod custom_route;
+mod diagnostics;
+mod enroute_alternates;
+mod export;
+mod goals;
+mod history_export;
+mod logbook_import;
+mod map_view;
+mod mark_flown;
+mod presets;
+mod random_airport;
+mod region_picker;
+mod report;
+mod route_aircraft;
+mod route_destination;
+mod searchable_dropdown;
+mod session;
+mod settings;
+mod sim_mapping;
+mod snapshot;
+mod table_sort;
+
+use crate::cli::arrival_label;
+use crate::models::{DataSource, FlightDetails, History, Runway};
+use crate::modules::airac::{stale_cycle_warning, AiracCycle};
+use crate::modules::airport::AirportCodes;
+use crate::modules::callsign::route_callsign;
+use crate::modules::long_haul::LongHaulMode;
+use crate::modules::region::Region;
+use crate::modules::routes::{
+    format_altitude, format_duration_hours, AircraftLoad, CompassSector, Route, RouteConstraints,
+    RouteGenerator,
+};
+use crate::modules::runway::can_use_runway;
+use crate::modules::seasons::{presets_in_season, SeasonalPreset};
+use crate::modules::simbrief::dispatch_url;
+use crate::modules::validation::FieldErrors;
 use crate::traits::*;
 use crate::{
-    get_destination_airport_with_suitable_runway_fast,
-    models::{Aircraft, Airport, Runway},
-    DatabasePool,
+    instance,
+    models::{Aircraft, Airport},
+    DatabasePool, SETTINGS_FILENAME, SNAPSHOT_FILENAME,
 };
+use achievements::AchievementsState;
+use actions::Action;
+use airport_compare::AirportCompareState;
+use airport_details::AirportDetailsState;
+use airport_list::AirportList;
+use chrono::{DateTime, NaiveDate, Utc};
+use command_palette::CommandPaletteState;
+use custom_route::CustomRouteState;
+use diesel::result::Error;
 use eframe::egui::{self, TextEdit};
 use egui::Id;
 use egui_extras::{Column, TableBuilder};
-use geo::{Distance, Haversine};
-use rand::prelude::SliceRandom;
-use rayon::prelude::*;
-use rstar::{RTree, RTreeObject, AABB};
+use enroute_alternates::EnrouteAlternatesState;
+use export::ExportState;
+use goals::GoalsState;
+use history_export::HistoryExportState;
+use logbook_import::LogbookImportState;
+use map_view::MapViewState;
+use mark_flown::MarkFlownForm;
+use presets::PresetsState;
+use random_airport::RandomAirportState;
+use report::ReportState;
+use route_aircraft::RouteAircraftState;
+use route_destination::RouteDestinationState;
+use serde::{Deserialize, Serialize};
+pub use session::Session;
+use settings::{data_age_warning, Settings};
+use sim_mapping::SimMappingState;
+use snapshot::{RouteSnapshot, Snapshot};
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
 use std::sync::Arc;
-use std::time::Instant;
+use table_sort::{sort_items, TableSort};
 
 const GENERATE_AMOUNT: usize = 50;
-const M_TO_NM: f64 = 0.00053995680345572;
-const M_TO_FT: f64 = 3.28084;
+/// The most routes kept in the snapshot, which grows as more routes are loaded.
+const MAX_SNAPSHOT_ROUTES: usize = 500;
 
 /// An enum representing the items that can be displayed in the table.
 enum TableItem {
@@ -33,27 +101,12 @@ enum TableItem {
     History(Arc<History>),
 }
 
-/// A structure representing a flight route.
-#[derive(Clone)]
-struct Route {
-    /// The departure airport.
-    departure: Arc<Airport>,
-    /// The destination airport.
-    destination: Arc<Airport>,
-    /// The aircraft used for the route.
-    aircraft: Arc<Aircraft>,
-    /// The departure runways.
-    departure_runway: Arc<Vec<Runway>>,
-    /// The destination runways.
-    destination_runway: Arc<Vec<Runway>>,
-}
-
 impl TableItem {
     /// Returns the column headers for the table item.
     fn get_columns(&self) -> Vec<&'static str> {
         match self {
-            TableItem::Airport(_) => vec!["ID", "Name", "ICAO"],
-            TableItem::Aircraft(_) => vec!["ID", "Model", "Registration", "Flown"],
+            TableItem::Airport(_) => vec!["ID", "Name", "ICAO", "IATA", "Longest runway"],
+            TableItem::Aircraft(_) => vec!["ID", "Model", "Registration", "Flights"],
             TableItem::Route(_) => vec![
                 "Departure",
                 "ICAO",
@@ -64,18 +117,57 @@ impl TableItem {
                 "Manufacturer",
                 "Aircraft",
                 "Distance",
+                "Block time",
+                "At arrival",
             ],
-            TableItem::History(_) => vec!["ID", "Departure", "Arrival", "Aircraft", "Date"],
+            TableItem::History(_) => {
+                vec![
+                    "ID",
+                    "Departure",
+                    "Arrival",
+                    "Aircraft",
+                    "Date",
+                    "Airline",
+                    "Notes",
+                ]
+            }
         }
     }
 
     /// Returns the data for the table item.
-    fn get_data(&self, db: &mut impl AircraftOperations) -> Vec<Cow<'_, str>> {
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database to look up aircraft names in.
+    /// * `airport_codes` - The IATA codes and former ICAO codes of airports.
+    /// * `block_overhead_minutes` - The taxi and climb time added to the block time of routes.
+    /// * `longest_runways` - The longest land runway of every airport, in feet.
+    /// * `now` - The time routes leave the gate, for day or night at their arrival.
+    fn get_data(
+        &self,
+        db: &mut impl AircraftOperations,
+        airport_codes: &AirportCodes,
+        block_overhead_minutes: u32,
+        longest_runways: Option<&HashMap<i32, i32>>,
+        now: DateTime<Utc>,
+    ) -> Vec<Cow<'_, str>> {
         match self {
             TableItem::Airport(airport) => vec![
                 Cow::Owned(airport.ID.to_string()),
                 Cow::Borrowed(&airport.Name),
                 Cow::Borrowed(&airport.ICAO),
+                Cow::Owned(
+                    airport_codes
+                        .iata(&airport.ICAO)
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+                Cow::Owned(
+                    longest_runways
+                        .and_then(|runways| runways.get(&airport.ID))
+                        .map(|length| length.to_string())
+                        .unwrap_or_default(),
+                ),
             ],
             TableItem::Aircraft(aircraft) => vec![
                 Cow::Owned(aircraft.id.to_string()),
@@ -84,24 +176,30 @@ impl TableItem {
                 Cow::Owned(aircraft.flown.to_string()),
             ],
             TableItem::Route(route) => {
-                let max_departure_runway = route
-                    .departure_runway
-                    .iter()
-                    .max_by(|a, b| a.Length.cmp(&b.Length))
-                    .map(|r| r.Length.to_string())
+                // Only the runways the aircraft can use, leaving out helipads
+                // and water runways for most aircraft.
+                let longest_usable = |runways: &[Runway]| {
+                    runways
+                        .iter()
+                        .filter(|runway| can_use_runway(&route.aircraft, runway))
+                        .map(|runway| runway.Length)
+                        .max()
+                        .map(|length| length.to_string())
+                        .unwrap_or_default()
+                };
+                let max_departure_runway = longest_usable(&route.departure_runway);
+                let max_destination_runway = longest_usable(&route.destination_runway);
+
+                let distance = route.distance_nm();
+                let block_time = route
+                    .block_time_hours(block_overhead_minutes)
+                    .map(format_duration_hours)
                     .unwrap_or_default();
-
-                let max_destination_runway = route
-                    .destination_runway
-                    .iter()
-                    .max_by(|a, b| a.Length.cmp(&b.Length))
-                    .map(|r| r.Length.to_string())
-                    .unwrap_or_default();
-
-                let point1 = geo::Point::new(route.departure.Latitude, route.departure.Longtitude);
-                let point2 =
-                    geo::Point::new(route.destination.Latitude, route.destination.Longtitude);
-                let distance = (Haversine::distance(point1, point2) * M_TO_NM).round();
+                let at_arrival = match route.arrives_in_daylight(now, block_overhead_minutes) {
+                    Some(true) => "Day",
+                    Some(false) => "Night",
+                    None => "",
+                };
 
                 vec![
                     Cow::Borrowed(&route.departure.Name),
@@ -113,6 +211,8 @@ impl TableItem {
                     Cow::Borrowed(&route.aircraft.manufacturer),
                     Cow::Borrowed(&route.aircraft.variant),
                     Cow::Owned(distance.to_string()),
+                    Cow::Owned(block_time),
+                    Cow::Borrowed(at_arrival),
                 ]
             }
             TableItem::History(history) => {
@@ -123,9 +223,11 @@ impl TableItem {
                 vec![
                     Cow::Owned(history.id.to_string()),
                     Cow::Borrowed(&history.departure_icao),
-                    Cow::Borrowed(&history.arrival_icao),
+                    Cow::Owned(arrival_label(history)),
                     Cow::Owned(aircraft_str),
                     Cow::Borrowed(&history.date),
+                    Cow::Borrowed(history.airline.as_deref().unwrap_or_default()),
+                    Cow::Borrowed(history.notes.as_deref().unwrap_or_default()),
                 ]
             }
         }
@@ -136,12 +238,14 @@ impl TableItem {
     /// # Arguments
     ///
     /// * `query` - The search query string.
-    fn matches_query(&self, query: &str) -> bool {
+    /// * `airport_codes` - The IATA codes and former ICAO codes of airports.
+    fn matches_query(&self, query: &str, airport_codes: &AirportCodes) -> bool {
         let query = query.to_lowercase();
         match self {
             TableItem::Airport(airport) => {
                 airport.Name.to_lowercase().contains(&query)
                     || airport.ICAO.to_lowercase().contains(&query)
+                    || airport_codes.matches(airport, &query)
                     || airport.ID.to_string().contains(&query)
             }
             TableItem::Aircraft(aircraft) => {
@@ -154,6 +258,8 @@ impl TableItem {
                     || route.departure.ICAO.to_lowercase().contains(&query)
                     || route.destination.Name.to_lowercase().contains(&query)
                     || route.destination.ICAO.to_lowercase().contains(&query)
+                    || airport_codes.matches(&route.departure, &query)
+                    || airport_codes.matches(&route.destination, &query)
                     || route.aircraft.manufacturer.to_lowercase().contains(&query)
                     || route.aircraft.variant.to_lowercase().contains(&query)
             }
@@ -166,6 +272,8 @@ impl TableItem {
 struct SearchState {
     /// The current search query.
     query: String,
+    /// The column the items are sorted by, the order they were listed in when `None`.
+    sort: Option<TableSort>,
     /// The items filtered based on the search query.
     filtered_items: Vec<Arc<TableItem>>,
 }
@@ -174,20 +282,113 @@ struct SearchState {
 pub struct Gui<'a> {
     /// The database pool for accessing data.
     database_pool: &'a mut DatabasePool,
+    /// The egui context, repainted when background work finishes.
+    egui_ctx: egui::Context,
     /// The items currently displayed in the GUI.
     displayed_items: Vec<Arc<TableItem>>,
+    /// Every airport, while the table lists them a page at a time.
+    airport_list: Option<AirportList>,
     /// All available aircraft.
     all_aircraft: Vec<Arc<Aircraft>>,
-    /// All available airports.
-    all_airports: Vec<Arc<Airport>>,
-    /// A map of all runways.
-    all_runways: HashMap<i32, Arc<Vec<Runway>>>,
+    /// The route generator holding airports, runways and the spatial index,
+    /// available once the background load has finished.
+    route_generator: Option<RouteGenerator>,
+    /// Receives the live data from the background loading thread, while it runs.
+    loading_receiver: Option<Receiver<Result<LoadedData, Error>>>,
     /// State for handling popups.
     popup_state: PopupState,
     /// State for handling search.
     search_state: SearchState,
-    /// Spatial index of airports for efficient queries.
-    spatial_airports: RTree<SpatialAirport>,
+    /// Whether to show the memory diagnostics window.
+    show_diagnostics: bool,
+    /// State of the custom route dialog.
+    custom_route_state: CustomRouteState,
+    /// State of the simulator aircraft mapping window.
+    sim_mapping_state: SimMappingState,
+    /// The user preferences.
+    settings: Settings,
+    /// Whether the settings window is shown.
+    show_settings: bool,
+    /// State of the fleet goal window.
+    goals_state: GoalsState,
+    /// State of the achievements window and the unlock toasts.
+    achievements_state: AchievementsState,
+    /// State of the airport details window.
+    airport_details_state: AirportDetailsState,
+    /// State of the airport comparison window.
+    airport_compare_state: AirportCompareState,
+    /// State of the report window.
+    report_state: ReportState,
+    /// State of the route export window.
+    export_state: ExportState,
+    /// State of the history export window.
+    history_export_state: HistoryExportState,
+    /// State of the logbook import window.
+    logbook_import_state: LogbookImportState,
+    /// State of the route map window.
+    map_view_state: MapViewState,
+    /// Receives the arguments of instances started while this one is running.
+    activation_receiver: Option<Receiver<Vec<String>>>,
+    /// State of the command palette.
+    command_palette_state: CommandPaletteState,
+    /// The aircraft picked for generating routes.
+    route_aircraft_state: RouteAircraftState,
+    /// The airport generated routes arrive at, if fixed.
+    route_destination_state: RouteDestinationState,
+    /// State of the generation presets.
+    presets_state: PresetsState,
+    /// The filter of the random airport.
+    random_airport_state: RandomAirportState,
+    /// The airports along the route shown in the popup.
+    enroute_alternates_state: EnrouteAlternatesState,
+    /// The compass sector generated destinations have to lie in.
+    route_direction: Option<CompassSector>,
+    /// The continent or country generated routes are kept in.
+    route_region: Option<Region>,
+    /// The seasonal suggestion generated destinations have to lie in.
+    seasonal_preset: Option<&'static SeasonalPreset>,
+    /// The IATA codes and former ICAO codes of airports.
+    airport_codes: AirportCodes,
+    /// The data sources recorded in the airport database.
+    data_sources: Vec<DataSource>,
+    /// The AIRAC cycle the airport database is tagged with.
+    airac_cycle: Option<AiracCycle>,
+    /// The inner size of the window, saved with the session.
+    window_size: Option<egui::Vec2>,
+    /// The destination of the last session, until the airports are loaded.
+    session_destination: Option<String>,
+    /// The batch of routes in the snapshot, kept while other items are listed.
+    saved_routes: Vec<Arc<Route>>,
+    /// Why the last action or data load failed, shown above the table.
+    action_error: Option<String>,
+}
+
+/// Data loaded from the database in the background at startup.
+struct LoadedData {
+    /// All available aircraft.
+    all_aircraft: Vec<Arc<Aircraft>>,
+    /// The route generator built from the airport database.
+    route_generator: RouteGenerator,
+    /// The IATA codes and former ICAO codes of airports.
+    airport_codes: AirportCodes,
+    /// The data sources recorded in the airport database.
+    data_sources: Vec<DataSource>,
+    /// The AIRAC cycle the airport database is tagged with.
+    airac_cycle: Option<AiracCycle>,
+}
+
+/// The aircraft the displayed routes are generated for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum RouteSource {
+    /// Any aircraft.
+    #[default]
+    All,
+    /// Aircraft that have not been flown yet.
+    NotFlown,
+    /// The aircraft selected in the side panel.
+    Selected,
+    /// Aircraft with enough range for a long haul mode.
+    LongHaul(LongHaulMode),
 }
 
 #[derive(Default)]
@@ -196,75 +397,235 @@ struct PopupState {
     show_alert: bool,
     /// The currently selected route.
     selected_route: Option<Arc<Route>>,
-    /// Whether the routes are generated from not flown aircraft list.
-    routes_from_not_flown: bool,
-}
-
-/// A spatial index object for airports.
-pub struct SpatialAirport {
-    pub airport: Arc<Airport>,
-}
-
-/// Implement the RTreeObject trait for SpatialAirport for the RTree index.
-impl RTreeObject for SpatialAirport {
-    type Envelope = AABB<[f64; 2]>;
-
-    fn envelope(&self) -> Self::Envelope {
-        let point = [self.airport.Latitude, self.airport.Longtitude];
-        AABB::from_point(point)
-    }
+    /// The aircraft the displayed routes are generated for.
+    route_source: RouteSource,
+    /// The fuel and payload used to estimate the range in the popup.
+    aircraft_load: AircraftLoad,
+    /// The confirmation form of "Mark as flown", while it is open.
+    mark_flown: Option<MarkFlownForm>,
 }
 
 impl<'a> Gui<'a> {
     /// Creates a new GUI instance.
     ///
+    /// The last session's snapshot is shown right away while the database is
+    /// loaded on a background thread.
+    ///
     /// # Arguments
     ///
-    /// * `_cc` - The creation context.
+    /// * `cc` - The creation context.
     /// * `database_pool` - A mutable reference to the database pool.
-    pub fn new(_cc: &eframe::CreationContext, database_pool: &'a mut DatabasePool) -> Self {
-        let all_aircraft = database_pool
-            .get_all_aircraft()
-            .expect("Failed to load aircraft");
-        let all_airports = database_pool
-            .get_airports()
-            .expect("Failed to load airports");
-        let runway_data = database_pool.get_runways().expect("Failed to load runways");
-
-        let mut runway_map: HashMap<i32, Vec<Runway>> = HashMap::new();
-        for runway in runway_data {
-            runway_map.entry(runway.AirportID).or_default().push(runway);
-        }
-
-        let all_runways: HashMap<i32, Arc<Vec<Runway>>> = runway_map
+    /// * `instance_listener` - Receives activation requests from later instances, if this
+    ///   instance holds the single-instance lock.
+    /// * `session` - The choices of the last session to restore.
+    pub fn new(
+        cc: &eframe::CreationContext,
+        database_pool: &'a mut DatabasePool,
+        instance_listener: Option<TcpListener>,
+        session: Session,
+    ) -> Self {
+        let settings = Settings::load(Path::new(SETTINGS_FILENAME));
+        settings.apply(&cc.egui_ctx);
+
+        let snapshot = Snapshot::load(Path::new(SNAPSHOT_FILENAME));
+        let all_aircraft: Vec<Arc<Aircraft>> =
+            snapshot.aircraft.into_iter().map(Arc::new).collect();
+        let displayed_items: Vec<Arc<TableItem>> = snapshot
+            .routes
             .into_iter()
-            .map(|(id, runways)| (id, Arc::new(runways)))
+            .map(|route| Arc::new(TableItem::Route(Arc::new(Route::from(route)))))
             .collect();
 
-        let all_aircraft: Vec<Arc<Aircraft>> = all_aircraft.into_iter().map(Arc::new).collect();
-        let all_airports: Vec<Arc<Airport>> = all_airports.into_iter().map(Arc::new).collect();
+        let activation_receiver = instance_listener.map(|listener| {
+            let (sender, receiver) = mpsc::channel();
+            let ctx = cc.egui_ctx.clone();
+            instance::listen(listener, move |args| {
+                let _ = sender.send(args);
+                ctx.request_repaint();
+            });
+            receiver
+        });
 
-        let spatial_airports = RTree::bulk_load(
-            all_airports
-                .iter()
-                .map(|airport| SpatialAirport {
-                    airport: Arc::clone(airport),
-                })
-                .collect(),
-        );
+        let mut gui = Gui {
+            displayed_items,
+            all_aircraft,
+            activation_receiver,
+            ..Gui::empty(database_pool, settings, cc.egui_ctx.clone())
+        };
+        gui.start_loading();
+        gui.saved_routes = gui.displayed_routes().cloned().collect();
+        gui.restore_session(session);
+        gui
+    }
 
+    /// Creates the state of a window on the databases with no data loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_pool` - The databases.
+    /// * `settings` - The settings.
+    /// * `egui_ctx` - The egui context, repainted when background work finishes.
+    fn empty(
+        database_pool: &'a mut DatabasePool,
+        settings: Settings,
+        egui_ctx: egui::Context,
+    ) -> Self {
         Gui {
             database_pool,
+            egui_ctx,
             displayed_items: Vec::new(),
-            all_aircraft,
-            all_airports,
-            all_runways,
+            airport_list: None,
+            all_aircraft: Vec::new(),
+            route_generator: None,
+            loading_receiver: None,
             popup_state: PopupState::default(),
             search_state: SearchState::default(),
-            spatial_airports,
+            show_diagnostics: false,
+            custom_route_state: CustomRouteState::default(),
+            sim_mapping_state: SimMappingState::default(),
+            settings,
+            show_settings: false,
+            goals_state: GoalsState::default(),
+            achievements_state: AchievementsState::default(),
+            airport_details_state: AirportDetailsState::default(),
+            airport_compare_state: AirportCompareState::default(),
+            report_state: ReportState::default(),
+            export_state: ExportState::default(),
+            history_export_state: HistoryExportState::default(),
+            logbook_import_state: LogbookImportState::default(),
+            map_view_state: MapViewState::default(),
+            activation_receiver: None,
+            command_palette_state: CommandPaletteState::default(),
+            route_aircraft_state: RouteAircraftState::default(),
+            route_destination_state: RouteDestinationState::default(),
+            presets_state: PresetsState::default(),
+            random_airport_state: RandomAirportState::default(),
+            enroute_alternates_state: EnrouteAlternatesState::default(),
+            route_direction: None,
+            route_region: None,
+            seasonal_preset: None,
+            airport_codes: AirportCodes::default(),
+            data_sources: Vec::new(),
+            airac_cycle: None,
+            window_size: None,
+            session_destination: None,
+            saved_routes: Vec::new(),
+            action_error: None,
+        }
+    }
+
+    /// Loads the aircraft, airports and runways in the background, to be
+    /// swapped in by [`Gui::poll_loading`].
+    fn start_loading(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let mut pool = self.database_pool.clone();
+        let ctx = self.egui_ctx.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(load_data(&mut pool));
+            ctx.request_repaint();
+        });
+        self.loading_receiver = Some(receiver);
+    }
+
+    /// Swaps in the live data once the background load has finished.
+    fn poll_loading(&mut self) {
+        let Some(receiver) = &self.loading_receiver else {
+            return;
+        };
+
+        match receiver.try_recv() {
+            Ok(Ok(data)) => {
+                self.all_aircraft = data.all_aircraft;
+                self.route_generator = Some(data.route_generator);
+                self.airport_codes = data.airport_codes;
+                self.data_sources = data.data_sources;
+                self.airac_cycle = data.airac_cycle;
+                self.loading_receiver = None;
+                self.refresh_route_aircraft_selection();
+                self.restore_session_destination();
+                self.refresh_achievements();
+            }
+            Ok(Err(e)) => {
+                self.loading_receiver = None;
+                self.show_error(format!(
+                    "Failed to load airports: {}. Fix the database and reload airports.",
+                    e
+                ));
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.loading_receiver = None;
+                self.show_error(
+                    "Loading airports stopped without a result. Reload airports to retry."
+                        .to_string(),
+                );
+            }
         }
     }
 
+    /// Brings the window to the front when another instance was started.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    fn poll_activation(&self, ctx: &egui::Context) {
+        let Some(receiver) = &self.activation_receiver else {
+            return;
+        };
+
+        while let Ok(args) = receiver.try_recv() {
+            log::info!("Activated by another instance with arguments {:?}", args);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// Saves the current aircraft list and the last batch of routes for the next startup.
+    fn save_snapshot(&self) {
+        let snapshot = Snapshot {
+            aircraft: self.all_aircraft.iter().map(|a| (**a).clone()).collect(),
+            routes: self
+                .saved_routes
+                .iter()
+                .take(MAX_SNAPSHOT_ROUTES)
+                .map(|route| RouteSnapshot::from(route.as_ref()))
+                .collect(),
+        };
+
+        if let Err(e) = snapshot.save(Path::new(SNAPSHOT_FILENAME)) {
+            log::warn!("Failed to save snapshot: {}", e);
+        }
+    }
+
+    /// Returns the routes among the displayed items, whether filtered out or not.
+    fn displayed_routes(&self) -> impl Iterator<Item = &Arc<Route>> {
+        self.displayed_items
+            .iter()
+            .filter_map(|item| match item.as_ref() {
+                TableItem::Route(route) => Some(route),
+                _ => None,
+            })
+    }
+
+    /// Saves the snapshot when a different batch of routes is displayed, so
+    /// restarting or a crash does not lose the routes being chosen from.
+    ///
+    /// Listing aircraft, airports or the history keeps the last batch.
+    fn persist_route_batch(&mut self) {
+        if self.displayed_routes().next().is_none()
+            || self
+                .displayed_routes()
+                .map(Arc::as_ptr)
+                .eq(self.saved_routes.iter().map(Arc::as_ptr))
+        {
+            return;
+        }
+
+        self.saved_routes = self.displayed_routes().cloned().collect();
+        self.save_snapshot();
+    }
+
     /// Generates a list of random routes.
     fn generate_random_routes(&mut self) -> Result<Vec<Route>, String> {
         self.generate_random_routes_generic(&self.all_aircraft, GENERATE_AMOUNT)
@@ -293,128 +654,321 @@ impl<'a> Gui<'a> {
         aircraft_list: &[Arc<Aircraft>],
         amount: usize,
     ) -> Result<Vec<Route>, String> {
-        let start_time = Instant::now();
-
-        let routes: Vec<Route> = (0..amount)
-            .into_par_iter()
-            .filter_map(|_| {
-                let mut rand = rand::thread_rng();
-                let aircraft = aircraft_list.choose(&mut rand)?;
-
-                loop {
-                    let departure = self.all_airports.choose(&mut rand)?;
-                    let departure_runways = self.all_runways.get(&departure.ID)?;
-                    let longest_runway = departure_runways.iter().max_by_key(|r| r.Length)?;
-
-                    if let Some(takeoff_distance) = aircraft.takeoff_distance {
-                        if takeoff_distance as f64 * M_TO_FT > longest_runway.Length as f64 {
-                            continue;
-                        }
-                    }
+        let route_generator = self
+            .route_generator
+            .as_ref()
+            .ok_or("Airports are still loading")?;
+
+        if let Some(destination) = &self.route_destination_state.airport {
+            return route_generator
+                .generate_routes_to(
+                    aircraft_list,
+                    destination,
+                    amount,
+                    &self.route_constraints(),
+                )
+                .inspect_err(|e| log::warn!("{}", e));
+        }
 
-                    if let Ok(destination) = get_destination_airport_with_suitable_runway_fast(
-                        aircraft,
-                        departure,
-                        &self.spatial_airports,
-                        &self.all_runways,
-                    ) {
-                        let destination_arc = Arc::new(destination);
-                        let departure_runways = Arc::clone(departure_runways);
-                        let destination_runways = self.all_runways.get(&destination_arc.ID)?;
-                        let destination_runways = Arc::clone(destination_runways);
-                        return Some(Route {
-                            departure: Arc::clone(departure),
-                            destination: Arc::clone(&destination_arc),
-                            aircraft: Arc::clone(aircraft),
-                            departure_runway: departure_runways,
-                            destination_runway: destination_runways,
-                        });
-                    } else {
-                        continue;
-                    }
-                }
-            })
-            .collect();
+        Ok(route_generator.generate_random_routes(
+            aircraft_list,
+            amount,
+            &self.route_constraints(),
+            &self.departure_hubs(),
+        ))
+    }
 
-        let duration = start_time.elapsed();
-        log::info!("Generated {} routes in {:?}", routes.len(), duration);
+    /// Generates routes of a long haul mode, half of them in each direction.
+    ///
+    /// The hubs of the virtual airline are not used, as they may lie outside the regions.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The long haul mode.
+    fn generate_long_haul_routes(&self, mode: LongHaulMode) -> Result<Vec<Route>, String> {
+        let route_generator = self
+            .route_generator
+            .as_ref()
+            .ok_or("Airports are still loading")?;
+        let base = RouteConstraints {
+            // The modes set their own distances.
+            max_distance_nm: None,
+            ..self.route_constraints()
+        };
+        let aircraft = mode.suitable_aircraft(&self.all_aircraft, &base);
+        if aircraft.is_empty() {
+            return Err(format!(
+                "No aircraft has a range of {} NM for {} flights",
+                mode.min_distance_nm(),
+                mode.label()
+            ));
+        }
+
+        let mut routes = Vec::with_capacity(GENERATE_AMOUNT);
+        for outbound in [true, false] {
+            routes.extend(route_generator.generate_random_routes(
+                &aircraft,
+                GENERATE_AMOUNT / 2,
+                &mode.constraints(base, outbound),
+                &[],
+            ));
+        }
 
         Ok(routes)
     }
 
+    /// Returns the home base airport, if one is set and known.
+    fn home_base(&self) -> Option<Arc<Airport>> {
+        let code = self.settings.home_base.as_deref()?;
+        self.route_generator
+            .as_ref()?
+            .find_airport_by_icao(self.airport_codes.current_icao(code))
+            .cloned()
+    }
+
+    /// Returns the airports most generated routes depart from: the hubs of
+    /// the virtual airline, or else the home base.
+    fn departure_hubs(&self) -> Vec<Arc<Airport>> {
+        let hubs = self.airline_hubs();
+        if hubs.is_empty() {
+            self.home_base().into_iter().collect()
+        } else {
+            hubs
+        }
+    }
+
+    /// Returns the hub airports of the virtual airline, skipping unknown codes.
+    fn airline_hubs(&self) -> Vec<Arc<Airport>> {
+        let (Some(airline), Some(route_generator)) =
+            (&self.settings.virtual_airline, &self.route_generator)
+        else {
+            return Vec::new();
+        };
+
+        airline
+            .hubs
+            .iter()
+            .filter_map(|code| {
+                route_generator.find_airport_by_icao(self.airport_codes.current_icao(code))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the limits on generated routes from the settings and the chosen direction.
+    fn route_constraints(&self) -> RouteConstraints {
+        RouteConstraints {
+            direction: self.route_direction,
+            area: self.seasonal_preset.map(|preset| preset.area),
+            region: self.route_region,
+            ..self.settings.route_constraints()
+        }
+    }
+
+    /// Replaces the destination of a displayed route with a new random one.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to reroll the destination of.
+    fn reroll_destination(&mut self, route: &Arc<Route>) {
+        let new_route = self.route_generator.as_ref().and_then(|route_generator| {
+            route_generator.reroll_destination(route, &self.route_constraints())
+        });
+
+        match new_route {
+            Some(new_route) => self.replace_route(route, new_route),
+            None => log::warn!(
+                "No other destination found for {} from {}",
+                route.aircraft.variant,
+                route.departure.ICAO
+            ),
+        }
+    }
+
+    /// Logs a failure and shows it above the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - What failed and why.
+    pub(super) fn show_error(&mut self, message: String) {
+        log::error!("{}", message);
+        self.action_error = Some(message);
+    }
+
+    /// Reloads airports and runways from the database, applying only the changes,
+    /// or loads everything again if the first load failed.
+    fn reload_airports(&mut self) {
+        if self.route_generator.is_none() {
+            self.start_loading();
+            return;
+        }
+        let airports = match self.database_pool.get_airports() {
+            Ok(airports) => airports,
+            Err(e) => return self.show_error(format!("Failed to load airports: {}", e)),
+        };
+        let runways = match self.database_pool.get_runways() {
+            Ok(runways) => runways,
+            Err(e) => return self.show_error(format!("Failed to load runways: {}", e)),
+        };
+
+        let Some(route_generator) = self.route_generator.as_mut() else {
+            return;
+        };
+        let changes = route_generator.apply_airport_changes(airports, runways);
+        log::info!("Reloaded airports, {} changed", changes);
+
+        match self.database_pool.get_data_sources() {
+            Ok(data_sources) => self.data_sources = data_sources,
+            Err(e) => log::warn!("Failed to load data sources: {}", e),
+        }
+        match self.database_pool.get_airac_cycle() {
+            Ok(airac_cycle) => self.airac_cycle = airac_cycle,
+            Err(e) => log::warn!("Failed to load the AIRAC cycle: {}", e),
+        }
+    }
+
     /// Updates the UI buttons.
     ///
     /// # Arguments
     ///
     /// * `ui` - The UI context.
     fn update_buttons(&mut self, ui: &mut egui::Ui) {
+        let loading = self.loading_receiver.is_some();
+
         ui.vertical(|ui| {
-            if ui
-                .button("Select random aircraft")
-                .on_hover_text("Select a random aircraft from the database")
-                .clicked()
-            {
-                if let Some(aircraft) = self.all_aircraft.choose(&mut rand::thread_rng()) {
-                    self.displayed_items =
-                        vec![Arc::new(TableItem::Aircraft(Arc::clone(aircraft)))];
-                    self.search_state.query.clear();
+            if loading {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Loading airports...");
+                });
+            }
+            if let Some(warning) = data_age_warning(&self.data_sources, &self.settings) {
+                ui.colored_label(ui.visuals().warn_fg_color, warning)
+                    .on_hover_text("Update the airport database, or raise the age in the settings");
+            }
+            if let Some(cycle) = self.airac_cycle {
+                match stale_cycle_warning(cycle) {
+                    Some(warning) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, warning);
+                    }
+                    None => {
+                        ui.label(format!("AIRAC {}", cycle));
+                    }
                 }
             }
 
-            if ui.button("Get random airport").clicked() {
-                if let Some(airport) = self.all_airports.choose(&mut rand::thread_rng()) {
-                    self.displayed_items = vec![Arc::new(TableItem::Airport(Arc::clone(airport)))];
-                    self.search_state.query.clear();
+            for action in Action::BUTTONS {
+                if action == Action::RandomRoutes {
+                    ui.separator();
+                    ui.label("Destination direction:");
+                    compass::compass(ui, "route_direction", &mut self.route_direction);
+                    ui.horizontal(|ui| {
+                        ui.label("Region:");
+                        region_picker::region_picker(ui, "route_region", &mut self.route_region);
+                    });
+                    self.show_route_distance(ui);
+                    self.show_route_destination_picker(ui);
+                }
+                if action == Action::RandomAirport {
+                    self.show_random_airport_filter(ui);
+                }
+                if action == Action::SelectedAircraftRoutes {
+                    self.show_route_aircraft_picker(ui);
+                }
+                if action == Action::LongHaul(LongHaulMode::Transatlantic) {
+                    ui.separator();
+                    ui.label("Long haul:");
+                }
+                if ui
+                    .add_enabled(
+                        self.is_action_enabled(action),
+                        egui::Button::new(action.label()),
+                    )
+                    .on_hover_text(action.hover_text(ui.ctx()))
+                    .clicked()
+                {
+                    self.run_action(action);
                 }
             }
 
-            if ui.button("List all airports").clicked() {
-                self.displayed_items = self
-                    .all_airports
-                    .iter()
-                    .map(|airport| Arc::new(TableItem::Airport(Arc::clone(airport))))
-                    .collect();
-                self.search_state.query.clear();
-            }
+            ui.separator();
+            self.show_generation_presets(ui);
 
-            if ui.button("List history").clicked() {
-                let history = self
-                    .database_pool
-                    .get_history()
-                    .expect("Failed to load history");
-                self.displayed_items = history
-                    .iter()
-                    .map(|history| Arc::new(TableItem::History(Arc::new(history.clone()))))
-                    .collect();
-                self.search_state.query.clear();
-            }
+            ui.separator();
+            ui.toggle_value(&mut self.show_settings, Action::ToggleSettings.label())
+                .on_hover_text(Action::ToggleSettings.hover_text(ui.ctx()));
+            ui.toggle_value(&mut self.map_view_state.open, Action::ToggleMap.label())
+                .on_hover_text(Action::ToggleMap.hover_text(ui.ctx()));
+            ui.toggle_value(&mut self.goals_state.open, Action::ToggleGoals.label())
+                .on_hover_text(Action::ToggleGoals.hover_text(ui.ctx()));
+            ui.toggle_value(
+                &mut self.achievements_state.open,
+                Action::ToggleAchievements.label(),
+            )
+            .on_hover_text(Action::ToggleAchievements.hover_text(ui.ctx()));
+            ui.toggle_value(
+                &mut self.show_diagnostics,
+                Action::ToggleDiagnostics.label(),
+            )
+            .on_hover_text(Action::ToggleDiagnostics.hover_text(ui.ctx()));
+            ui.weak("Ctrl+P: command palette");
+        });
+    }
 
-            if ui.button("Random route").clicked() {
-                self.displayed_items.clear();
-                self.popup_state.routes_from_not_flown = false;
+    /// Shows the seasonal suggestions of today as chips, which limit generated
+    /// destinations to their area.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    fn update_seasonal_suggestions(&mut self, ui: &mut egui::Ui) {
+        if self.route_generator.is_none() {
+            return;
+        }
+        let today = chrono::Local::now().date_naive();
+        let mut presets = presets_in_season(today).peekable();
+        if presets.peek().is_none() && self.seasonal_preset.is_none() {
+            return;
+        }
 
-                if let Ok(routes) = self.generate_random_routes() {
-                    self.displayed_items.extend(
-                        routes
-                            .into_iter()
-                            .map(|route| Arc::new(TableItem::Route(Arc::new(route)))),
-                    );
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.label("In season:");
+            for preset in presets {
+                let selected = self
+                    .seasonal_preset
+                    .is_some_and(|selected| std::ptr::eq(selected, preset));
+                if ui
+                    .selectable_label(selected, preset.name)
+                    .on_hover_text(preset.description)
+                    .clicked()
+                {
+                    clicked = Some((preset, selected));
                 }
             }
-
-            if ui.button("Random not flown aircraft routes").clicked() {
-                self.displayed_items.clear();
-                self.popup_state.routes_from_not_flown = true;
-
-                if let Ok(routes) = self.generate_random_not_flown_aircraft_routes() {
-                    self.displayed_items.extend(
-                        routes
-                            .into_iter()
-                            .map(|route| Arc::new(TableItem::Route(Arc::new(route)))),
-                    );
+            // A suggestion picked before the season ended stays until it is cleared.
+            if let Some(preset) = self
+                .seasonal_preset
+                .filter(|preset| !preset.is_in_season(today))
+            {
+                if ui
+                    .small_button(format!("✖ {}", preset.name))
+                    .on_hover_text("Generate destinations anywhere again")
+                    .clicked()
+                {
+                    clicked = Some((preset, true));
                 }
             }
         });
+
+        match clicked {
+            Some((_, true)) => self.seasonal_preset = None,
+            Some((preset, false)) => {
+                self.seasonal_preset = Some(preset);
+                self.run_action(Action::RandomRoutes);
+            }
+            None => {}
+        }
     }
 
     /// Updates the search bar UI component.
@@ -424,10 +978,41 @@ impl<'a> Gui<'a> {
     /// * `ui` - The UI context.
     fn update_search_bar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            ui.label("Search:");
+            let label = ui.label("Search:");
             ui.add(
                 TextEdit::singleline(&mut self.search_state.query).hint_text("Type to search..."),
+            )
+            .labelled_by(label.id);
+
+            let showing_routes = matches!(
+                self.displayed_items.first().map(|item| item.as_ref()),
+                Some(TableItem::Route(_))
             );
+            if !self.settings.infinite_scroll
+                && showing_routes
+                && ui
+                    .add_enabled(
+                        self.search_state.query.is_empty(),
+                        egui::Button::new("Load more"),
+                    )
+                    .on_hover_text("Generate more routes")
+                    .clicked()
+            {
+                self.load_more_routes_if_needed();
+            }
+
+            let listed = self.search_state.filtered_items.len();
+            if !self.settings.infinite_scroll
+                && self
+                    .paged_airports()
+                    .is_some_and(|list| list.can_load_more(listed))
+                && ui
+                    .button("Load more")
+                    .on_hover_text("Show more airports")
+                    .clicked()
+            {
+                self.load_more_airports();
+            }
         });
     }
 
@@ -451,8 +1036,8 @@ impl<'a> Gui<'a> {
     /// * `first_item` - The first item to determine the table structure.
     fn build_table<'t>(&self, ui: &'t mut egui::Ui, first_item: &TableItem) -> TableBuilder<'t> {
         let mut columns = first_item.get_columns();
-        if let TableItem::Route(_) = first_item {
-            columns.push("Select");
+        if let TableItem::Route(_) | TableItem::History(_) | TableItem::Airport(_) = first_item {
+            columns.push("Actions");
         }
 
         let mut table = TableBuilder::new(ui)
@@ -473,19 +1058,55 @@ impl<'a> Gui<'a> {
     ///
     /// * `table` - The table builder instance.
     fn populate_table(&mut self, table: TableBuilder) {
-        let row_height = 30.0;
+        let row_height = self.settings.density.row_height();
+        let header_height = self.settings.density.header_height();
         let mut create_more_routes = false;
+        let mut show_more_airports = false;
+        let mut refly_history = None;
+        let mut airport_details = None;
+        let mut sort_column = None;
+        let sort = self.search_state.sort;
+        let block_overhead_minutes = self.settings.block_overhead_minutes;
+        let now = Utc::now();
+        let longest_runways = self
+            .route_generator
+            .as_ref()
+            .map(|route_generator| &route_generator.longest_runways);
         let filtered_items = &self.search_state.filtered_items;
+        let airports_left = self
+            .paged_airports()
+            .is_some_and(|list| list.can_load_more(filtered_items.len()));
+        let lazy_load = self.settings.infinite_scroll
+            && filtered_items.len() >= self.settings.min_items_for_lazy_load;
+        let load_more_from = filtered_items
+            .len()
+            .saturating_sub(1 + self.settings.load_more_distance);
 
         table
-            .header(20.0, |mut header| {
+            .header(header_height, |mut header| {
                 if let Some(first_item) = filtered_items.first() {
-                    for name in first_item.get_columns() {
+                    for (index, name) in first_item.get_columns().into_iter().enumerate() {
                         header.col(|ui| {
-                            ui.label(name);
+                            let Some(column) = first_item.sort_column(index) else {
+                                ui.label(name);
+                                return;
+                            };
+                            let sorted = sort.is_some_and(|sort| sort.column == column);
+                            if ui
+                                .selectable_label(
+                                    sorted,
+                                    format!("{}{}", name, TableSort::arrow(sort, column)),
+                                )
+                                .on_hover_text(format!("Sort by {}", name.to_lowercase()))
+                                .clicked()
+                            {
+                                sort_column = Some(column);
+                            }
                         });
                     }
-                    if let TableItem::Route(_) = first_item.as_ref() {
+                    if let TableItem::Route(_) | TableItem::History(_) | TableItem::Airport(_) =
+                        first_item.as_ref()
+                    {
                         header.col(|ui| {
                             ui.label("Actions");
                         });
@@ -497,7 +1118,13 @@ impl<'a> Gui<'a> {
                     let item = &filtered_items[row.index()];
 
                     // Display regular columns
-                    for name in item.get_data(self.database_pool) {
+                    for name in item.get_data(
+                        self.database_pool,
+                        &self.airport_codes,
+                        block_overhead_minutes,
+                        longest_runways,
+                        now,
+                    ) {
                         row.col(|ui| {
                             ui.label(name);
                         });
@@ -505,23 +1132,152 @@ impl<'a> Gui<'a> {
 
                     // Handle route-specific columns
                     if let TableItem::Route(route) = item.as_ref() {
-                        if row.index() == filtered_items.len() - 1 {
+                        if lazy_load && row.index() >= load_more_from {
                             create_more_routes = true;
                         }
 
                         row.col(|ui| {
-                            if ui.button("Select").clicked() {
+                            let response = ui.button("Select");
+                            response.widget_info(|| {
+                                egui::WidgetInfo::labeled(
+                                    egui::WidgetType::Button,
+                                    true,
+                                    format!(
+                                        "Select route {} to {}",
+                                        route.departure.ICAO, route.destination.ICAO
+                                    ),
+                                )
+                            });
+                            if response.clicked() {
                                 self.popup_state.show_alert = true;
                                 self.popup_state.selected_route = Some(Arc::clone(route));
                             }
                         });
                     }
+
+                    if let TableItem::History(history) = item.as_ref() {
+                        row.col(|ui| {
+                            let response = ui.button("Refly").on_hover_text("Fly this route again");
+                            response.widget_info(|| {
+                                egui::WidgetInfo::labeled(
+                                    egui::WidgetType::Button,
+                                    true,
+                                    format!(
+                                        "Refly {} to {}",
+                                        history.departure_icao, history.arrival_icao
+                                    ),
+                                )
+                            });
+                            if response.clicked() {
+                                refly_history = Some(Arc::clone(history));
+                            }
+                        });
+                    }
+
+                    if let TableItem::Airport(airport) = item.as_ref() {
+                        if airports_left && lazy_load && row.index() >= load_more_from {
+                            show_more_airports = true;
+                        }
+
+                        row.col(|ui| {
+                            let response = ui
+                                .button("Details")
+                                .on_hover_text("Show the runways and radio frequencies");
+                            response.widget_info(|| {
+                                egui::WidgetInfo::labeled(
+                                    egui::WidgetType::Button,
+                                    true,
+                                    format!("Details of {}", airport.ICAO),
+                                )
+                            });
+                            if response.clicked() {
+                                airport_details = Some(Arc::clone(airport));
+                            }
+                        });
+                    }
                 });
             });
 
+        if let Some(column) = sort_column {
+            self.search_state.sort = TableSort::toggle(sort, column);
+        }
+
         if create_more_routes {
             self.load_more_routes_if_needed();
         }
+
+        if show_more_airports {
+            self.load_more_airports();
+        }
+
+        if let Some(history) = refly_history {
+            self.refly(&history);
+        }
+
+        if let Some(airport) = airport_details {
+            self.open_airport_details(airport);
+        }
+    }
+
+    /// Rebuilds the route of a history entry, adds it on top of the route list
+    /// and opens it in the route popup, where a different aircraft can be picked.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The history entry to fly again.
+    fn refly(&mut self, history: &History) {
+        let route = self.route_generator.as_ref().and_then(|route_generator| {
+            let departure = route_generator
+                .find_airport_by_icao(self.airport_codes.current_icao(&history.departure_icao))?;
+            let destination = route_generator
+                .find_airport_by_icao(self.airport_codes.current_icao(&history.arrival_icao))?;
+            let aircraft = self
+                .all_aircraft
+                .iter()
+                .find(|a| a.id == history.aircraft)?;
+
+            Some(route_generator.create_route(
+                Arc::clone(departure),
+                Arc::clone(destination),
+                Arc::clone(aircraft),
+            ))
+        });
+
+        let Some(route) = route else {
+            log::warn!(
+                "Cannot refly {} - {}: airport or aircraft no longer available",
+                history.departure_icao,
+                history.arrival_icao
+            );
+            return;
+        };
+
+        let route = Arc::new(route);
+        // Bring back the batch of routes that listing the history replaced.
+        if self.displayed_routes().next().is_none() {
+            self.displayed_items = self
+                .saved_routes
+                .iter()
+                .map(|route| Arc::new(TableItem::Route(Arc::clone(route))))
+                .collect();
+        }
+        self.displayed_items
+            .insert(0, Arc::new(TableItem::Route(Arc::clone(&route))));
+        self.search_state.query.clear();
+        self.popup_state.selected_route = Some(route);
+        self.popup_state.show_alert = true;
+    }
+
+    /// Returns the ICAO code of an airport, followed by its IATA code if it has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to describe.
+    fn airport_codes(&self, airport: &Airport) -> String {
+        match self.airport_codes.iata(&airport.ICAO) {
+            Some(iata) => format!("{} / {}", airport.ICAO, iata),
+            None => airport.ICAO.clone(),
+        }
     }
 
     /// Shows the modal popup for route selection.
@@ -531,70 +1287,343 @@ impl<'a> Gui<'a> {
     /// * `ctx` - The egui context.
     fn show_modal_popup(&mut self, ctx: &egui::Context) {
         let modal = egui::Modal::new(Id::NULL);
+        let route = Arc::clone(self.popup_state.selected_route.as_ref().unwrap());
+        let mut substitute = None;
+        let mut reroll = false;
+        let mut export = false;
+        let (position, route_count) = self.route_position(&route);
+        let mut step = 0;
+
         modal.show(ctx, |ui| {
-            let route = self.popup_state.selected_route.as_ref().unwrap();
-            let route_clone = Arc::clone(route);
-            let distance = {
-                let point1 = geo::Point::new(route.departure.Latitude, route.departure.Longtitude);
-                let point2 =
-                    geo::Point::new(route.destination.Latitude, route.destination.Longtitude);
-                (Haversine::distance(point1, point2) * M_TO_NM).round()
-            };
+            if let Some(position) = position {
+                ui.horizontal(|ui| {
+                    let previous = ui
+                        .add_enabled(position > 0, egui::Button::new("◀ Previous"))
+                        .on_hover_text("Previous route (Page Up)");
+                    if previous.clicked() {
+                        step = -1;
+                    }
+                    ui.label(format!("Route {} of {}", position + 1, route_count));
+                    let next = ui
+                        .add_enabled(position + 1 < route_count, egui::Button::new("Next ▶"))
+                        .on_hover_text("Next route (Page Down)");
+                    if next.clicked() {
+                        step = 1;
+                    }
+                });
+                ui.separator();
+            }
 
             ui.label(format!(
                 "Departure: {} ({})",
-                route.departure.Name, route.departure.ICAO
+                route.departure.Name,
+                self.airport_codes(&route.departure)
             ));
             ui.label(format!(
                 "Destination: {} ({})",
-                route.destination.Name, route.destination.ICAO
+                route.destination.Name,
+                self.airport_codes(&route.destination)
             ));
-            ui.label(format!("Distance: {:.2} NM", distance));
+            ui.label(format!("Distance: {:.2} NM", route.distance_nm()));
+            ui.horizontal(|ui| {
+                let callsign = route_callsign(
+                    &route,
+                    self.settings.callsign_style,
+                    self.settings.callsign_prefix(),
+                );
+                ui.label(format!("Callsign: {}", callsign));
+                if ui
+                    .small_button("Copy")
+                    .on_hover_text("Copy the callsign for the simulator")
+                    .clicked()
+                {
+                    ui.ctx().copy_text(callsign);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let label = ui.label("Aircraft:");
+                egui::ComboBox::from_id_salt("route_aircraft")
+                    .selected_text(format!(
+                        "{} {}",
+                        route.aircraft.manufacturer, route.aircraft.variant
+                    ))
+                    .show_ui(ui, |ui| {
+                        for aircraft in self
+                            .all_aircraft
+                            .iter()
+                            .filter(|aircraft| route.is_suitable_for(aircraft))
+                        {
+                            let selected = aircraft.id == route.aircraft.id;
+                            let label = format!("{} {}", aircraft.manufacturer, aircraft.variant);
+                            if ui.selectable_label(selected, label).clicked() && !selected {
+                                substitute = Some(Arc::clone(aircraft));
+                            }
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id)
+                    .on_hover_text("Aircraft with enough range and runway for this route");
+            });
+
+            if let Some(ete) = route.ete_hours() {
+                ui.label(format!(
+                    "Estimated time en route: {}",
+                    format_duration_hours(ete)
+                ));
+            }
+            let overhead_minutes = self.settings.block_overhead_minutes;
+            if let Some(block_time) = route.block_time_hours(overhead_minutes) {
+                ui.label(format!(
+                    "Estimated block time: {}",
+                    format_duration_hours(block_time)
+                ))
+                .on_hover_text(format!(
+                    "Time en route plus {} minutes of taxiing and climbing, set in the settings",
+                    overhead_minutes
+                ));
+            }
+            match route.arrives_in_daylight(Utc::now(), overhead_minutes) {
+                Some(true) => {
+                    ui.label("Arrives in daylight")
+                        .on_hover_text("Leaving the gate now, between sunrise and sunset");
+                }
+                Some(false) => {
+                    ui.label("Arrives at night")
+                        .on_hover_text("Leaving the gate now, after sunset or before sunrise");
+                }
+                None => {}
+            }
+            self.show_diversion_coverage(ui, &route);
             ui.label(format!(
-                "Aircraft: {} {}",
-                route.aircraft.manufacturer, route.aircraft.variant
-            ));
+                "Suggested cruise altitude: {}",
+                format_altitude(route.suggested_altitude_ft())
+            ))
+            .on_hover_text(
+                "Based on distance, direction of flight (odd thousands eastbound, \
+                 even westbound) and the aircraft's service ceiling",
+            );
+
+            egui::CollapsingHeader::new("Fuel and payload").show(ui, |ui| {
+                let load = &mut self.popup_state.aircraft_load;
+                ui.add(
+                    egui::Slider::new(&mut load.fuel_percent, 0.0..=100.0)
+                        .text("Fuel")
+                        .suffix(" %")
+                        .fixed_decimals(0),
+                );
+                ui.add(
+                    egui::Slider::new(&mut load.payload_percent, 0.0..=100.0)
+                        .text("Payload")
+                        .suffix(" %")
+                        .fixed_decimals(0),
+                );
+
+                let effective_range = load.effective_range_nm(&route.aircraft);
+                ui.label(format!("Estimated range: {:.0} NM", effective_range))
+                    .on_hover_text("Rough estimate from the published range of the aircraft");
+                if route.distance_nm() > effective_range {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "⚠ The route is longer than the estimated range",
+                    );
+                }
+            });
+
+            self.show_enroute_alternates(ui, &route);
+
+            // A form left open for another route is discarded.
+            if self
+                .popup_state
+                .mark_flown
+                .as_ref()
+                .is_some_and(|form| !form.is_for(&route))
+            {
+                self.popup_state.mark_flown = None;
+            }
+            let form_open = self.popup_state.mark_flown.is_some();
 
             ui.separator();
             ui.horizontal(|ui| {
-                if self.popup_state.routes_from_not_flown && ui.button("Mark as flown").clicked() {
-                    self.handle_mark_flown_button(&route_clone);
+                if self.popup_state.route_source != RouteSource::All
+                    && ui
+                        .add_enabled(!form_open, egui::Button::new("Mark as flown"))
+                        .clicked()
+                {
+                    self.popup_state.mark_flown = Some(MarkFlownForm::new(Arc::clone(&route)));
+                }
+                reroll = ui
+                    .add_enabled(
+                        self.route_generator.is_some(),
+                        egui::Button::new("Reroll destination"),
+                    )
+                    .on_hover_text("Keep the departure and aircraft, pick another destination")
+                    .clicked();
+                if ui
+                    .add_enabled(
+                        !route.aircraft.icao_code.is_empty(),
+                        egui::Button::new("Export to SimBrief"),
+                    )
+                    .on_hover_text("Start a flight plan for this route in SimBrief")
+                    .on_disabled_hover_text("The aircraft has no ICAO type code")
+                    .clicked()
+                {
+                    let callsign = route_callsign(
+                        &route,
+                        self.settings.callsign_style,
+                        self.settings.callsign_prefix(),
+                    );
+                    let sim_titles = self.database_pool.get_sim_titles().unwrap_or_else(|e| {
+                        log::error!("Failed to load simulator titles: {}", e);
+                        Vec::new()
+                    });
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(dispatch_url(
+                        &route,
+                        &callsign,
+                        &sim_titles,
+                    )));
+                }
+                if ui
+                    .button("Export")
+                    .on_hover_text("Save this route as GPX or KML")
+                    .clicked()
+                {
+                    export = true;
                 }
                 if ui.button("Close").clicked() {
                     self.popup_state.show_alert = false;
+                    self.popup_state.mark_flown = None;
                 }
             });
+            if form_open {
+                self.show_mark_flown_form(ui);
+            }
         });
+
+        if let Some(aircraft) = substitute {
+            self.replace_route(&route, route.with_aircraft(aircraft));
+        } else if reroll {
+            self.reroll_destination(&route);
+        } else if export {
+            // The popup is modal, so it has to close for the export window to be used.
+            self.popup_state.show_alert = false;
+            self.popup_state.mark_flown = None;
+            self.open_export_window(vec![Arc::clone(&route)]);
+        }
+
+        if step == 0 && !ctx.wants_keyboard_input() {
+            ctx.input_mut(|input| {
+                if input.consume_key(egui::Modifiers::NONE, egui::Key::PageUp) {
+                    step = -1;
+                } else if input.consume_key(egui::Modifiers::NONE, egui::Key::PageDown) {
+                    step = 1;
+                }
+            });
+        }
+        if let Some(position) = position.filter(|_| step != 0) {
+            let route = self
+                .visible_routes()
+                .nth(position.wrapping_add_signed(step))
+                .cloned();
+            if route.is_some() {
+                self.popup_state.selected_route = route;
+            }
+        }
+    }
+
+    /// Returns the routes shown in the table, in order.
+    fn visible_routes(&self) -> impl Iterator<Item = &Arc<Route>> {
+        self.search_state
+            .filtered_items
+            .iter()
+            .filter_map(|item| match item.as_ref() {
+                TableItem::Route(route) => Some(route),
+                _ => None,
+            })
+    }
+
+    /// Returns the position of a route among the routes shown in the table,
+    /// and the number of routes shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to look up.
+    fn route_position(&self, route: &Arc<Route>) -> (Option<usize>, usize) {
+        let position = self
+            .visible_routes()
+            .position(|visible| Arc::ptr_eq(visible, route));
+
+        (position, self.visible_routes().count())
+    }
+
+    /// Replaces a displayed route, keeping its position in the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_route` - The route currently displayed.
+    /// * `new_route` - The route to show in its place.
+    fn replace_route(&mut self, old_route: &Arc<Route>, new_route: Route) {
+        let new_route = Arc::new(new_route);
+
+        for item in self.displayed_items.iter_mut() {
+            if matches!(item.as_ref(), TableItem::Route(route) if Arc::ptr_eq(route, old_route)) {
+                *item = Arc::new(TableItem::Route(Arc::clone(&new_route)));
+            }
+        }
+
+        self.popup_state.selected_route = Some(new_route);
     }
 
-    /// Handles the action when the "Mark as flown" button is pressed.
+    /// Handles the action when marking a route as flown is confirmed.
     ///
     /// # Arguments
     ///
     /// * `route` - The route to mark as flown.
-    fn handle_mark_flown_button(&mut self, route: &Route) {
+    /// * `date` - The date the route was flown.
+    /// * `notes` - An optional note stored with the history entry.
+    /// * `arrival` - The airport actually landed at, which may differ from the
+    ///   destination after a diversion. The aircraft is credited either way.
+    fn handle_mark_flown_button(
+        &mut self,
+        route: &Route,
+        date: NaiveDate,
+        notes: Option<&str>,
+        arrival: &Airport,
+    ) {
+        let date = date.format("%Y-%m-%d").to_string();
         self.popup_state.show_alert = false;
         self.database_pool
-            .add_to_history(
+            .add_to_history_on(
                 route.departure.as_ref(),
-                route.destination.as_ref(),
+                arrival,
                 route.aircraft.as_ref(),
+                &date,
+                FlightDetails {
+                    notes,
+                    planned_arrival: (arrival.ID != route.destination.ID)
+                        .then_some(route.destination.as_ref()),
+                    airline: self
+                        .settings
+                        .virtual_airline
+                        .as_ref()
+                        .map(|airline| airline.name.trim())
+                        .filter(|name| !name.is_empty()),
+                },
             )
             .expect("Failed to add route to history");
+        self.reload_flown_aircraft();
+    }
 
-        let mut aircraft = (*route.aircraft).clone();
-        aircraft.date_flown = Some(chrono::Local::now().format("%Y-%m-%d").to_string());
-        aircraft.flown = 1;
-
-        self.database_pool
-            .update_aircraft(&aircraft)
-            .expect("Failed to update aircraft");
-
+    /// Reloads the aircraft after flights were added to the history, which
+    /// counted them for their aircraft.
+    fn reload_flown_aircraft(&mut self) {
         let all_aircraft = self
             .database_pool
             .get_all_aircraft()
             .expect("Failed to load aircraft");
         self.all_aircraft = all_aircraft.into_iter().map(Arc::new).collect();
+        self.refresh_route_aircraft_selection();
+        self.refresh_achievements();
     }
 
     /// Handles user input and updates state.
@@ -603,24 +1632,103 @@ impl<'a> Gui<'a> {
     ///
     /// * `ctx` - The egui context.
     fn handle_input(&mut self, ctx: &egui::Context) {
+        self.poll_loading();
+        self.poll_activation(ctx);
+
         if self.popup_state.show_alert {
             self.show_modal_popup(ctx);
         }
 
+        if self.show_diagnostics {
+            self.show_diagnostics_window(ctx);
+        }
+
+        if self.custom_route_state.open {
+            self.show_custom_route_window(ctx);
+        }
+
+        if self.sim_mapping_state.open {
+            self.show_sim_mapping_window(ctx);
+        }
+
+        if self.show_settings {
+            self.show_settings_window(ctx);
+        }
+
+        if self.goals_state.open {
+            self.show_goals_window(ctx);
+        }
+
+        if self.achievements_state.open {
+            self.show_achievements_window(ctx);
+        }
+
+        if self.airport_details_state.is_open() {
+            self.show_airport_details_window(ctx);
+        }
+
+        if self.airport_compare_state.open {
+            self.show_airport_compare_window(ctx);
+        }
+
+        if self.report_state.open {
+            self.show_report_window(ctx);
+        }
+
+        if self.export_state.is_open() {
+            self.show_export_window(ctx);
+        }
+
+        if self.history_export_state.open {
+            self.show_history_export_window(ctx);
+        }
+
+        if self.logbook_import_state.open {
+            self.show_logbook_import_window(ctx);
+        }
+
+        if self.map_view_state.open {
+            self.show_map_window(ctx);
+        }
+
+        self.handle_command_palette(ctx);
+        self.handle_shortcuts(ctx);
+
         self.handle_search();
     }
 
     /// Filters the displayed items based on the search query.
     fn handle_search(&mut self) {
-        self.search_state.filtered_items = if self.search_state.query.is_empty() {
+        if self.filter_paged_airports() {
+            return;
+        }
+
+        let mut filtered_items = if self.search_state.query.is_empty() {
             self.displayed_items.iter().map(Arc::clone).collect()
         } else {
             self.displayed_items
                 .iter()
-                .filter(|item| item.matches_query(&self.search_state.query))
+                .filter(|item| item.matches_query(&self.search_state.query, &self.airport_codes))
                 .map(Arc::clone)
                 .collect()
         };
+        self.sort_table_items(&mut filtered_items);
+        self.search_state.filtered_items = filtered_items;
+    }
+
+    /// Sorts items of the table by the column chosen, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items to sort.
+    fn sort_table_items(&self, items: &mut Vec<Arc<TableItem>>) {
+        if let Some(sort) = self.search_state.sort {
+            let longest_runways = self
+                .route_generator
+                .as_ref()
+                .map(|route_generator| &route_generator.longest_runways);
+            sort_items(items, sort, longest_runways);
+        }
     }
 
     /// Loads more routes if needed.
@@ -629,10 +1737,11 @@ impl<'a> Gui<'a> {
             return;
         }
 
-        if let Ok(routes) = if self.popup_state.routes_from_not_flown {
-            self.generate_random_not_flown_aircraft_routes()
-        } else {
-            self.generate_random_routes()
+        if let Ok(routes) = match self.popup_state.route_source {
+            RouteSource::All => self.generate_random_routes(),
+            RouteSource::NotFlown => self.generate_random_not_flown_aircraft_routes(),
+            RouteSource::Selected => self.generate_selected_aircraft_routes(),
+            RouteSource::LongHaul(mode) => self.generate_long_haul_routes(mode),
         } {
             self.displayed_items.extend(
                 routes
@@ -648,19 +1757,36 @@ impl<'a> Gui<'a> {
     ///
     /// * `ctx` - The egui context.
     fn render_ui(&mut self, ctx: &egui::Context) {
+        self.show_goal_reminder(ctx);
+        self.show_achievement_toasts(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
+            // Registered before the widgets so it only receives clicks they don't handle.
+            let background = ui.interact(
+                ui.max_rect(),
+                ui.id().with("action_menu"),
+                egui::Sense::click(),
+            );
+
             ui.add_enabled_ui(!self.popup_state.show_alert, |ui| {
                 ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
                     self.update_buttons(ui);
                     ui.add_space(50.0);
 
                     ui.vertical(|ui| {
+                        self.update_seasonal_suggestions(ui);
+                        if let Some(e) = &self.action_error {
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                        }
                         self.update_search_bar(ui);
 
                         self.update_table(ui);
                     });
                 });
             });
+
+            if !self.popup_state.show_alert {
+                background.context_menu(|ui| self.action_menu(ui));
+            }
         });
     }
 }
@@ -673,7 +1799,253 @@ impl eframe::App for Gui<'_> {
     /// * `ctx` - The egui context.
     /// * `_frame` - The eframe frame.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(rect) = ctx.input(|input| input.viewport().inner_rect) {
+            self.window_size = Some(rect.size());
+        }
         self.handle_input(ctx);
         self.render_ui(ctx);
+        self.persist_route_batch();
+    }
+
+    /// Saves a snapshot of the session so the next startup can show it immediately,
+    /// and the choices made in the session to restore them.
+    ///
+    /// # Arguments
+    ///
+    /// * `_gl` - The glow context.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_snapshot();
+        self.save_session();
+    }
+}
+
+/// Shows the error of a form field in the row below it in a two column grid, if any.
+///
+/// # Arguments
+///
+/// * `ui` - The UI of the grid.
+/// * `errors` - The errors of the form.
+/// * `field` - The name of the field.
+fn show_field_error(ui: &mut egui::Ui, errors: &FieldErrors, field: &str) {
+    if let Some(message) = errors.get(field) {
+        ui.label("");
+        ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", message));
+        ui.end_row();
+    }
+}
+
+/// Loads aircraft, airports and runways and builds the route generator.
+///
+/// # Arguments
+///
+/// * `database_pool` - The database pool to load from.
+fn load_data(database_pool: &mut DatabasePool) -> Result<LoadedData, Error> {
+    let all_aircraft = database_pool.get_all_aircraft()?;
+    let all_airports = database_pool.get_airports()?;
+    let runway_data = database_pool.get_runways()?;
+    let airport_codes = AirportCodes::load(database_pool)?;
+    let data_sources = database_pool.get_data_sources()?;
+    let airac_cycle = database_pool.get_airac_cycle()?;
+
+    let route_generator = RouteGenerator::new(all_airports, runway_data);
+
+    Ok(LoadedData {
+        all_aircraft: all_aircraft.into_iter().map(Arc::new).collect(),
+        route_generator,
+        airport_codes,
+        data_sources,
+        airac_cycle,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Opens databases without any tables, so every query fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the database file, unique per test.
+    fn empty_databases(name: &str) -> DatabasePool {
+        let path = std::env::temp_dir().join(format!(
+            "flight-planner-gui-{}-{}.db3",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_string_lossy();
+
+        DatabasePool::open(&path, &path).unwrap()
+    }
+
+    #[test]
+    fn history_load_failures_are_shown() {
+        let mut database_pool = empty_databases("history");
+        let mut gui = Gui::empty(
+            &mut database_pool,
+            Settings::default(),
+            egui::Context::default(),
+        );
+        gui.route_generator = Some(RouteGenerator::new(Vec::new(), Vec::new()));
+
+        gui.refresh_achievements();
+        assert!(gui
+            .action_error
+            .take()
+            .is_some_and(|e| e.starts_with("Failed to load history")));
+
+        assert_eq!(gui.render_report(), None);
+        assert!(gui.action_error.take().is_some());
+
+        gui.run_action(Action::ListHistory);
+        assert!(gui.action_error.is_some());
+    }
+
+    #[test]
+    fn airport_load_failures_are_shown_and_retried() {
+        let mut database_pool = empty_databases("airports");
+        let mut gui = Gui::empty(
+            &mut database_pool,
+            Settings::default(),
+            egui::Context::default(),
+        );
+
+        gui.start_loading();
+        assert!(!gui.is_action_enabled(Action::ReloadAirports));
+        while gui.loading_receiver.is_some() {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            gui.poll_loading();
+        }
+
+        assert!(gui
+            .action_error
+            .as_deref()
+            .is_some_and(|e| e.starts_with("Failed to load airports")));
+        assert!(gui.is_action_enabled(Action::ReloadAirports));
+
+        gui.run_action(Action::ReloadAirports);
+        assert!(gui.loading_receiver.is_some());
+        assert_eq!(gui.action_error, None);
+    }
+
+    #[test]
+    fn airport_reload_failures_keep_the_route_generator() {
+        let mut database_pool = empty_databases("reload");
+        let mut gui = Gui::empty(
+            &mut database_pool,
+            Settings::default(),
+            egui::Context::default(),
+        );
+        let airport = Airport {
+            ID: 1,
+            ICAO: "EHAM".to_string(),
+            ..Default::default()
+        };
+        gui.route_generator = Some(RouteGenerator::new(vec![airport], Vec::new()));
+
+        gui.run_action(Action::ReloadAirports);
+
+        assert!(gui
+            .action_error
+            .as_deref()
+            .is_some_and(|e| e.starts_with("Failed to load airports")));
+        assert!(gui
+            .route_generator
+            .as_ref()
+            .is_some_and(|generator| generator.find_airport_by_icao("EHAM").is_some()));
+        assert!(gui.loading_receiver.is_none());
+    }
+
+    #[test]
+    fn route_generation_failures_keep_the_table() {
+        let mut database_pool = empty_databases("generate");
+        let mut gui = Gui::empty(
+            &mut database_pool,
+            Settings::default(),
+            egui::Context::default(),
+        );
+        gui.route_generator = Some(RouteGenerator::new(Vec::new(), Vec::new()));
+        let airport = Arc::new(Airport {
+            ID: 1,
+            ICAO: "EHAM".to_string(),
+            ..Default::default()
+        });
+        gui.displayed_items = vec![Arc::new(TableItem::Airport(airport))];
+
+        // There are no aircraft to fly the long haul routes.
+        gui.run_action(Action::LongHaul(LongHaulMode::Polar));
+
+        assert!(gui
+            .action_error
+            .as_deref()
+            .is_some_and(|e| e.starts_with("Failed to generate routes")));
+        assert_eq!(gui.displayed_items.len(), 1);
+    }
+
+    #[test]
+    fn refly_adds_the_route_on_top_of_the_batch() {
+        let airport = |id: i32, icao: &str| Airport {
+            ID: id,
+            ICAO: icao.to_string(),
+            Latitude: 52.0,
+            Longtitude: f64::from(id),
+            ..Default::default()
+        };
+        let aircraft = Arc::new(Aircraft {
+            id: 1,
+            manufacturer: "Boeing".to_string(),
+            variant: "737-800".to_string(),
+            icao_code: "B738".to_string(),
+            flown: 0,
+            aircraft_range: 3000,
+            category: "A".to_string(),
+            cruise_speed: 450,
+            date_flown: None,
+            takeoff_distance: None,
+            service_ceiling: None,
+            helicopter: false,
+            amphibian: false,
+        });
+        let mut database_pool = empty_databases("refly");
+        let mut gui = Gui::empty(
+            &mut database_pool,
+            Settings::default(),
+            egui::Context::default(),
+        );
+        let route_generator = RouteGenerator::new(
+            vec![airport(1, "EHAM"), airport(2, "EDDF"), airport(3, "EGLL")],
+            Vec::new(),
+        );
+        let [eham, eddf] = [0, 1].map(|i| Arc::clone(&route_generator.all_airports[i]));
+        let batch = Arc::new(route_generator.create_route(eham, eddf, Arc::clone(&aircraft)));
+        gui.route_generator = Some(route_generator);
+        gui.all_aircraft = vec![aircraft];
+        gui.saved_routes = vec![Arc::clone(&batch)];
+        let history = History {
+            id: 1,
+            departure_icao: "EGLL".to_string(),
+            arrival_icao: "EHAM".to_string(),
+            aircraft: 1,
+            date: "2026-10-01".to_string(),
+            notes: None,
+            planned_arrival_icao: None,
+            airline: None,
+        };
+        gui.displayed_items = vec![Arc::new(TableItem::History(Arc::new(history.clone())))];
+
+        gui.refly(&history);
+
+        let routes: Vec<(&str, &str)> = gui
+            .displayed_routes()
+            .map(|route| {
+                (
+                    route.departure.ICAO.as_str(),
+                    route.destination.ICAO.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(routes, [("EGLL", "EHAM"), ("EHAM", "EDDF")]);
+        assert!(Arc::ptr_eq(&gui.saved_routes[0], &batch));
     }
 }
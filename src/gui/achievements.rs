@@ -0,0 +1,141 @@
+use super::Gui;
+use crate::modules::achievements::{unlocked_achievements, Achievement, Unlock};
+use crate::traits::HistoryOperations;
+use eframe::egui;
+
+/// How long an unlock toast stays on screen in seconds.
+const TOAST_SECONDS: f64 = 6.0;
+
+/// State of the achievements window and the unlock toasts.
+#[derive(Default)]
+pub struct AchievementsState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The achievements unlocked so far, `None` until the airports have loaded.
+    unlocked: Option<Vec<Unlock>>,
+    /// Newly unlocked achievements and the time their toast was first shown.
+    toasts: Vec<(Achievement, Option<f64>)>,
+}
+
+impl Gui<'_> {
+    /// Evaluates the achievements from the history, queueing a toast for every
+    /// achievement unlocked since the last evaluation.
+    pub(super) fn refresh_achievements(&mut self) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let history = match self.database_pool.get_history() {
+            Ok(history) => history,
+            Err(e) => {
+                self.show_error(format!("Failed to load history: {}", e));
+                return;
+            }
+        };
+        let unlocked =
+            unlocked_achievements(&history, &route_generator.all_airports, &self.all_aircraft);
+
+        let state = &mut self.achievements_state;
+        // The first evaluation only records what was unlocked in earlier sessions.
+        if let Some(previous) = &state.unlocked {
+            for unlock in &unlocked {
+                if !previous
+                    .iter()
+                    .any(|known| known.achievement == unlock.achievement)
+                {
+                    state.toasts.push((unlock.achievement, None));
+                }
+            }
+        }
+        state.unlocked = Some(unlocked);
+    }
+
+    /// Shows the achievements window, listing every achievement and when it was unlocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_achievements_window(&mut self, ctx: &egui::Context) {
+        let state = &mut self.achievements_state;
+
+        egui::Window::new("Achievements")
+            .open(&mut state.open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(unlocked) = &state.unlocked else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading airports…");
+                    });
+                    return;
+                };
+
+                egui::Grid::new("achievements")
+                    .striped(true)
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for achievement in Achievement::ALL {
+                            let unlock = unlocked
+                                .iter()
+                                .find(|unlock| unlock.achievement == achievement);
+
+                            ui.vertical(|ui| {
+                                if unlock.is_some() {
+                                    ui.strong(format!("🏆 {}", achievement.title()));
+                                } else {
+                                    ui.weak(achievement.title());
+                                }
+                                ui.label(achievement.description());
+                            });
+                            match unlock {
+                                Some(unlock) => ui.label(format!("Unlocked {}", unlock.date)),
+                                None => ui.weak("Locked"),
+                            };
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.label(format!(
+                    "{} of {} unlocked",
+                    unlocked.len(),
+                    Achievement::ALL.len()
+                ));
+            });
+    }
+
+    /// Shows a toast in the corner of the window for every newly unlocked achievement.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_achievement_toasts(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|input| input.time);
+        let toasts = &mut self.achievements_state.toasts;
+        toasts.retain(|(_, shown)| shown.is_none_or(|shown| now - shown < TOAST_SECONDS));
+        if toasts.is_empty() {
+            return;
+        }
+
+        let mut open_window = false;
+        egui::Area::new(egui::Id::new("achievement_toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (achievement, shown) in toasts.iter_mut() {
+                    shown.get_or_insert(now);
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.strong(format!("🏆 Achievement unlocked: {}", achievement.title()));
+                        ui.label(achievement.description());
+                        if ui.small_button("Show achievements").clicked() {
+                            open_window = true;
+                        }
+                    });
+                }
+            });
+
+        if open_window {
+            self.achievements_state.open = true;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+}
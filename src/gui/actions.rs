@@ -0,0 +1,629 @@
+use super::{Gui, RouteSource, TableItem};
+use crate::modules::long_haul::LongHaulMode;
+use crate::traits::HistoryOperations;
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use rand::prelude::SliceRandom;
+use std::sync::Arc;
+
+/// A user action that can be triggered from the buttons, the context menu,
+/// a keyboard shortcut or the command palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    SelectRandomAircraft,
+    SuggestAircraft,
+    RandomAirport,
+    ListAirports,
+    CompareAirports,
+    ReloadAirports,
+    ListHistory,
+    RandomRoutes,
+    RandomNotFlownRoutes,
+    SelectedAircraftRoutes,
+    LongHaul(LongHaulMode),
+    CustomRoute,
+    SimulatorAircraft,
+    GenerateReport,
+    ExportRoutes,
+    ExportHistory,
+    ImportLogbook,
+    ToggleSettings,
+    ToggleMap,
+    ToggleGoals,
+    ToggleAchievements,
+    ToggleDiagnostics,
+}
+
+impl Action {
+    /// The actions shown as buttons in the side panel, in order.
+    pub const BUTTONS: [Action; 19] = [
+        Action::SelectRandomAircraft,
+        Action::SuggestAircraft,
+        Action::RandomAirport,
+        Action::ListAirports,
+        Action::CompareAirports,
+        Action::ReloadAirports,
+        Action::ListHistory,
+        Action::RandomRoutes,
+        Action::RandomNotFlownRoutes,
+        Action::SelectedAircraftRoutes,
+        Action::LongHaul(LongHaulMode::Transatlantic),
+        Action::LongHaul(LongHaulMode::Transpacific),
+        Action::LongHaul(LongHaulMode::Polar),
+        Action::CustomRoute,
+        Action::SimulatorAircraft,
+        Action::GenerateReport,
+        Action::ExportRoutes,
+        Action::ExportHistory,
+        Action::ImportLogbook,
+    ];
+
+    /// Every action, as listed in the command palette.
+    pub const ALL: [Action; 24] = [
+        Action::SelectRandomAircraft,
+        Action::SuggestAircraft,
+        Action::RandomAirport,
+        Action::ListAirports,
+        Action::CompareAirports,
+        Action::ReloadAirports,
+        Action::ListHistory,
+        Action::RandomRoutes,
+        Action::RandomNotFlownRoutes,
+        Action::SelectedAircraftRoutes,
+        Action::LongHaul(LongHaulMode::Transatlantic),
+        Action::LongHaul(LongHaulMode::Transpacific),
+        Action::LongHaul(LongHaulMode::Polar),
+        Action::CustomRoute,
+        Action::SimulatorAircraft,
+        Action::GenerateReport,
+        Action::ExportRoutes,
+        Action::ExportHistory,
+        Action::ImportLogbook,
+        Action::ToggleSettings,
+        Action::ToggleMap,
+        Action::ToggleGoals,
+        Action::ToggleAchievements,
+        Action::ToggleDiagnostics,
+    ];
+
+    /// Returns the label of the action.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::SelectRandomAircraft => "Select random aircraft",
+            Action::SuggestAircraft => "Suggest aircraft",
+            Action::RandomAirport => "Get random airport",
+            Action::ListAirports => "List all airports",
+            Action::CompareAirports => "Compare airports",
+            Action::ReloadAirports => "Reload airports",
+            Action::ListHistory => "List history",
+            Action::RandomRoutes => "Random route",
+            Action::RandomNotFlownRoutes => "Random not flown aircraft routes",
+            Action::SelectedAircraftRoutes => "Routes for selected aircraft",
+            Action::LongHaul(mode) => mode.label(),
+            Action::CustomRoute => "Custom route",
+            Action::SimulatorAircraft => "Simulator aircraft",
+            Action::GenerateReport => "Generate report",
+            Action::ExportRoutes => "Export routes",
+            Action::ExportHistory => "Export history",
+            Action::ImportLogbook => "Import logbook",
+            Action::ToggleSettings => "Settings",
+            Action::ToggleMap => "Route map",
+            Action::ToggleGoals => "Fleet goal",
+            Action::ToggleAchievements => "Achievements",
+            Action::ToggleDiagnostics => "Memory diagnostics",
+        }
+    }
+
+    /// Returns a short explanation of the action.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::SelectRandomAircraft => "Select a random aircraft from the database",
+            Action::SuggestAircraft => {
+                "Pick the aircraft flown least recently for the selected aircraft routes"
+            }
+            Action::RandomAirport => "Show a random airport within the airport filter",
+            Action::ListAirports => "List every airport in the database",
+            Action::CompareAirports => {
+                "Put two airports side by side, such as candidates for a home base"
+            }
+            Action::ReloadAirports => "Pick up changes made to the airport database",
+            Action::ListHistory => "List the flights marked as flown",
+            Action::RandomRoutes => "Generate routes for random aircraft",
+            Action::RandomNotFlownRoutes => "Generate routes for aircraft not flown yet",
+            Action::SelectedAircraftRoutes => "Generate routes for the aircraft picked above",
+            Action::LongHaul(mode) => mode.description(),
+            Action::CustomRoute => {
+                "Build a route from a chosen departure, destination and aircraft"
+            }
+            Action::SimulatorAircraft => "Map simulator aircraft titles to your aircraft",
+            Action::GenerateReport => "Save a summary of your flights in a period to share",
+            Action::ExportRoutes => "Save the routes in the table as GPX or KML",
+            Action::ExportHistory => "Save every flight flown as CSV or JSON",
+            Action::ImportLogbook => "Add flights from a Volanta or SimToolkitPro CSV export",
+            Action::ToggleSettings => "Show or hide the settings window",
+            Action::ToggleMap => "Show the routes in the table as great circles on a map",
+            Action::ToggleGoals => "Show the progress towards flying every aircraft",
+            Action::ToggleAchievements => "Show the milestones unlocked by your flights",
+            Action::ToggleDiagnostics => "Show how much memory the loaded data uses",
+        }
+    }
+
+    /// Returns the keyboard shortcut of the action, if it has one.
+    pub fn shortcut(&self) -> Option<KeyboardShortcut> {
+        let shortcut = match self {
+            Action::ListAirports => KeyboardShortcut::new(Modifiers::COMMAND, Key::L),
+            Action::ReloadAirports => KeyboardShortcut::new(Modifiers::NONE, Key::F5),
+            Action::ListHistory => KeyboardShortcut::new(Modifiers::COMMAND, Key::H),
+            Action::RandomRoutes => KeyboardShortcut::new(Modifiers::COMMAND, Key::R),
+            Action::RandomNotFlownRoutes => KeyboardShortcut::new(Modifiers::COMMAND, Key::N),
+            Action::ToggleSettings => KeyboardShortcut::new(Modifiers::COMMAND, Key::Comma),
+            Action::ToggleMap => KeyboardShortcut::new(Modifiers::COMMAND, Key::M),
+            Action::ToggleDiagnostics => KeyboardShortcut::new(Modifiers::NONE, Key::F12),
+            Action::SelectRandomAircraft
+            | Action::SuggestAircraft
+            | Action::RandomAirport
+            | Action::CompareAirports
+            | Action::SelectedAircraftRoutes
+            | Action::LongHaul(_)
+            | Action::CustomRoute
+            | Action::SimulatorAircraft
+            | Action::GenerateReport
+            | Action::ExportRoutes
+            | Action::ExportHistory
+            | Action::ImportLogbook
+            | Action::ToggleGoals
+            | Action::ToggleAchievements => return None,
+        };
+
+        Some(shortcut)
+    }
+
+    /// Returns the description followed by the shortcut, for hover texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context, used to format the shortcut for the platform.
+    pub fn hover_text(&self, ctx: &egui::Context) -> String {
+        match self.shortcut() {
+            Some(shortcut) => format!(
+                "{} ({})",
+                self.description(),
+                ctx.format_shortcut(&shortcut)
+            ),
+            None => self.description().to_string(),
+        }
+    }
+}
+
+/// The part of the GUI state that actions read and change without the
+/// databases, the route generator or randomness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ViewState {
+    /// Whether the airport database is loaded into a route generator.
+    pub airports_loaded: bool,
+    /// Whether the airport database is being loaded in the background.
+    pub loading: bool,
+    /// Whether any aircraft are picked for the selected aircraft routes.
+    pub aircraft_selected: bool,
+    /// Whether the table shows any routes.
+    pub has_visible_routes: bool,
+    /// The aircraft the displayed routes are generated for.
+    pub route_source: RouteSource,
+    /// Whether the settings window is shown.
+    pub show_settings: bool,
+    /// Whether the memory diagnostics window is shown.
+    pub show_diagnostics: bool,
+    /// Whether the route map is open.
+    pub map_open: bool,
+    /// Whether the fleet goal window is open.
+    pub goals_open: bool,
+    /// Whether the achievements window is open.
+    pub achievements_open: bool,
+    /// Whether the airport comparison window is open.
+    pub compare_open: bool,
+    /// Whether the report window is open.
+    pub report_open: bool,
+    /// Whether the logbook import window is open.
+    pub logbook_import_open: bool,
+}
+
+/// Work left to the GUI after an action, because it needs the databases,
+/// the route generator or randomness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    ShowRandomAircraft,
+    ShowSuggestedAircraft,
+    ShowRandomAirport,
+    ListAirports,
+    ReloadAirports,
+    ListHistory,
+    /// Replaces the table with routes generated for the route source.
+    GenerateRoutes,
+    OpenCustomRoute,
+    OpenSimMapping,
+    OpenRouteExport,
+    OpenHistoryExport,
+}
+
+impl ViewState {
+    /// Returns whether the action can currently be run.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to check.
+    pub fn is_enabled(&self, action: Action) -> bool {
+        match action {
+            Action::RandomAirport
+            | Action::ListAirports
+            | Action::CompareAirports
+            | Action::RandomRoutes
+            | Action::RandomNotFlownRoutes
+            | Action::LongHaul(_)
+            | Action::CustomRoute
+            | Action::GenerateReport => self.airports_loaded,
+            // Retries the load after it failed.
+            Action::ReloadAirports => self.airports_loaded || !self.loading,
+            Action::SelectedAircraftRoutes => self.airports_loaded && self.aircraft_selected,
+            Action::ExportRoutes => self.has_visible_routes,
+            Action::SelectRandomAircraft
+            | Action::SuggestAircraft
+            | Action::ListHistory
+            | Action::SimulatorAircraft
+            | Action::ExportHistory
+            | Action::ImportLogbook
+            | Action::ToggleSettings
+            | Action::ToggleMap
+            | Action::ToggleGoals
+            | Action::ToggleAchievements
+            | Action::ToggleDiagnostics => true,
+        }
+    }
+
+    /// Applies an action to the state and returns the work left to the GUI.
+    ///
+    /// Disabled actions change nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to apply.
+    pub fn reduce(&mut self, action: Action) -> Option<Command> {
+        if !self.is_enabled(action) {
+            return None;
+        }
+
+        let command = match action {
+            Action::SelectRandomAircraft => Command::ShowRandomAircraft,
+            Action::SuggestAircraft => Command::ShowSuggestedAircraft,
+            Action::RandomAirport => Command::ShowRandomAirport,
+            Action::ListAirports => Command::ListAirports,
+            Action::ReloadAirports => Command::ReloadAirports,
+            Action::ListHistory => Command::ListHistory,
+            Action::RandomRoutes => return self.generate_routes(RouteSource::All),
+            Action::RandomNotFlownRoutes => return self.generate_routes(RouteSource::NotFlown),
+            Action::SelectedAircraftRoutes => return self.generate_routes(RouteSource::Selected),
+            Action::LongHaul(mode) => return self.generate_routes(RouteSource::LongHaul(mode)),
+            Action::CustomRoute => Command::OpenCustomRoute,
+            Action::SimulatorAircraft => Command::OpenSimMapping,
+            Action::ExportRoutes => Command::OpenRouteExport,
+            Action::ExportHistory => Command::OpenHistoryExport,
+            Action::CompareAirports => return open_window(&mut self.compare_open, false),
+            Action::GenerateReport => return open_window(&mut self.report_open, false),
+            Action::ImportLogbook => return open_window(&mut self.logbook_import_open, false),
+            Action::ToggleSettings => return open_window(&mut self.show_settings, true),
+            Action::ToggleMap => return open_window(&mut self.map_open, true),
+            Action::ToggleGoals => return open_window(&mut self.goals_open, true),
+            Action::ToggleAchievements => return open_window(&mut self.achievements_open, true),
+            Action::ToggleDiagnostics => return open_window(&mut self.show_diagnostics, true),
+        };
+
+        Some(command)
+    }
+
+    /// Switches the route source and asks for routes to be generated for it.
+    fn generate_routes(&mut self, source: RouteSource) -> Option<Command> {
+        self.route_source = source;
+        Some(Command::GenerateRoutes)
+    }
+}
+
+/// Opens a window, or closes it again if it toggles. Leaves no work to the GUI.
+///
+/// # Arguments
+///
+/// * `open` - Whether the window is open.
+/// * `toggle` - Whether the action closes the window when it is open.
+fn open_window(open: &mut bool, toggle: bool) -> Option<Command> {
+    *open = !(toggle && *open);
+    None
+}
+
+impl Gui<'_> {
+    /// Returns the part of the state that actions read and change.
+    pub(super) fn view_state(&self) -> ViewState {
+        ViewState {
+            airports_loaded: self.route_generator.is_some(),
+            loading: self.loading_receiver.is_some(),
+            aircraft_selected: !self.route_aircraft_state.selected.is_empty(),
+            has_visible_routes: self.visible_routes().next().is_some(),
+            route_source: self.popup_state.route_source,
+            show_settings: self.show_settings,
+            show_diagnostics: self.show_diagnostics,
+            map_open: self.map_view_state.open,
+            goals_open: self.goals_state.open,
+            achievements_open: self.achievements_state.open,
+            compare_open: self.airport_compare_state.open,
+            report_open: self.report_state.open,
+            logbook_import_open: self.logbook_import_state.open,
+        }
+    }
+
+    /// Writes back the state changed by an action.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The changed state.
+    fn apply_view_state(&mut self, state: ViewState) {
+        self.popup_state.route_source = state.route_source;
+        self.show_settings = state.show_settings;
+        self.show_diagnostics = state.show_diagnostics;
+        self.map_view_state.open = state.map_open;
+        self.goals_state.open = state.goals_open;
+        self.achievements_state.open = state.achievements_open;
+        self.airport_compare_state.open = state.compare_open;
+        self.report_state.open = state.report_open;
+        self.logbook_import_state.open = state.logbook_import_open;
+    }
+
+    /// Returns whether the action can currently be run.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to check.
+    pub(super) fn is_action_enabled(&self, action: Action) -> bool {
+        self.view_state().is_enabled(action)
+    }
+    /// Runs the actions whose keyboard shortcut was pressed this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.popup_state.show_alert {
+            return;
+        }
+
+        for action in Action::ALL {
+            let Some(shortcut) = action.shortcut() else {
+                continue;
+            };
+            if ctx.input_mut(|input| input.consume_shortcut(&shortcut)) {
+                self.run_action(action);
+            }
+        }
+    }
+
+    /// Fills a context menu with every action.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI of the menu.
+    pub(super) fn action_menu(&mut self, ui: &mut egui::Ui) {
+        for action in Action::ALL {
+            let mut button = egui::Button::new(action.label());
+            if let Some(shortcut) = action.shortcut() {
+                button = button.shortcut_text(ui.ctx().format_shortcut(&shortcut));
+            }
+
+            if ui
+                .add_enabled(self.is_action_enabled(action), button)
+                .clicked()
+            {
+                ui.close_menu();
+                self.run_action(action);
+            }
+        }
+    }
+
+    /// Runs the action if it is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to run.
+    pub(super) fn run_action(&mut self, action: Action) {
+        let mut state = self.view_state();
+        if !state.is_enabled(action) {
+            return;
+        }
+        self.action_error = None;
+
+        let command = state.reduce(action);
+        self.apply_view_state(state);
+        if let Some(command) = command {
+            self.run_command(command);
+        }
+    }
+
+    /// Does the work left by an action.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The work to do.
+    fn run_command(&mut self, command: Command) {
+        match command {
+            Command::ShowRandomAircraft => {
+                if let Some(aircraft) = self.all_aircraft.choose(&mut rand::thread_rng()) {
+                    self.displayed_items =
+                        vec![Arc::new(TableItem::Aircraft(Arc::clone(aircraft)))];
+                    self.search_state.query.clear();
+                }
+            }
+            Command::ShowSuggestedAircraft => {
+                if let Some(aircraft) = self.suggest_aircraft() {
+                    self.displayed_items = vec![Arc::new(TableItem::Aircraft(aircraft))];
+                    self.search_state.query.clear();
+                }
+            }
+            Command::ShowRandomAirport => self.show_random_airport(),
+            Command::ListAirports => self.list_airports(),
+            Command::ReloadAirports => self.reload_airports(),
+            Command::ListHistory => match self.database_pool.get_history() {
+                Ok(history) => {
+                    self.displayed_items = history
+                        .into_iter()
+                        .map(|history| Arc::new(TableItem::History(Arc::new(history))))
+                        .collect();
+                    self.search_state.query.clear();
+                }
+                Err(e) => self.show_error(format!("Failed to load history: {}", e)),
+            },
+            Command::GenerateRoutes => {
+                let routes = match self.popup_state.route_source {
+                    RouteSource::All => self.generate_random_routes(),
+                    RouteSource::NotFlown => self.generate_random_not_flown_aircraft_routes(),
+                    RouteSource::Selected => self.generate_selected_aircraft_routes(),
+                    RouteSource::LongHaul(mode) => self.generate_long_haul_routes(mode),
+                };
+                match routes {
+                    Ok(routes) => {
+                        self.displayed_items = routes
+                            .into_iter()
+                            .map(|route| Arc::new(TableItem::Route(Arc::new(route))))
+                            .collect();
+                    }
+                    Err(e) => self.show_error(format!("Failed to generate routes: {}", e)),
+                }
+            }
+            Command::OpenCustomRoute => self.open_custom_route_window(),
+            Command::OpenSimMapping => self.open_sim_mapping_window(),
+            Command::OpenRouteExport => {
+                let routes = self.visible_routes().cloned().collect();
+                self.open_export_window(routes);
+            }
+            Command::OpenHistoryExport => self.open_history_export_window(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Command, ViewState};
+    use crate::gui::RouteSource;
+    use crate::modules::long_haul::LongHaulMode;
+
+    /// Applies actions in order and returns the work they leave to the GUI.
+    fn replay(state: &mut ViewState, actions: &[Action]) -> Vec<Command> {
+        actions
+            .iter()
+            .filter_map(|action| state.reduce(*action))
+            .collect()
+    }
+
+    fn loaded() -> ViewState {
+        ViewState {
+            airports_loaded: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn route_actions_switch_the_route_source() {
+        let mut state = loaded();
+
+        let commands = replay(
+            &mut state,
+            &[
+                Action::RandomNotFlownRoutes,
+                Action::LongHaul(LongHaulMode::Polar),
+            ],
+        );
+
+        assert_eq!(commands, [Command::GenerateRoutes, Command::GenerateRoutes]);
+        assert_eq!(
+            state.route_source,
+            RouteSource::LongHaul(LongHaulMode::Polar)
+        );
+    }
+
+    #[test]
+    fn disabled_actions_change_nothing() {
+        let mut state = ViewState {
+            route_source: RouteSource::NotFlown,
+            ..Default::default()
+        };
+        let before = state;
+
+        let commands = replay(
+            &mut state,
+            &[
+                Action::RandomRoutes,
+                Action::SelectedAircraftRoutes,
+                Action::CompareAirports,
+                Action::ExportRoutes,
+            ],
+        );
+
+        assert!(commands.is_empty());
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn selected_aircraft_routes_need_a_selection() {
+        let mut state = loaded();
+        assert_eq!(state.reduce(Action::SelectedAircraftRoutes), None);
+
+        state.aircraft_selected = true;
+        assert_eq!(
+            state.reduce(Action::SelectedAircraftRoutes),
+            Some(Command::GenerateRoutes)
+        );
+        assert_eq!(state.route_source, RouteSource::Selected);
+    }
+
+    #[test]
+    fn toggles_flip_and_openers_stay_open() {
+        let mut state = loaded();
+
+        let commands = replay(
+            &mut state,
+            &[
+                Action::ToggleMap,
+                Action::ToggleSettings,
+                Action::ToggleMap,
+                Action::GenerateReport,
+                Action::GenerateReport,
+            ],
+        );
+
+        assert!(commands.is_empty());
+        assert!(!state.map_open);
+        assert!(state.show_settings);
+        assert!(state.report_open);
+    }
+
+    #[test]
+    fn loading_actions_leave_their_work_to_the_gui() {
+        let mut state = ViewState {
+            has_visible_routes: true,
+            ..loaded()
+        };
+        let before = state;
+
+        let commands = replay(
+            &mut state,
+            &[
+                Action::ListHistory,
+                Action::ExportRoutes,
+                Action::SelectRandomAircraft,
+            ],
+        );
+
+        assert_eq!(
+            commands,
+            [
+                Command::ListHistory,
+                Command::OpenRouteExport,
+                Command::ShowRandomAircraft
+            ]
+        );
+        assert_eq!(state, before);
+    }
+}
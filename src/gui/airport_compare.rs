@@ -0,0 +1,206 @@
+use super::custom_route::{airport_label, airport_matches};
+use super::searchable_dropdown::SearchableDropdown;
+use super::Gui;
+use crate::models::{Aircraft, Airport, Runway};
+use crate::modules::routes::airport_distance_nm;
+use crate::modules::runway::{can_use_runway, longest_land_runway, AirportKind};
+use eframe::egui;
+use std::sync::Arc;
+
+/// The ID salts of the airport dropdowns, left and right.
+const DROPDOWN_IDS: [&str; 2] = ["airport_compare_left", "airport_compare_right"];
+
+/// State of the airport comparison window.
+#[derive(Default)]
+pub struct AirportCompareState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The two airports compared, side by side.
+    airports: [Option<Arc<Airport>>; 2],
+    /// Search queries of the two airport dropdowns.
+    searches: [String; 2],
+}
+
+/// An airport of the comparison with its runways.
+struct ComparedAirport<'a> {
+    airport: &'a Airport,
+    runways: &'a [Runway],
+    /// The aircraft of the fleet that can use a runway of the airport.
+    usable: Vec<&'a Arc<Aircraft>>,
+}
+
+/// Returns the aircraft of the fleet that can use a runway of the airport.
+///
+/// # Arguments
+///
+/// * `fleet` - The aircraft to check.
+/// * `runways` - The runways of the airport.
+fn usable_aircraft<'a>(fleet: &'a [Arc<Aircraft>], runways: &[Runway]) -> Vec<&'a Arc<Aircraft>> {
+    fleet
+        .iter()
+        .filter(|aircraft| {
+            runways
+                .iter()
+                .any(|runway| can_use_runway(aircraft, runway))
+        })
+        .collect()
+}
+
+impl Gui<'_> {
+    /// Opens the airport comparison window with an airport on the left.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to compare.
+    pub(super) fn open_airport_compare(&mut self, airport: Arc<Airport>) {
+        let state = &mut self.airport_compare_state;
+        state.airports[0] = Some(airport);
+        state.open = true;
+    }
+
+    /// Shows the airport comparison window, which puts the runways, elevation
+    /// and usable aircraft of two airports side by side, together with the
+    /// aircraft of the fleet that can fly between them.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_airport_compare_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.airport_compare_state.open;
+
+        egui::Window::new("Compare airports")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let Some(route_generator) = &self.route_generator else {
+                    ui.label("Loading airports...");
+                    return;
+                };
+                let airport_codes = &self.airport_codes;
+                let matches =
+                    |airport: &Airport, query: &str| airport_matches(airport_codes, airport, query);
+                let state = &mut self.airport_compare_state;
+
+                ui.horizontal(|ui| {
+                    for (side, ((id_salt, search), airport)) in DROPDOWN_IDS
+                        .into_iter()
+                        .zip(&mut state.searches)
+                        .zip(&mut state.airports)
+                        .enumerate()
+                    {
+                        if side > 0 {
+                            ui.label("and");
+                        }
+                        SearchableDropdown::new(
+                            id_salt,
+                            &route_generator.all_airports,
+                            airport_label,
+                            &matches,
+                        )
+                        .show(ui, search, airport);
+                    }
+                });
+
+                let sides = state.airports.each_ref().map(|airport| {
+                    airport.as_ref().map(|airport| {
+                        let runways = route_generator
+                            .all_runways
+                            .get(&airport.ID)
+                            .map_or(&[][..], |runways| runways.as_slice());
+                        ComparedAirport {
+                            airport,
+                            runways,
+                            usable: usable_aircraft(&self.all_aircraft, runways),
+                        }
+                    })
+                });
+
+                egui::Grid::new("airport_compare")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        let row = |ui: &mut egui::Ui,
+                                   title: &str,
+                                   value: &dyn Fn(&ComparedAirport) -> String| {
+                            ui.strong(title);
+                            for side in &sides {
+                                match side {
+                                    Some(side) => ui.label(value(side)),
+                                    None => ui.weak("-"),
+                                };
+                            }
+                            ui.end_row();
+                        };
+
+                        row(ui, "ICAO", &|side| side.airport.ICAO.clone());
+                        row(ui, "Name", &|side| side.airport.Name.clone());
+                        row(ui, "Kind", &|side| {
+                            AirportKind::of(side.runways).display_name().to_string()
+                        });
+                        row(ui, "Elevation", &|side| {
+                            format!("{} ft", side.airport.Elevation)
+                        });
+                        row(ui, "Longest runway", &|side| {
+                            longest_land_runway(side.runways).map_or_else(
+                                || "None".to_string(),
+                                |length| format!("{} ft", length),
+                            )
+                        });
+                        row(ui, "Runways", &|side| {
+                            if side.runways.is_empty() {
+                                return "None known".to_string();
+                            }
+                            side.runways
+                                .iter()
+                                .map(|runway| {
+                                    format!(
+                                        "{} {} ft {}",
+                                        runway.Ident, runway.Length, runway.Surface
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        });
+                        row(ui, "Usable aircraft", &|side| {
+                            format!("{} of {}", side.usable.len(), self.all_aircraft.len())
+                        });
+                    });
+
+                let [Some(first), Some(second)] = &sides else {
+                    return;
+                };
+                let distance = airport_distance_nm(first.airport, second.airport);
+                ui.separator();
+                ui.label(format!("Distance between them: {:.0} NM", distance));
+
+                let between: Vec<&Arc<Aircraft>> = first
+                    .usable
+                    .iter()
+                    .filter(|aircraft| {
+                        second.usable.iter().any(|other| other.id == aircraft.id)
+                            && f64::from(aircraft.aircraft_range) >= distance
+                    })
+                    .copied()
+                    .collect();
+                ui.strong(format!(
+                    "Aircraft that can fly between them ({})",
+                    between.len()
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        if between.is_empty() {
+                            ui.label("None of your aircraft");
+                        }
+                        for aircraft in between {
+                            ui.label(format!(
+                                "{} {} ({} NM range)",
+                                aircraft.manufacturer, aircraft.variant, aircraft.aircraft_range
+                            ));
+                        }
+                    });
+            });
+
+        self.airport_compare_state.open = open;
+    }
+}
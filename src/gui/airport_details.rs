@@ -0,0 +1,173 @@
+use super::Gui;
+use crate::models::{Airport, Frequency, Runway};
+use crate::modules::frequency::format_frequency;
+use crate::modules::region::country_of;
+use crate::modules::routes::airport_distance_nm;
+use crate::modules::runway::AirportKind;
+use crate::modules::solar::{sun_times, SunTimes};
+use crate::traits::{AirportOperations, FrequencyOperations};
+use chrono::Utc;
+use eframe::egui;
+use std::sync::Arc;
+
+/// State of the airport details window.
+#[derive(Default)]
+pub struct AirportDetailsState {
+    /// The airport shown, `None` while the window is closed.
+    airport: Option<Arc<Airport>>,
+    /// The runways of the airport.
+    runways: Vec<Runway>,
+    /// The radio frequencies of the airport.
+    frequencies: Vec<Frequency>,
+}
+
+impl AirportDetailsState {
+    /// Returns whether the window is open.
+    pub fn is_open(&self) -> bool {
+        self.airport.is_some()
+    }
+}
+
+impl Gui<'_> {
+    /// Opens the details window for an airport, loading its runways and frequencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to show.
+    pub(super) fn open_airport_details(&mut self, airport: Arc<Airport>) {
+        let state = &mut self.airport_details_state;
+        state.runways = self
+            .database_pool
+            .get_runways_for_airport(&airport)
+            .unwrap_or_default();
+        state.frequencies = self
+            .database_pool
+            .get_frequencies(&airport)
+            .unwrap_or_else(|e| {
+                log::error!("Failed to load frequencies of {}: {}", airport.ICAO, e);
+                Vec::new()
+            });
+        state.airport = Some(airport);
+    }
+
+    /// Shows the airport details window with the runways and the radio
+    /// frequencies, which can be copied for setting up the radios.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_airport_details_window(&mut self, ctx: &egui::Context) {
+        let home_base = self.home_base();
+        let state = &mut self.airport_details_state;
+        let Some(airport) = state.airport.clone() else {
+            return;
+        };
+        let mut open = true;
+        let mut compare = false;
+
+        egui::Window::new(format!("{} details", airport.ICAO))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading(&airport.Name);
+                let kind = AirportKind::of(&state.runways);
+                if kind != AirportKind::Airport {
+                    ui.label(kind.display_name());
+                }
+                match self.airport_codes.iata(&airport.ICAO) {
+                    Some(iata) => ui.label(format!("ICAO {} / IATA {}", airport.ICAO, iata)),
+                    None => ui.label(format!("ICAO {}", airport.ICAO)),
+                };
+                if let Some(country) = country_of(&airport.ICAO) {
+                    ui.label(country.name);
+                }
+                ui.label(format!(
+                    "{:.4}, {:.4}, elevation {} ft",
+                    airport.Latitude, airport.Longtitude, airport.Elevation
+                ));
+                match sun_times(
+                    airport.Latitude,
+                    airport.Longtitude,
+                    Utc::now().date_naive(),
+                ) {
+                    SunTimes::Times { sunrise, sunset } => ui.label(format!(
+                        "Sunrise {} UTC, sunset {} UTC",
+                        sunrise.format("%H:%M"),
+                        sunset.format("%H:%M")
+                    )),
+                    SunTimes::PolarDay => ui.label("The sun does not set today"),
+                    SunTimes::PolarNight => ui.label("The sun does not rise today"),
+                };
+                if let Some(transition_altitude) = airport.TransitionAltitude {
+                    ui.label(format!("Transition altitude {} ft", transition_altitude));
+                }
+                match &home_base {
+                    Some(home) if home.ID == airport.ID => {
+                        ui.label("Home base");
+                    }
+                    Some(home) => {
+                        ui.label(format!(
+                            "{:.0} NM from the home base {}",
+                            airport_distance_nm(home, &airport),
+                            home.ICAO
+                        ));
+                    }
+                    None => {}
+                }
+                compare = ui
+                    .button("Compare")
+                    .on_hover_text("Put the airport side by side with another airport")
+                    .clicked();
+
+                ui.separator();
+                ui.strong("Runways");
+                if state.runways.is_empty() {
+                    ui.label("No runways known");
+                }
+                egui::Grid::new("airport_details_runways")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for runway in &state.runways {
+                            ui.label(&runway.Ident);
+                            ui.label(format!("{} ft", runway.Length));
+                            ui.label(&runway.Surface);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.strong("Frequencies");
+                if state.frequencies.is_empty() {
+                    ui.label("No frequencies known")
+                        .on_hover_text("Import them with `flight-planner frequencies import`");
+                }
+                egui::Grid::new("airport_details_frequencies")
+                    .striped(true)
+                    .num_columns(4)
+                    .show(ui, |ui| {
+                        for frequency in &state.frequencies {
+                            let tuned = format_frequency(frequency.frequency_khz);
+                            ui.label(&frequency.kind);
+                            ui.monospace(&tuned);
+                            ui.label(&frequency.description);
+                            if ui
+                                .small_button("Copy")
+                                .on_hover_text("Copy the frequency to the clipboard")
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(tuned);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if !open {
+            *state = AirportDetailsState::default();
+        }
+        if compare {
+            self.open_airport_compare(airport);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::{Gui, TableItem};
+use std::sync::Arc;
+
+/// The number of airports added to the table at a time.
+const AIRPORT_PAGE_SIZE: usize = 500;
+
+/// Every airport of the database, listed in the table a page at a time.
+pub struct AirportList {
+    /// Every airport as a table item.
+    items: Vec<Arc<TableItem>>,
+    /// The number of airports shown, growing a page at a time.
+    shown: usize,
+}
+
+impl AirportList {
+    /// Returns whether another page can be shown, which is the case while
+    /// airports are left and the table is filled up to the pages shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `listed` - The number of airports in the table after searching.
+    pub fn can_load_more(&self, listed: usize) -> bool {
+        self.shown < self.items.len() && listed >= self.shown
+    }
+}
+
+impl Gui<'_> {
+    /// Lists every airport in the table, starting with the first page.
+    pub(super) fn list_airports(&mut self) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let items: Vec<Arc<TableItem>> = route_generator
+            .all_airports
+            .iter()
+            .map(|airport| Arc::new(TableItem::Airport(Arc::clone(airport))))
+            .collect();
+        let shown = items.len().min(AIRPORT_PAGE_SIZE);
+
+        self.displayed_items = items[..shown].to_vec();
+        self.airport_list = Some(AirportList { items, shown });
+        self.search_state.query.clear();
+    }
+
+    /// Returns the list of every airport while the table shows it.
+    pub(super) fn paged_airports(&self) -> Option<&AirportList> {
+        self.airport_list.as_ref().filter(|list| {
+            list.items
+                .first()
+                .zip(self.displayed_items.first())
+                .is_some_and(|(listed, displayed)| Arc::ptr_eq(listed, displayed))
+        })
+    }
+
+    /// Shows the next page of airports, if the table lists every airport.
+    pub(super) fn load_more_airports(&mut self) {
+        if self.paged_airports().is_none() {
+            return;
+        }
+        let Some(list) = &mut self.airport_list else {
+            return;
+        };
+
+        let shown = (list.shown + AIRPORT_PAGE_SIZE).min(list.items.len());
+        self.displayed_items
+            .extend(list.items[list.shown..shown].iter().map(Arc::clone));
+        list.shown = shown;
+    }
+
+    /// Filters and sorts every airport, then keeps the pages shown, so that
+    /// searching and sorting cover the airports not shown yet.
+    ///
+    /// # Returns
+    ///
+    /// Whether the table lists every airport, leaving the other items alone if not.
+    pub(super) fn filter_paged_airports(&mut self) -> bool {
+        let Some(list) = self.paged_airports() else {
+            return false;
+        };
+        if self.search_state.query.is_empty() && self.search_state.sort.is_none() {
+            return false;
+        }
+
+        let mut items: Vec<Arc<TableItem>> = list
+            .items
+            .iter()
+            .filter(|item| {
+                self.search_state.query.is_empty()
+                    || item.matches_query(&self.search_state.query, &self.airport_codes)
+            })
+            .map(Arc::clone)
+            .collect();
+        self.sort_table_items(&mut items);
+        items.truncate(list.shown);
+        self.search_state.filtered_items = items;
+
+        true
+    }
+}
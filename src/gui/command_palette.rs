@@ -0,0 +1,176 @@
+use super::actions::Action;
+use super::Gui;
+use eframe::egui;
+
+/// State of the command palette.
+#[derive(Default)]
+pub struct CommandPaletteState {
+    /// Whether the palette is open.
+    open: bool,
+    /// The text typed into the palette.
+    query: String,
+    /// The index of the highlighted match.
+    selected: usize,
+}
+
+impl Gui<'_> {
+    /// Opens the command palette on Ctrl+P and shows it while it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn handle_command_palette(&mut self, ctx: &egui::Context) {
+        if self.popup_state.show_alert {
+            return;
+        }
+
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            let state = &mut self.command_palette_state;
+            state.open = !state.open;
+            state.query.clear();
+            state.selected = 0;
+        }
+
+        if self.command_palette_state.open {
+            self.show_command_palette(ctx);
+        }
+    }
+
+    /// Shows the command palette and runs the chosen action.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        let matches = matching_actions(&self.command_palette_state.query);
+        let (up, down, enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        let state = &mut self.command_palette_state;
+        if escape {
+            state.open = false;
+            return;
+        }
+        if down && state.selected + 1 < matches.len() {
+            state.selected += 1;
+        }
+        if up {
+            state.selected = state.selected.saturating_sub(1);
+        }
+        state.selected = state.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = enter
+            .then(|| matches.get(state.selected).copied())
+            .flatten();
+        let enabled: Vec<bool> = matches
+            .iter()
+            .map(|action| self.is_action_enabled(*action))
+            .collect();
+        let state = &mut self.command_palette_state;
+
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .fixed_size([400.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut state.query)
+                        .hint_text("Type a command...")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    state.selected = 0;
+                }
+                ui.separator();
+
+                if matches.is_empty() {
+                    ui.weak("No matching commands");
+                }
+                for (index, action) in matches.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = ui
+                            .add_enabled(
+                                enabled[index],
+                                egui::SelectableLabel::new(index == state.selected, action.label()),
+                            )
+                            .on_hover_text(action.description());
+                        if label.clicked() {
+                            chosen = Some(*action);
+                        }
+                        if let Some(shortcut) = action.shortcut() {
+                            ui.weak(ui.ctx().format_shortcut(&shortcut));
+                        }
+                    });
+                }
+            });
+
+        if let Some(action) = chosen {
+            self.command_palette_state.open = false;
+            self.run_action(action);
+        }
+    }
+}
+
+/// Returns the actions matching the query, best match first.
+///
+/// # Arguments
+///
+/// * `query` - The text typed into the palette.
+fn matching_actions(query: &str) -> Vec<Action> {
+    let mut scored: Vec<(i32, Action)> = Action::ALL
+        .iter()
+        .filter_map(|action| fuzzy_score(query, action.label()).map(|score| (score, *action)))
+        .collect();
+
+    // A stable sort keeps the registry order for equal scores.
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, action)| action).collect()
+}
+
+/// Scores how well the query matches the text, or returns `None` if the
+/// characters of the query do not all appear in order in the text.
+///
+/// Consecutive characters and characters at the start of a word score higher,
+/// so `rr` ranks "Random route" above "Reload airports".
+///
+/// # Arguments
+///
+/// * `query` - The text typed by the user.
+/// * `text` - The text to match against.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut text_chars = text.char_indices();
+    let mut previous_char = ' ';
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_ascii_lowercase();
+        loop {
+            let (index, text_char) = text_chars.next()?;
+            let at_word_start = !previous_char.is_alphanumeric();
+            previous_char = text_char;
+
+            if text_char.to_ascii_lowercase() == query_char {
+                score += 1;
+                if at_word_start {
+                    score += 3;
+                }
+                if previous_match.is_some_and(|previous| previous + 1 == index) {
+                    score += 2;
+                }
+                previous_match = Some(index);
+                break;
+            }
+        }
+    }
+
+    Some(score)
+}
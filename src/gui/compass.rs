@@ -0,0 +1,75 @@
+use crate::modules::routes::CompassSector;
+use eframe::egui;
+
+/// The sectors laid out as on a compass rose, with `None` in the middle for any direction.
+const LAYOUT: [[Option<CompassSector>; 3]; 3] = [
+    [
+        Some(CompassSector::NorthWest),
+        Some(CompassSector::North),
+        Some(CompassSector::NorthEast),
+    ],
+    [Some(CompassSector::West), None, Some(CompassSector::East)],
+    [
+        Some(CompassSector::SouthWest),
+        Some(CompassSector::South),
+        Some(CompassSector::SouthEast),
+    ],
+];
+
+/// The width and height of each button of the compass.
+const BUTTON_SIZE: f32 = 32.0;
+
+/// Shows a compass rose for picking the direction of generated destinations.
+///
+/// # Arguments
+///
+/// * `ui` - The UI context.
+/// * `id_salt` - The unique ID of the compass.
+/// * `direction` - The selected sector, or `None` for any direction.
+///
+/// # Returns
+///
+/// Whether the selection changed.
+pub fn compass(ui: &mut egui::Ui, id_salt: &str, direction: &mut Option<CompassSector>) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new(id_salt).spacing([2.0, 2.0]).show(ui, |ui| {
+        for row in LAYOUT {
+            for sector in row {
+                let (text, hover_text) = match sector {
+                    Some(sector) => (
+                        sector.abbreviation(),
+                        format!(
+                            "Destinations to the {} ({:.0}° ± 22.5°)",
+                            sector.abbreviation(),
+                            sector.center_bearing()
+                        ),
+                    ),
+                    None => ("Any", "Destinations in any direction".to_string()),
+                };
+
+                let response = ui
+                    .add_sized(
+                        [BUTTON_SIZE, BUTTON_SIZE],
+                        egui::SelectableLabel::new(*direction == sector, text),
+                    )
+                    .on_hover_text(&hover_text);
+                response.widget_info(|| {
+                    egui::WidgetInfo::selected(
+                        egui::WidgetType::SelectableLabel,
+                        ui.is_enabled(),
+                        *direction == sector,
+                        &hover_text,
+                    )
+                });
+                if response.clicked() && *direction != sector {
+                    *direction = sector;
+                    changed = true;
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    changed
+}
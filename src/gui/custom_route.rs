@@ -0,0 +1,272 @@
+use super::mark_flown::MarkFlownForm;
+use super::searchable_dropdown::SearchableDropdown;
+use super::{Gui, TableItem};
+use crate::models::{Aircraft, Airport};
+use crate::modules::airport::AirportCodes;
+use crate::modules::routes::{format_altitude, format_duration_hours, Route};
+use crate::traits::HistoryOperations;
+use eframe::egui;
+use std::sync::Arc;
+
+/// The number of airports in the recent and nearest sections of the departure dropdown.
+const SUGGESTED_AIRPORTS: usize = 5;
+
+/// State of the custom route dialog.
+#[derive(Default)]
+pub struct CustomRouteState {
+    /// Whether the dialog is open.
+    pub open: bool,
+    /// The selected departure airport.
+    departure: Option<Arc<Airport>>,
+    /// The selected destination airport.
+    destination: Option<Arc<Airport>>,
+    /// The selected aircraft.
+    aircraft: Option<Arc<Aircraft>>,
+    /// Search query of the departure dropdown.
+    departure_search: String,
+    /// Search query of the destination dropdown.
+    destination_search: String,
+    /// Search query of the aircraft dropdown.
+    aircraft_search: String,
+    /// Whether the aircraft dropdown only lists aircraft that have not been flown.
+    aircraft_not_flown_only: bool,
+    /// Airports from the most recent flights, newest first.
+    recent_airports: Vec<Arc<Airport>>,
+    /// Airports closest to the arrival airport of the last flight.
+    nearest_airports: Vec<Arc<Airport>>,
+}
+
+/// An action requested from the custom route dialog.
+enum CustomRouteAction {
+    /// Add the route to the route list.
+    AddToList(Route),
+    /// Mark the route as flown.
+    MarkFlown(Route),
+}
+
+impl Gui<'_> {
+    /// Opens the custom route dialog, suggesting departures based on the flight history.
+    ///
+    /// The departure starts at the home base, unless one was already picked.
+    pub(super) fn open_custom_route_window(&mut self) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let history = self.database_pool.get_history().unwrap_or_else(|e| {
+            log::error!("Failed to load history: {}", e);
+            Vec::new()
+        });
+
+        let mut recent_airports: Vec<Arc<Airport>> = Vec::new();
+        for icao in history
+            .iter()
+            .flat_map(|record| [&record.arrival_icao, &record.departure_icao])
+        {
+            if recent_airports.len() == SUGGESTED_AIRPORTS {
+                break;
+            }
+            let icao = self.airport_codes.current_icao(icao);
+            if let Some(airport) = route_generator.find_airport_by_icao(icao) {
+                if !recent_airports.iter().any(|a| Arc::ptr_eq(a, airport)) {
+                    recent_airports.push(Arc::clone(airport));
+                }
+            }
+        }
+
+        let nearest_airports = history
+            .first()
+            .and_then(|record| {
+                route_generator
+                    .find_airport_by_icao(self.airport_codes.current_icao(&record.arrival_icao))
+            })
+            .map(|arrival| route_generator.nearest_airports(arrival, SUGGESTED_AIRPORTS))
+            .unwrap_or_default();
+
+        let home_base = self.home_base();
+        let state = &mut self.custom_route_state;
+        if state.departure.is_none() {
+            state.departure = home_base;
+        }
+        state.recent_airports = recent_airports;
+        state.nearest_airports = nearest_airports;
+        state.open = true;
+    }
+
+    /// Shows the dialog for building a route by hand.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_custom_route_window(&mut self, ctx: &egui::Context) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let airport_codes = &self.airport_codes;
+        let matches =
+            |airport: &Airport, query: &str| airport_matches(airport_codes, airport, query);
+        let state = &mut self.custom_route_state;
+        let mut open = state.open;
+        let mut action = None;
+
+        egui::Window::new("Custom route")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("custom_route_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        let departure_caption = ui.label("Departure:");
+                        SearchableDropdown::new(
+                            "custom_route_departure",
+                            &route_generator.all_airports,
+                            airport_label,
+                            &matches,
+                        )
+                        .labelled_by(departure_caption.id)
+                        .section("Recently used", &state.recent_airports)
+                        .section("Nearest to last arrival", &state.nearest_airports)
+                        .show(
+                            ui,
+                            &mut state.departure_search,
+                            &mut state.departure,
+                        );
+                        ui.end_row();
+
+                        let destination_caption = ui.label("Destination:");
+                        SearchableDropdown::new(
+                            "custom_route_destination",
+                            &route_generator.all_airports,
+                            airport_label,
+                            &matches,
+                        )
+                        .labelled_by(destination_caption.id)
+                        .show(
+                            ui,
+                            &mut state.destination_search,
+                            &mut state.destination,
+                        );
+                        ui.end_row();
+
+                        let aircraft_caption = ui.label("Aircraft:");
+                        SearchableDropdown::new(
+                            "custom_route_aircraft",
+                            &self.all_aircraft,
+                            aircraft_label,
+                            &aircraft_matches,
+                        )
+                        .labelled_by(aircraft_caption.id)
+                        .grouped_by(aircraft_manufacturer)
+                        .filter(
+                            "Not flown only",
+                            aircraft_not_flown,
+                            &mut state.aircraft_not_flown_only,
+                        )
+                        .show(
+                            ui,
+                            &mut state.aircraft_search,
+                            &mut state.aircraft,
+                        );
+                        ui.end_row();
+                    });
+
+                let (Some(departure), Some(destination), Some(aircraft)) =
+                    (&state.departure, &state.destination, &state.aircraft)
+                else {
+                    return;
+                };
+
+                let route = route_generator.create_route(
+                    Arc::clone(departure),
+                    Arc::clone(destination),
+                    Arc::clone(aircraft),
+                );
+
+                ui.separator();
+                ui.label(format!("Distance: {:.0} NM", route.distance_nm()));
+                if let Some(ete) = route.ete_hours() {
+                    ui.label(format!(
+                        "Estimated time en route: {}",
+                        format_duration_hours(ete)
+                    ));
+                }
+                ui.label(format!(
+                    "Suggested cruise altitude: {}",
+                    format_altitude(route.suggested_altitude_ft())
+                ));
+
+                for issue in route.issues_for(aircraft) {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", issue));
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Add to route list").clicked() {
+                        action = Some(CustomRouteAction::AddToList(route.clone()));
+                    }
+                    if ui.button("Mark as flown").clicked() {
+                        action = Some(CustomRouteAction::MarkFlown(route.clone()));
+                    }
+                });
+            });
+
+        self.custom_route_state.open = open;
+
+        match action {
+            Some(CustomRouteAction::AddToList(route)) => {
+                if !matches!(
+                    self.displayed_items.first().map(|item| item.as_ref()),
+                    Some(TableItem::Route(_))
+                ) {
+                    self.displayed_items.clear();
+                }
+                self.displayed_items
+                    .push(Arc::new(TableItem::Route(Arc::new(route))));
+            }
+            Some(CustomRouteAction::MarkFlown(route)) => {
+                // Confirm the date and notes in the route popup.
+                let route = Arc::new(route);
+                self.popup_state.mark_flown = Some(MarkFlownForm::new(Arc::clone(&route)));
+                self.popup_state.selected_route = Some(route);
+                self.popup_state.show_alert = true;
+                self.custom_route_state.open = false;
+            }
+            None => {}
+        }
+    }
+}
+
+pub(super) fn airport_label(airport: &Airport) -> String {
+    format!("{} ({})", airport.ICAO, airport.Name)
+}
+
+/// Returns whether the ICAO code, the name, the IATA code or a former ICAO
+/// code of an airport contains the query.
+///
+/// # Arguments
+///
+/// * `codes` - The IATA codes and former ICAO codes of the airports.
+/// * `airport` - The airport to check.
+/// * `query` - The lowercase search query.
+pub(super) fn airport_matches(codes: &AirportCodes, airport: &Airport, query: &str) -> bool {
+    airport.ICAO.to_lowercase().contains(query)
+        || airport.Name.to_lowercase().contains(query)
+        || codes.matches(airport, query)
+}
+
+pub(super) fn aircraft_manufacturer(aircraft: &Aircraft) -> String {
+    aircraft.manufacturer.clone()
+}
+
+pub(super) fn aircraft_not_flown(aircraft: &Aircraft) -> bool {
+    aircraft.flown == 0
+}
+
+pub(super) fn aircraft_label(aircraft: &Aircraft) -> String {
+    format!("{} {}", aircraft.manufacturer, aircraft.variant)
+}
+
+pub(super) fn aircraft_matches(aircraft: &Aircraft, query: &str) -> bool {
+    aircraft.manufacturer.to_lowercase().contains(query)
+        || aircraft.variant.to_lowercase().contains(query)
+        || aircraft.icao_code.to_lowercase().contains(query)
+}
@@ -0,0 +1,226 @@
+use super::{Gui, TableItem};
+use crate::models::{Aircraft, Airport};
+use crate::modules::routes::{Route, RouteGenerator, SpatialAirport};
+use eframe::egui;
+use std::collections::HashMap;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+/// Size of the reference counts stored alongside every `Arc` allocation.
+const ARC_HEADER: usize = 2 * size_of::<usize>();
+
+/// A row in the memory diagnostics panel.
+struct MemoryEntry {
+    /// The name of the structure.
+    name: &'static str,
+    /// The number of elements in the structure.
+    count: usize,
+    /// The approximate heap size in bytes.
+    bytes: usize,
+}
+
+impl Gui<'_> {
+    /// Shows the memory diagnostics window.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_diagnostics_window(&mut self, ctx: &egui::Context) {
+        let entries = self.memory_entries();
+        let total: usize = entries.iter().map(|entry| entry.bytes).sum();
+
+        egui::Window::new("Memory diagnostics")
+            .open(&mut self.show_diagnostics)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("memory_diagnostics")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.strong("Structure");
+                        ui.strong("Count");
+                        ui.strong("Approx. memory");
+                        ui.end_row();
+
+                        for entry in &entries {
+                            ui.label(entry.name);
+                            ui.label(entry.count.to_string());
+                            ui.label(format_bytes(entry.bytes));
+                            ui.end_row();
+                        }
+
+                        ui.strong("Total");
+                        ui.label("");
+                        ui.strong(format_bytes(total));
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                ui.label("Sizes are estimates of the data held in memory, not process usage.");
+            });
+    }
+
+    /// Collects the approximate memory usage of the large in-memory structures.
+    fn memory_entries(&self) -> Vec<MemoryEntry> {
+        let mut entries = vec![MemoryEntry {
+            name: "Aircraft",
+            count: self.all_aircraft.len(),
+            bytes: self
+                .all_aircraft
+                .iter()
+                .map(|a| aircraft_bytes(a))
+                .sum::<usize>()
+                + vec_bytes(&self.all_aircraft),
+        }];
+
+        if let Some(route_generator) = &self.route_generator {
+            entries.extend(route_generator_entries(route_generator));
+        }
+
+        entries.push(MemoryEntry {
+            name: "Displayed items",
+            count: self.displayed_items.len(),
+            bytes: items_bytes(&self.displayed_items),
+        });
+        entries.push(MemoryEntry {
+            name: "Search results",
+            count: self.search_state.filtered_items.len(),
+            bytes: vec_bytes(&self.search_state.filtered_items),
+        });
+
+        entries
+    }
+}
+
+/// Returns the entries for the airports, runways and indexes of the route generator.
+///
+/// # Arguments
+///
+/// * `route_generator` - The route generator to measure.
+fn route_generator_entries(route_generator: &RouteGenerator) -> Vec<MemoryEntry> {
+    let runway_count: usize = route_generator.all_runways.values().map(|r| r.len()).sum();
+    let runway_bytes: usize = route_generator
+        .all_runways
+        .values()
+        .map(|runways| {
+            ARC_HEADER
+                + vec_bytes(runways)
+                + runways
+                    .iter()
+                    .map(|r| r.Ident.capacity() + r.Surface.capacity())
+                    .sum::<usize>()
+        })
+        .sum::<usize>()
+        + map_bytes(&route_generator.all_runways);
+
+    vec![
+        MemoryEntry {
+            name: "Airports",
+            count: route_generator.all_airports.len(),
+            bytes: route_generator
+                .all_airports
+                .iter()
+                .map(|a| airport_bytes(a))
+                .sum::<usize>()
+                + vec_bytes(&route_generator.all_airports),
+        },
+        MemoryEntry {
+            name: "Runways",
+            count: runway_count,
+            bytes: runway_bytes,
+        },
+        MemoryEntry {
+            name: "Spatial index",
+            count: route_generator.spatial_airports.size(),
+            // Leaves plus roughly one internal node entry for every few leaves.
+            bytes: route_generator.spatial_airports.size()
+                * (size_of::<SpatialAirport>() + size_of::<[f64; 4]>())
+                * 5
+                / 4,
+        },
+        MemoryEntry {
+            name: "Runway length cache",
+            count: route_generator.longest_runways.len(),
+            bytes: map_bytes(&route_generator.longest_runways)
+                + vec_bytes(&route_generator.sorted_airports),
+        },
+    ]
+}
+
+/// Returns the approximate size of the table items, excluding data shared with other structures.
+///
+/// # Arguments
+///
+/// * `items` - The table items.
+fn items_bytes(items: &Vec<Arc<TableItem>>) -> usize {
+    let item_bytes: usize = items
+        .iter()
+        .map(|item| {
+            ARC_HEADER
+                + size_of::<TableItem>()
+                + match item.as_ref() {
+                    TableItem::Route(_) => ARC_HEADER + size_of::<Route>(),
+                    TableItem::History(history) => {
+                        ARC_HEADER
+                            + size_of_val(history.as_ref())
+                            + history.departure_icao.capacity()
+                            + history.arrival_icao.capacity()
+                            + history.date.capacity()
+                            + history.notes.as_ref().map_or(0, String::capacity)
+                            + history
+                                .planned_arrival_icao
+                                .as_ref()
+                                .map_or(0, String::capacity)
+                    }
+                    TableItem::Airport(_) | TableItem::Aircraft(_) => 0,
+                }
+        })
+        .sum();
+
+    item_bytes + vec_bytes(items)
+}
+
+fn airport_bytes(airport: &Airport) -> usize {
+    ARC_HEADER + size_of::<Airport>() + airport.Name.capacity() + airport.ICAO.capacity()
+}
+
+fn aircraft_bytes(aircraft: &Aircraft) -> usize {
+    ARC_HEADER
+        + size_of::<Aircraft>()
+        + aircraft.manufacturer.capacity()
+        + aircraft.variant.capacity()
+        + aircraft.icao_code.capacity()
+        + aircraft.category.capacity()
+        + aircraft.date_flown.as_ref().map_or(0, String::capacity)
+}
+
+fn vec_bytes<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * size_of::<T>()
+}
+
+fn map_bytes<K, V>(map: &HashMap<K, V>) -> usize {
+    // One control byte per bucket in addition to the key and value.
+    map.capacity() * (size_of::<K>() + size_of::<V>() + 1)
+}
+
+/// Formats a byte count using binary units.
+///
+/// # Arguments
+///
+/// * `bytes` - The number of bytes.
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
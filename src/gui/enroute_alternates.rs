@@ -0,0 +1,146 @@
+use super::Gui;
+use crate::modules::routes::{CoverageGap, EnrouteAlternate, Route};
+use eframe::egui;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// The allowed distance off the path of a route in nautical miles.
+const CORRIDOR_RANGE: RangeInclusive<u32> = 5..=200;
+
+/// The airports along the route shown in the popup, and the stretches out of reach of them.
+pub struct EnrouteAlternatesState {
+    /// The largest distance off the path in nautical miles.
+    corridor_nm: u32,
+    /// The route and corridor the alternates were found for, with the alternates.
+    found: Option<(Arc<Route>, u32, Vec<EnrouteAlternate>)>,
+    /// The route and diversion distance the coverage was checked for, with
+    /// the stretches out of reach of an airport.
+    coverage: Option<(Arc<Route>, u32, Vec<CoverageGap>)>,
+}
+
+impl Default for EnrouteAlternatesState {
+    fn default() -> Self {
+        EnrouteAlternatesState {
+            corridor_nm: 50,
+            found: None,
+            coverage: None,
+        }
+    }
+}
+
+impl Gui<'_> {
+    /// Shows the "En-route alternates" section of the route popup, listing the
+    /// airports near the great circle path the aircraft could divert to.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    /// * `route` - The route shown in the popup.
+    pub(super) fn show_enroute_alternates(&mut self, ui: &mut egui::Ui, route: &Arc<Route>) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let state = &mut self.enroute_alternates_state;
+
+        egui::CollapsingHeader::new("En-route alternates").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Within");
+                ui.add(
+                    egui::DragValue::new(&mut state.corridor_nm)
+                        .range(CORRIDOR_RANGE)
+                        .speed(1)
+                        .suffix(" NM"),
+                )
+                .on_hover_text("Largest distance off the great circle path");
+                ui.label("of the path");
+            });
+
+            // The search only runs again for another route or corridor.
+            let corridor_nm = state.corridor_nm;
+            let alternates = match &state.found {
+                Some((found_route, found_corridor, alternates))
+                    if Arc::ptr_eq(found_route, route) && *found_corridor == corridor_nm =>
+                {
+                    alternates
+                }
+                _ => {
+                    let alternates =
+                        route_generator.enroute_alternates(route, f64::from(corridor_nm));
+                    &state
+                        .found
+                        .insert((Arc::clone(route), corridor_nm, alternates))
+                        .2
+                }
+            };
+
+            if alternates.is_empty() {
+                ui.label("No airports with a usable runway near the path");
+                return;
+            }
+            ui.label(format!("{} airports", alternates.len()));
+            egui::ScrollArea::vertical()
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("enroute_alternates")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for alternate in alternates {
+                                ui.label(&alternate.airport.ICAO);
+                                ui.label(&alternate.airport.Name);
+                                ui.label(format!(
+                                    "{:.0} NM along, {:.0} NM off",
+                                    alternate.along_track_nm, alternate.cross_track_nm
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+
+    /// Warns about the stretches of a route farther than the diversion
+    /// distance of the settings from every airport the aircraft can use.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    /// * `route` - The route shown in the popup.
+    pub(super) fn show_diversion_coverage(&mut self, ui: &mut egui::Ui, route: &Arc<Route>) {
+        let diversion_nm = self.settings.diversion_limit_nm;
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        if diversion_nm == 0 {
+            return;
+        }
+        let state = &mut self.enroute_alternates_state;
+
+        // The check only runs again for another route or diversion distance.
+        let gaps = match &state.coverage {
+            Some((checked_route, checked_nm, gaps))
+                if Arc::ptr_eq(checked_route, route) && *checked_nm == diversion_nm =>
+            {
+                gaps
+            }
+            _ => {
+                let gaps = route_generator.coverage_gaps(route, f64::from(diversion_nm));
+                &state
+                    .coverage
+                    .insert((Arc::clone(route), diversion_nm, gaps))
+                    .2
+            }
+        };
+
+        for gap in gaps {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "⚠ No airport within {} NM from {:.0} to {:.0} NM along the route",
+                    diversion_nm, gap.start_nm, gap.end_nm
+                ),
+            )
+            .on_hover_text("Set the diversion distance in the settings");
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::Gui;
+use crate::models::AircraftSimTitle;
+use crate::modules::export::{export_routes, ExportFormat};
+use crate::modules::routes::Route;
+use crate::traits::SimulatorMappingOperations;
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// State of the route export window.
+pub struct ExportState {
+    /// The routes to export, empty while the window is closed.
+    routes: Vec<Arc<Route>>,
+    /// The simulator titles of the aircraft, named in the route descriptions.
+    sim_titles: Vec<AircraftSimTitle>,
+    /// The file name to save to, without the extension.
+    file_name: String,
+    /// The format to export to.
+    format: ExportFormat,
+    /// The outcome of the last save, the path written or the error.
+    saved: Option<Result<PathBuf, String>>,
+}
+
+impl Default for ExportState {
+    fn default() -> Self {
+        ExportState {
+            routes: Vec::new(),
+            sim_titles: Vec::new(),
+            file_name: "routes".to_string(),
+            format: ExportFormat::default(),
+            saved: None,
+        }
+    }
+}
+
+impl ExportState {
+    /// Returns whether the window is open.
+    pub fn is_open(&self) -> bool {
+        !self.routes.is_empty()
+    }
+}
+
+impl Gui<'_> {
+    /// Opens the export window for routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The routes to export.
+    pub(super) fn open_export_window(&mut self, routes: Vec<Arc<Route>>) {
+        let sim_titles = self.database_pool.get_sim_titles().unwrap_or_else(|e| {
+            log::error!("Failed to load simulator titles: {}", e);
+            Vec::new()
+        });
+        let state = &mut self.export_state;
+        state.sim_titles = sim_titles;
+        if let [route] = routes.as_slice() {
+            state.file_name = format!("{}-{}", route.departure.ICAO, route.destination.ICAO);
+        }
+        state.routes = routes;
+        state.saved = None;
+    }
+
+    /// Shows the export window, which saves routes as GPX or KML for viewing
+    /// in GPS tools and Google Earth.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_export_window(&mut self, ctx: &egui::Context) {
+        let state = &mut self.export_state;
+        let mut open = true;
+
+        egui::Window::new("Export routes")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                match state.routes.as_slice() {
+                    [route] => ui.label(format!(
+                        "{} to {}",
+                        route.departure.ICAO, route.destination.ICAO
+                    )),
+                    routes => ui.label(format!("{} routes", routes.len())),
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    for format in ExportFormat::ALL {
+                        ui.selectable_value(&mut state.format, format, format.display_name());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let label = ui.label("File name:");
+                    ui.add(egui::TextEdit::singleline(&mut state.file_name).desired_width(160.0))
+                        .labelled_by(label.id);
+                    ui.label(format!(".{}", state.format.extension()));
+                });
+
+                let file_name = state.file_name.trim();
+                if ui
+                    .add_enabled(!file_name.is_empty(), egui::Button::new("Save"))
+                    .on_hover_text("Save in the working directory")
+                    .clicked()
+                {
+                    let path = PathBuf::from(format!("{}.{}", file_name, state.format.extension()));
+                    let routes: Vec<&Route> = state.routes.iter().map(Arc::as_ref).collect();
+                    state.saved = Some(
+                        std::fs::write(
+                            &path,
+                            export_routes(&routes, state.format, &state.sim_titles),
+                        )
+                        .map(|()| std::path::absolute(&path).unwrap_or(path))
+                        .map_err(|e| format!("Failed to export the routes: {}", e)),
+                    );
+                }
+
+                match &state.saved {
+                    Some(Ok(path)) => {
+                        ui.label(format!("Saved to {}", path.display()));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => {}
+                }
+            });
+
+        if !open {
+            state.routes.clear();
+        }
+    }
+}
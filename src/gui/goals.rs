@@ -0,0 +1,262 @@
+use super::{show_field_error, Gui};
+use crate::models::Aircraft;
+use crate::modules::validation::{parse_period, FieldErrors};
+use crate::SETTINGS_FILENAME;
+use chrono::{Datelike, NaiveDate};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The number of pending aircraft named in the reminder banner.
+const BANNER_AIRCRAFT: usize = 5;
+
+/// A goal of flying every aircraft at least once within a period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FleetGoal {
+    /// The first day flights count towards the goal, `YYYY-MM-DD`.
+    pub start: String,
+    /// The last day of the goal, `YYYY-MM-DD`.
+    pub deadline: String,
+    /// How many days before the deadline the reminder banner appears.
+    pub reminder_days: u32,
+}
+
+impl FleetGoal {
+    /// Returns a goal running from 1 January to 31 December of the year of the date.
+    ///
+    /// # Arguments
+    ///
+    /// * `today` - A date in the year of the goal.
+    pub fn for_year(today: NaiveDate) -> FleetGoal {
+        FleetGoal {
+            start: format!("{}-01-01", today.year()),
+            deadline: format!("{}-12-31", today.year()),
+            reminder_days: 60,
+        }
+    }
+
+    /// Returns the start and deadline, or the errors of the dates.
+    fn dates(&self) -> Result<(NaiveDate, NaiveDate), FieldErrors> {
+        parse_period(&self.start, &self.deadline)
+    }
+
+    /// Returns the progress towards the goal.
+    ///
+    /// An aircraft counts as flown when its last flight was on or after the start.
+    ///
+    /// # Arguments
+    ///
+    /// * `all_aircraft` - All aircraft of the fleet.
+    /// * `today` - The date to count the remaining days from.
+    pub fn progress(
+        &self,
+        all_aircraft: &[Arc<Aircraft>],
+        today: NaiveDate,
+    ) -> Option<GoalProgress> {
+        let (start, deadline) = self.dates().ok()?;
+
+        let pending: Vec<Arc<Aircraft>> = all_aircraft
+            .iter()
+            .filter(|aircraft| {
+                aircraft
+                    .date_flown
+                    .as_deref()
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                    .is_none_or(|date| date < start)
+            })
+            .cloned()
+            .collect();
+
+        Some(GoalProgress {
+            flown: all_aircraft.len() - pending.len(),
+            total: all_aircraft.len(),
+            pending,
+            days_left: (deadline - today).num_days(),
+        })
+    }
+}
+
+/// How far the fleet goal has come.
+pub struct GoalProgress {
+    /// The number of aircraft flown since the start of the goal.
+    pub flown: usize,
+    /// The number of aircraft in the fleet.
+    pub total: usize,
+    /// The aircraft not flown since the start of the goal.
+    pub pending: Vec<Arc<Aircraft>>,
+    /// The days until the deadline, negative once it has passed.
+    pub days_left: i64,
+}
+
+/// State of the fleet goal window.
+#[derive(Default)]
+pub struct GoalsState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// Whether the reminder banner was dismissed for this session.
+    banner_dismissed: bool,
+}
+
+impl Gui<'_> {
+    /// Shows the fleet goal window, where the goal is set and its progress shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_goals_window(&mut self, ctx: &egui::Context) {
+        let today = chrono::Local::now().date_naive();
+        let mut open = self.goals_state.open;
+        let mut changed = false;
+
+        egui::Window::new("Fleet goal")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(goal) = &mut self.settings.fleet_goal else {
+                    ui.label("Set a goal of flying every aircraft at least once.");
+                    if ui.button("Fly every aircraft this year").clicked() {
+                        self.settings.fleet_goal = Some(FleetGoal::for_year(today));
+                        changed = true;
+                    }
+                    return;
+                };
+
+                let errors = goal.dates().err().unwrap_or_default();
+                egui::Grid::new("fleet_goal").num_columns(2).show(ui, |ui| {
+                    let label = ui.label("From:");
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut goal.start).desired_width(100.0))
+                        .labelled_by(label.id)
+                        .lost_focus();
+                    ui.end_row();
+                    show_field_error(ui, &errors, "start");
+
+                    let label = ui.label("Until:");
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut goal.deadline).desired_width(100.0))
+                        .labelled_by(label.id)
+                        .lost_focus();
+                    ui.end_row();
+                    show_field_error(ui, &errors, "end");
+
+                    let label = ui.label("Remind from:");
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut goal.reminder_days)
+                                .range(0..=365)
+                                .suffix(" days before"),
+                        )
+                        .labelled_by(label.id);
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                    ui.end_row();
+                });
+
+                if let Some(progress) = goal.progress(&self.all_aircraft, today) {
+                    ui.separator();
+                    ui.add(
+                        egui::ProgressBar::new(
+                            progress.flown as f32 / progress.total.max(1) as f32,
+                        )
+                        .text(format!(
+                            "{} of {} aircraft flown",
+                            progress.flown, progress.total
+                        )),
+                    );
+                    ui.label(match progress.days_left {
+                        days if days < 0 => "The deadline has passed".to_string(),
+                        0 => "The deadline is today".to_string(),
+                        days => format!("{} days left", days),
+                    });
+
+                    if !progress.pending.is_empty() {
+                        egui::CollapsingHeader::new(format!(
+                            "Still to fly ({})",
+                            progress.pending.len()
+                        ))
+                        .show(ui, |ui| {
+                            egui::ScrollArea::vertical()
+                                .max_height(200.0)
+                                .show(ui, |ui| {
+                                    for aircraft in &progress.pending {
+                                        ui.label(format!(
+                                            "{} {}",
+                                            aircraft.manufacturer, aircraft.variant
+                                        ));
+                                    }
+                                });
+                        });
+                    }
+                }
+
+                ui.separator();
+                if ui.button("Remove goal").clicked() {
+                    self.settings.fleet_goal = None;
+                    changed = true;
+                }
+            });
+
+        self.goals_state.open = open;
+        if changed {
+            self.goals_state.banner_dismissed = false;
+            if let Err(e) = self.settings.save(Path::new(SETTINGS_FILENAME)) {
+                log::warn!("Failed to save settings: {}", e);
+            }
+        }
+    }
+
+    /// Shows a reminder listing the aircraft still to fly when the goal deadline is near.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_goal_reminder(&mut self, ctx: &egui::Context) {
+        if self.goals_state.banner_dismissed {
+            return;
+        }
+        let Some(goal) = &self.settings.fleet_goal else {
+            return;
+        };
+        let Some(progress) = goal.progress(&self.all_aircraft, chrono::Local::now().date_naive())
+        else {
+            return;
+        };
+        if progress.pending.is_empty()
+            || progress.days_left < 0
+            || progress.days_left > i64::from(goal.reminder_days)
+        {
+            return;
+        }
+
+        let mut names: Vec<String> = progress
+            .pending
+            .iter()
+            .take(BANNER_AIRCRAFT)
+            .map(|aircraft| format!("{} {}", aircraft.manufacturer, aircraft.variant))
+            .collect();
+        if progress.pending.len() > BANNER_AIRCRAFT {
+            names.push(format!("{} more", progress.pending.len() - BANNER_AIRCRAFT));
+        }
+
+        egui::TopBottomPanel::top("goal_reminder").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{} aircraft still to fly in the next {} days: {}",
+                        progress.pending.len(),
+                        progress.days_left,
+                        names.join(", ")
+                    ),
+                );
+                if ui.small_button("Show").clicked() {
+                    self.goals_state.open = true;
+                }
+                if ui.small_button("Dismiss").clicked() {
+                    self.goals_state.banner_dismissed = true;
+                }
+            });
+        });
+    }
+}
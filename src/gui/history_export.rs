@@ -0,0 +1,84 @@
+use super::Gui;
+use crate::modules::history_export::HistoryExportFormat;
+use crate::traits::HistoryOperations;
+use eframe::egui;
+use std::path::PathBuf;
+
+/// The file name of an exported history, without its extension.
+const HISTORY_FILE_STEM: &str = "flight-history";
+
+/// State of the history export window.
+#[derive(Default)]
+pub struct HistoryExportState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The format to export to.
+    format: HistoryExportFormat,
+    /// The outcome of the last save, the path written or the error.
+    saved: Option<Result<PathBuf, String>>,
+}
+
+impl Gui<'_> {
+    /// Opens the history export window.
+    pub(super) fn open_history_export_window(&mut self) {
+        self.history_export_state.open = true;
+        self.history_export_state.saved = None;
+    }
+
+    /// Shows the history export window, which saves every flight with the
+    /// names of its aircraft and airports as CSV or JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_history_export_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.history_export_state.open;
+        let mut save = false;
+
+        egui::Window::new("Export history")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let state = &mut self.history_export_state;
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    for format in HistoryExportFormat::ALL {
+                        ui.selectable_value(&mut state.format, format, format.display_name());
+                    }
+                });
+
+                save = ui
+                    .button("Save")
+                    .on_hover_text(format!(
+                        "Save as {}.{} in the working directory",
+                        HISTORY_FILE_STEM,
+                        state.format.extension()
+                    ))
+                    .clicked();
+
+                match &state.saved {
+                    Some(Ok(path)) => {
+                        ui.label(format!("Saved to {}", path.display()));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => {}
+                }
+            });
+
+        if save {
+            let format = self.history_export_state.format;
+            let path = PathBuf::from(format!("{}.{}", HISTORY_FILE_STEM, format.extension()));
+            self.history_export_state.saved = Some(
+                self.database_pool
+                    .export_history(format)
+                    .map_err(|e| e.to_string())
+                    .and_then(|contents| std::fs::write(&path, contents).map_err(|e| e.to_string()))
+                    .map(|()| std::path::absolute(&path).unwrap_or(path))
+                    .map_err(|e| format!("Failed to export the history: {}", e)),
+            );
+        }
+        self.history_export_state.open = open;
+    }
+}
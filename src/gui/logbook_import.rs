@@ -0,0 +1,180 @@
+use super::Gui;
+use crate::modules::history::import::{
+    import_logbook, parse_logbook_csv, plan_logbook_import, ImportEntry, ImportStatus,
+    LogbookFormat,
+};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+/// State of the logbook import window.
+#[derive(Default)]
+pub struct LogbookImportState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The path of the CSV export to import.
+    path: String,
+    /// The tool the file was exported from.
+    format: LogbookFormat,
+    /// The flights of the last preview, matched against the database.
+    entries: Vec<ImportEntry>,
+    /// The outcome of the last preview or import, a message or the error.
+    outcome: Option<Result<String, String>>,
+}
+
+impl LogbookImportState {
+    /// Returns the number of previewed flights that will be added.
+    fn ready_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status == ImportStatus::Ready)
+            .count()
+    }
+}
+
+impl Gui<'_> {
+    /// Reads and matches the logbook at the entered path, replacing the preview.
+    fn preview_logbook(&mut self) {
+        let state = &mut self.logbook_import_state;
+        state.entries.clear();
+        let path = state.path.trim();
+
+        let planned = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))
+            .and_then(|contents| {
+                parse_logbook_csv(&contents, state.format).map_err(|e| e.to_string())
+            })
+            .and_then(|flights| {
+                plan_logbook_import(self.database_pool, flights).map_err(|e| e.to_string())
+            });
+        state.outcome = Some(planned.map(|entries| {
+            state.entries = entries;
+            format!(
+                "{} of {} flights are ready to import",
+                state.ready_count(),
+                state.entries.len()
+            )
+        }));
+    }
+
+    /// Adds the previewed flights that are ready to the history.
+    fn confirm_logbook_import(&mut self) {
+        let state = &mut self.logbook_import_state;
+        let entries = std::mem::take(&mut state.entries);
+        state.outcome = Some(
+            import_logbook(self.database_pool, &entries, state.format)
+                .map(|imported| format!("Added {} flights to the history", imported))
+                .map_err(|e| format!("Failed to import the logbook: {}", e)),
+        );
+        self.reload_flown_aircraft();
+    }
+
+    /// Shows the logbook import window, which previews the flights of a
+    /// Volanta or SimToolkitPro CSV export before adding them to the history.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_logbook_import_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.logbook_import_state.open;
+        let mut preview = false;
+        let mut confirm = false;
+
+        egui::Window::new("Import logbook")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let state = &mut self.logbook_import_state;
+                ui.horizontal(|ui| {
+                    ui.label("Exported from:");
+                    for format in LogbookFormat::ALL {
+                        preview |= ui
+                            .selectable_value(&mut state.format, format, format.display_name())
+                            .changed()
+                            && !state.entries.is_empty();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let label = ui.label("CSV file:");
+                    ui.add(egui::TextEdit::singleline(&mut state.path).desired_width(300.0))
+                        .labelled_by(label.id);
+                    preview |= ui
+                        .add_enabled(!state.path.trim().is_empty(), egui::Button::new("Preview"))
+                        .on_hover_text("Read the file without changing the history")
+                        .clicked();
+                });
+
+                match &state.outcome {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => {}
+                }
+
+                if state.entries.is_empty() {
+                    return;
+                }
+
+                ui.separator();
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .max_scroll_height(300.0)
+                    .column(Column::auto())
+                    .column(Column::auto())
+                    .column(Column::auto())
+                    .column(Column::auto())
+                    .column(Column::remainder())
+                    .header(20.0, |mut header| {
+                        for title in ["Date", "From", "To", "Aircraft", "Status"] {
+                            header.col(|ui| {
+                                ui.strong(title);
+                            });
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(18.0, state.entries.len(), |mut row| {
+                            let entry = &state.entries[row.index()];
+                            let flight = &entry.flight;
+                            row.col(|ui| {
+                                ui.label(flight.date.to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(&flight.departure);
+                            });
+                            row.col(|ui| {
+                                ui.label(&flight.arrival);
+                            });
+                            row.col(|ui| {
+                                ui.label(&flight.aircraft_type);
+                            });
+                            row.col(|ui| {
+                                let status = entry.status.describe();
+                                if entry.status == ImportStatus::Ready {
+                                    ui.label(status);
+                                } else {
+                                    ui.colored_label(ui.visuals().warn_fg_color, status)
+                                        .on_hover_text(format!("Line {}", flight.line));
+                                }
+                            });
+                        });
+                    });
+
+                let ready = state.ready_count();
+                confirm = ui
+                    .add_enabled(
+                        ready > 0,
+                        egui::Button::new(format!("Import {} flights", ready)),
+                    )
+                    .clicked();
+            });
+
+        if preview {
+            self.preview_logbook();
+        } else if confirm {
+            self.confirm_logbook_import();
+        }
+        self.logbook_import_state.open = open;
+    }
+}
@@ -0,0 +1,255 @@
+use super::Gui;
+use crate::models::Airport;
+use crate::modules::export::great_circle_points;
+use crate::modules::routes::Route;
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Shape, Stroke, Vec2};
+use std::sync::Arc;
+
+/// The spacing of the drawn meridians and parallels, in degrees.
+const GRATICULE_DEGREES: i32 = 30;
+/// The radius of an airport marker in points.
+const MARKER_RADIUS: f32 = 3.5;
+/// How far from a marker a click or hover still picks it, in points.
+const MARKER_PICK_RADIUS: f32 = 7.0;
+/// The zoom range of the map, where 1 fits the world to the width of the window.
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 1.0..=32.0;
+
+/// State of the route map window.
+pub struct MapViewState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The longitude and latitude in the middle of the map.
+    center: Vec2,
+    /// The zoom factor of the map.
+    zoom: f32,
+}
+
+impl Default for MapViewState {
+    fn default() -> Self {
+        MapViewState {
+            open: false,
+            center: Vec2::ZERO,
+            zoom: *ZOOM_RANGE.start(),
+        }
+    }
+}
+
+/// Projects longitudes and latitudes onto the map with an equirectangular
+/// projection, which keeps the map simple at the cost of stretching the poles.
+struct Projection {
+    rect: Rect,
+    center: Vec2,
+    points_per_degree: f32,
+}
+
+impl Projection {
+    fn new(rect: Rect, state: &MapViewState) -> Self {
+        Projection {
+            rect,
+            center: state.center,
+            points_per_degree: rect.width() / 360.0 * state.zoom,
+        }
+    }
+
+    /// Returns the position of a longitude and latitude on the map.
+    fn project(&self, longitude: f64, latitude: f64) -> Pos2 {
+        self.rect.center()
+            + Vec2::new(
+                (longitude as f32 - self.center.x) * self.points_per_degree,
+                (self.center.y - latitude as f32) * self.points_per_degree,
+            )
+    }
+
+    /// Returns the position of an airport on the map.
+    fn airport(&self, airport: &Airport) -> Pos2 {
+        self.project(airport.Longtitude, airport.Latitude)
+    }
+}
+
+impl Gui<'_> {
+    /// Shows the route map window, which draws the routes shown in the table
+    /// as great circles and marks the home base. Clicking an airport opens its
+    /// details.
+    ///
+    /// The map is panned by dragging and zoomed with the scroll wheel.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_map_window(&mut self, ctx: &egui::Context) {
+        let routes: Vec<Arc<Route>> = self.visible_routes().cloned().collect();
+        let home_base = self.home_base();
+        let mut open = self.map_view_state.open;
+        let mut clicked_airport = None;
+
+        egui::Window::new("Route map")
+            .open(&mut open)
+            .default_size([640.0, 360.0])
+            .show(ctx, |ui| {
+                ui.label(match routes.len() {
+                    0 => "Generate routes to see them on the map".to_string(),
+                    1 => "1 route".to_string(),
+                    count => format!("{} routes", count),
+                });
+                let (response, painter) = ui.allocate_painter(
+                    ui.available_size().max(Vec2::splat(120.0)),
+                    Sense::click_and_drag(),
+                );
+                let state = &mut self.map_view_state;
+                handle_map_input(ui, &response, state);
+
+                let projection = Projection::new(response.rect, state);
+                let visuals = ui.visuals();
+                painter.rect_filled(response.rect, 0.0, visuals.extreme_bg_color);
+                draw_graticule(&painter, &projection, visuals.weak_text_color());
+
+                let route_stroke = Stroke::new(1.5, visuals.selection.bg_fill);
+                for route in &routes {
+                    draw_great_circle(&painter, &projection, route, route_stroke);
+                }
+
+                let mut airports = route_airports(&routes);
+                if let Some(home) = &home_base {
+                    if !airports.iter().any(|airport| airport.ID == home.ID) {
+                        airports.push(home);
+                    }
+                }
+                let is_home = |airport: &Airport| {
+                    home_base.as_ref().is_some_and(|home| home.ID == airport.ID)
+                };
+                let hovered = response
+                    .hover_pos()
+                    .and_then(|pointer| nearest_airport(&projection, &airports, pointer));
+                for airport in &airports {
+                    let highlighted = hovered.is_some_and(|hovered| Arc::ptr_eq(hovered, airport));
+                    let (radius, color) = if is_home(airport) {
+                        (MARKER_RADIUS + 1.5, visuals.warn_fg_color)
+                    } else {
+                        (MARKER_RADIUS, visuals.strong_text_color())
+                    };
+                    painter.circle(
+                        projection.airport(airport),
+                        if highlighted { radius + 1.5 } else { radius },
+                        color,
+                        Stroke::new(1.0, visuals.extreme_bg_color),
+                    );
+                }
+
+                if let Some(airport) = hovered {
+                    let home = if is_home(airport) { " (home base)" } else { "" };
+                    response
+                        .clone()
+                        .on_hover_text(format!("{} - {}{}", airport.ICAO, airport.Name, home));
+                    if response.clicked() {
+                        clicked_airport = Some(Arc::clone(airport));
+                    }
+                }
+            });
+
+        self.map_view_state.open = open;
+        if let Some(airport) = clicked_airport {
+            self.open_airport_details(airport);
+        }
+    }
+}
+
+/// Pans the map by dragging and zooms it around the pointer by scrolling.
+fn handle_map_input(ui: &egui::Ui, response: &egui::Response, state: &mut MapViewState) {
+    let points_per_degree = response.rect.width() / 360.0 * state.zoom;
+    if response.dragged() {
+        let delta = response.drag_delta() / points_per_degree;
+        state.center += Vec2::new(-delta.x, delta.y);
+    }
+
+    if let Some(pointer) = response.hover_pos() {
+        let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+        if scroll != 0.0 {
+            let zoom =
+                (state.zoom * (scroll / 200.0).exp()).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+            // Keep the coordinate under the pointer in place.
+            let offset = pointer - response.rect.center();
+            let offset_degrees = Vec2::new(offset.x, -offset.y) / points_per_degree;
+            state.center += offset_degrees * (1.0 - state.zoom / zoom);
+            state.zoom = zoom;
+        }
+    }
+
+    state.center.x = state.center.x.clamp(-180.0, 180.0);
+    state.center.y = state.center.y.clamp(-90.0, 90.0);
+}
+
+/// Draws the meridians and parallels, with the equator and the prime meridian stronger.
+fn draw_graticule(painter: &egui::Painter, projection: &Projection, color: Color32) {
+    let weak = Stroke::new(0.5, color.gamma_multiply(0.5));
+    let strong = Stroke::new(1.0, color);
+
+    for longitude in (-180..=180).step_by(GRATICULE_DEGREES as usize) {
+        let stroke = if longitude == 0 { strong } else { weak };
+        painter.line_segment(
+            [
+                projection.project(f64::from(longitude), 90.0),
+                projection.project(f64::from(longitude), -90.0),
+            ],
+            stroke,
+        );
+    }
+    for latitude in (-90..=90).step_by(GRATICULE_DEGREES as usize) {
+        let stroke = if latitude == 0 { strong } else { weak };
+        painter.line_segment(
+            [
+                projection.project(-180.0, f64::from(latitude)),
+                projection.project(180.0, f64::from(latitude)),
+            ],
+            stroke,
+        );
+    }
+}
+
+/// Draws a route as a great circle, broken where it crosses the antimeridian.
+fn draw_great_circle(
+    painter: &egui::Painter,
+    projection: &Projection,
+    route: &Route,
+    stroke: Stroke,
+) {
+    let points = great_circle_points(&route.departure, &route.destination);
+    let mut line: Vec<Pos2> = Vec::with_capacity(points.len());
+    let mut previous_longitude = None;
+
+    for point in points {
+        if previous_longitude.is_some_and(|previous: f64| (point.x() - previous).abs() > 180.0) {
+            painter.add(Shape::line(std::mem::take(&mut line), stroke));
+        }
+        line.push(projection.project(point.x(), point.y()));
+        previous_longitude = Some(point.x());
+    }
+    painter.add(Shape::line(line, stroke));
+}
+
+/// Returns the departures and destinations of the routes, each airport once.
+fn route_airports(routes: &[Arc<Route>]) -> Vec<&Arc<Airport>> {
+    let mut airports: Vec<&Arc<Airport>> = Vec::new();
+    for route in routes {
+        for airport in [&route.departure, &route.destination] {
+            if !airports.iter().any(|known| known.ID == airport.ID) {
+                airports.push(airport);
+            }
+        }
+    }
+
+    airports
+}
+
+/// Returns the airport whose marker is closest to the pointer, if one is close enough.
+fn nearest_airport<'a>(
+    projection: &Projection,
+    airports: &[&'a Arc<Airport>],
+    pointer: Pos2,
+) -> Option<&'a Arc<Airport>> {
+    airports
+        .iter()
+        .map(|airport| (projection.airport(airport).distance(pointer), *airport))
+        .filter(|(distance, _)| *distance <= MARKER_PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, airport)| airport)
+}
@@ -0,0 +1,190 @@
+use super::{show_field_error, Gui};
+use crate::modules::routes::{airport_distance_nm, Route};
+use crate::modules::validation::{parse_airport_code, parse_flight_date, FieldErrors, DATE_FORMAT};
+use crate::traits::HistoryOperations;
+use eframe::egui;
+use std::sync::Arc;
+
+/// The confirmation form shown in the route popup before a route is marked as flown.
+pub struct MarkFlownForm {
+    /// The route to mark as flown.
+    route: Arc<Route>,
+    /// The date of the flight as typed, `YYYY-MM-DD`.
+    date: String,
+    /// An optional note stored with the history entry.
+    notes: String,
+    /// The ICAO code of the airport actually landed at, which differs from the
+    /// destination after a diversion.
+    arrival: String,
+    /// Whether the entered flight is already in the history, found when confirming.
+    duplicate: bool,
+}
+
+impl MarkFlownForm {
+    /// Creates a form for the route, dated today.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to mark as flown.
+    pub fn new(route: Arc<Route>) -> Self {
+        MarkFlownForm {
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            notes: String::new(),
+            arrival: route.destination.ICAO.clone(),
+            duplicate: false,
+            route,
+        }
+    }
+
+    /// Returns whether the form belongs to the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route shown in the popup.
+    pub fn is_for(&self, route: &Arc<Route>) -> bool {
+        Arc::ptr_eq(&self.route, route)
+    }
+}
+
+impl Gui<'_> {
+    /// Shows the mark as flown form in the route popup, writing the history entry
+    /// once confirmed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI of the popup.
+    pub(super) fn show_mark_flown_form(&mut self, ui: &mut egui::Ui) {
+        let Some(form) = &mut self.popup_state.mark_flown else {
+            return;
+        };
+        let mut confirmed = None;
+        let mut cancelled = false;
+
+        ui.separator();
+        let mut edited = false;
+        let mut errors = FieldErrors::default();
+        let mut date = None;
+        let mut arrival = None;
+        egui::Grid::new("mark_flown_form")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let label = ui.label("Date flown:");
+                edited |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut form.date)
+                            .hint_text("YYYY-MM-DD")
+                            .desired_width(100.0),
+                    )
+                    .labelled_by(label.id)
+                    .changed();
+                ui.end_row();
+                date = errors.check(
+                    "date",
+                    parse_flight_date(&form.date, chrono::Local::now().date_naive()),
+                );
+                show_field_error(ui, &errors, "date");
+
+                let label = ui.label("Arrival:");
+                edited |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut form.arrival)
+                            .hint_text("ICAO")
+                            .desired_width(100.0),
+                    )
+                    .labelled_by(label.id)
+                    .on_hover_text("Change to the airport you diverted to")
+                    .changed();
+                ui.end_row();
+                if let Some(code) = errors.check("arrival", parse_airport_code(&form.arrival)) {
+                    arrival = self.route_generator.as_ref().and_then(|route_generator| {
+                        route_generator
+                            .find_airport_by_icao(self.airport_codes.current_icao(&code))
+                            .cloned()
+                    });
+                    if arrival.is_none() {
+                        errors.add("arrival", format!("No airport with code {}", code));
+                    }
+                }
+                show_field_error(ui, &errors, "arrival");
+
+                let label = ui.label("Notes:");
+                ui.add(
+                    egui::TextEdit::multiline(&mut form.notes)
+                        .hint_text("Optional")
+                        .desired_rows(2),
+                )
+                .labelled_by(label.id);
+                ui.end_row();
+            });
+        if edited {
+            form.duplicate = false;
+        }
+
+        if let Some(arrival) = &arrival {
+            if arrival.ID != form.route.destination.ID {
+                ui.label(format!(
+                    "Diverted to {}, {:.0} NM flown",
+                    arrival.Name,
+                    airport_distance_nm(&form.route.departure, arrival)
+                ));
+            }
+        }
+        if form.duplicate {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "⚠ This flight is already in the history for this date",
+            );
+        }
+        let confirm_text = if form.duplicate {
+            "Add anyway"
+        } else {
+            "Confirm"
+        };
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(errors.is_empty(), egui::Button::new(confirm_text))
+                .clicked()
+            {
+                confirmed = date.zip(arrival);
+            }
+            if ui.button("Cancel").clicked() {
+                cancelled = true;
+            }
+        });
+
+        if let Some((date, arrival)) = confirmed {
+            let form = self.popup_state.mark_flown.as_mut().unwrap();
+            if !form.duplicate {
+                let existing = self.database_pool.find_history_entry(
+                    &form.route.departure,
+                    &arrival,
+                    &form.route.aircraft,
+                    &date.format(DATE_FORMAT).to_string(),
+                );
+                match existing {
+                    Ok(Some(_)) => {
+                        // Ask before logging the same flight twice.
+                        form.duplicate = true;
+                        return;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.show_error(format!("Failed to look up history: {}", e));
+                        return;
+                    }
+                }
+            }
+
+            let form = self.popup_state.mark_flown.take().unwrap();
+            let notes = form.notes.trim();
+            self.handle_mark_flown_button(
+                &form.route,
+                date,
+                Some(notes).filter(|notes| !notes.is_empty()),
+                &arrival,
+            );
+        } else if cancelled {
+            self.popup_state.mark_flown = None;
+        }
+    }
+}
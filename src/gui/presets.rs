@@ -0,0 +1,370 @@
+use super::actions::Action;
+use super::Gui;
+use crate::models::Aircraft;
+use crate::modules::routes::CompassSector;
+use crate::modules::seasons::SEASONAL_PRESETS;
+use crate::SETTINGS_FILENAME;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A named set of route generation options, applied with one click.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct GenerationPreset {
+    /// The name shown on the preset button.
+    pub name: String,
+    /// The IDs of the aircraft to generate routes for, random aircraft when empty.
+    pub aircraft: Vec<i32>,
+    /// The share of the aircraft range not used for generated routes, in percent.
+    pub range_reserve_percent: u32,
+    /// The shortest generated route in nautical miles.
+    pub min_leg_nm: u32,
+    /// The longest generated route in nautical miles, 0 for the aircraft range.
+    pub max_leg_nm: u32,
+    /// The compass sector the destinations have to lie in, if any.
+    pub direction: Option<CompassSector>,
+    /// The name of the seasonal area the destinations have to lie in, if any.
+    pub region: Option<String>,
+}
+
+/// The file presets are exported to and imported from, in the working directory.
+const PRESETS_FILENAME: &str = "generation-presets.toml";
+
+/// A preset as written to an export file, naming its aircraft instead of
+/// using the IDs of the local database.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SharedPreset {
+    name: String,
+    /// The aircraft as manufacturer and variant, e.g. `Boeing 737-800`.
+    aircraft: Vec<String>,
+    range_reserve_percent: u32,
+    min_leg_nm: u32,
+    max_leg_nm: u32,
+    direction: Option<CompassSector>,
+    region: Option<String>,
+}
+
+/// The contents of a preset export file.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct PresetFile {
+    presets: Vec<SharedPreset>,
+}
+
+/// Returns the name an aircraft is shared by.
+fn shared_aircraft_name(aircraft: &Aircraft) -> String {
+    format!("{} {}", aircraft.manufacturer, aircraft.variant)
+}
+
+/// State of the generation presets in the side panel.
+#[derive(Default)]
+pub struct PresetsState {
+    /// The name of the preset to save.
+    name: String,
+    /// The outcome of the last export or import.
+    message: Option<String>,
+}
+
+impl Gui<'_> {
+    /// Shows the saved generation presets as buttons, and a field to save the
+    /// current options as a new preset.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    pub(super) fn show_generation_presets(&mut self, ui: &mut egui::Ui) {
+        ui.label("Presets:");
+
+        let mut applied = None;
+        let mut deleted = None;
+        ui.horizontal_wrapped(|ui| {
+            for (index, preset) in self.settings.generation_presets.iter().enumerate() {
+                let response = ui
+                    .add_enabled(
+                        self.route_generator.is_some(),
+                        egui::Button::new(&preset.name),
+                    )
+                    .on_hover_text(self.describe_generation_preset(preset));
+                if response.clicked() {
+                    applied = Some(index);
+                }
+                response.context_menu(|ui| {
+                    if ui.button("Delete preset").clicked() {
+                        deleted = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.presets_state.name)
+                    .hint_text("Preset name")
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.presets_state.name.trim().is_empty(),
+                    egui::Button::new("Save"),
+                )
+                .on_hover_text("Save the current aircraft, route distance, direction and region")
+                .clicked()
+            {
+                self.save_generation_preset();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.settings.generation_presets.is_empty(),
+                    egui::Button::new("Export"),
+                )
+                .on_hover_text(format!(
+                    "Write the presets to {} to share them",
+                    PRESETS_FILENAME
+                ))
+                .clicked()
+            {
+                self.presets_state.message = Some(self.export_generation_presets());
+            }
+            if ui
+                .button("Import")
+                .on_hover_text(format!(
+                    "Add the presets in {}, replacing presets with the same name",
+                    PRESETS_FILENAME
+                ))
+                .clicked()
+            {
+                self.presets_state.message = Some(self.import_generation_presets());
+            }
+        });
+        if let Some(message) = &self.presets_state.message {
+            ui.label(message);
+        }
+
+        if let Some(index) = deleted {
+            self.settings.generation_presets.remove(index);
+            self.save_settings();
+        } else if let Some(index) = applied {
+            self.apply_generation_preset(index);
+        }
+    }
+
+    /// Returns a summary of the options of a preset, for its hover text.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to describe.
+    fn describe_generation_preset(&self, preset: &GenerationPreset) -> String {
+        let mut lines = Vec::new();
+        if preset.aircraft.is_empty() {
+            lines.push("Random aircraft".to_string());
+        } else {
+            let names: Vec<String> = self
+                .all_aircraft
+                .iter()
+                .filter(|aircraft| preset.aircraft.contains(&aircraft.id))
+                .map(|aircraft| format!("{} {}", aircraft.manufacturer, aircraft.variant))
+                .collect();
+            lines.push(names.join(", "));
+        }
+        if preset.max_leg_nm > 0 {
+            lines.push(format!(
+                "Legs of {} to {} NM, {} % range reserve",
+                preset.min_leg_nm, preset.max_leg_nm, preset.range_reserve_percent
+            ));
+        } else {
+            lines.push(format!(
+                "Legs of at least {} NM, {} % range reserve",
+                preset.min_leg_nm, preset.range_reserve_percent
+            ));
+        }
+        if let Some(direction) = preset.direction {
+            lines.push(format!("Towards {}", direction.abbreviation()));
+        }
+        if let Some(region) = &preset.region {
+            lines.push(format!("In {}", region));
+        }
+        lines.push("Right-click to delete".to_string());
+
+        lines.join("\n")
+    }
+
+    /// Saves the current generation options as a preset, replacing a preset
+    /// with the same name.
+    fn save_generation_preset(&mut self) {
+        let preset = GenerationPreset {
+            name: self.presets_state.name.trim().to_string(),
+            aircraft: self
+                .route_aircraft_state
+                .selected
+                .iter()
+                .map(|aircraft| aircraft.id)
+                .collect(),
+            range_reserve_percent: self.settings.range_reserve_percent,
+            min_leg_nm: self.settings.min_leg_nm,
+            max_leg_nm: self.settings.max_leg_nm,
+            direction: self.route_direction,
+            region: self.seasonal_preset.map(|preset| preset.name.to_string()),
+        };
+
+        self.add_generation_preset(preset);
+        self.presets_state.name.clear();
+        self.save_settings();
+    }
+
+    /// Adds a preset, replacing a preset with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to add.
+    fn add_generation_preset(&mut self, preset: GenerationPreset) {
+        let presets = &mut self.settings.generation_presets;
+        match presets
+            .iter_mut()
+            .find(|existing| existing.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+    }
+
+    /// Writes the presets to the export file.
+    ///
+    /// # Returns
+    ///
+    /// A message telling where the presets were written, or why that failed.
+    fn export_generation_presets(&self) -> String {
+        let file = PresetFile {
+            presets: self
+                .settings
+                .generation_presets
+                .iter()
+                .map(|preset| SharedPreset {
+                    name: preset.name.clone(),
+                    aircraft: self
+                        .all_aircraft
+                        .iter()
+                        .filter(|aircraft| preset.aircraft.contains(&aircraft.id))
+                        .map(|aircraft| shared_aircraft_name(aircraft))
+                        .collect(),
+                    range_reserve_percent: preset.range_reserve_percent,
+                    min_leg_nm: preset.min_leg_nm,
+                    max_leg_nm: preset.max_leg_nm,
+                    direction: preset.direction,
+                    region: preset.region.clone(),
+                })
+                .collect(),
+        };
+
+        let written = toml::to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                std::fs::write(PRESETS_FILENAME, contents).map_err(|e| e.to_string())
+            });
+        match written {
+            Ok(()) => format!(
+                "Exported {} presets to {}",
+                file.presets.len(),
+                PRESETS_FILENAME
+            ),
+            Err(e) => format!("Failed to export presets: {}", e),
+        }
+    }
+
+    /// Adds the presets of the export file, matching their aircraft by name.
+    ///
+    /// # Returns
+    ///
+    /// A message telling how many presets were imported and which aircraft
+    /// are unknown, or why the import failed.
+    fn import_generation_presets(&mut self) -> String {
+        let file: PresetFile = match std::fs::read_to_string(PRESETS_FILENAME)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(file) => file,
+            Err(e) => return format!("Failed to import {}: {}", PRESETS_FILENAME, e),
+        };
+
+        let mut unknown = Vec::new();
+        let count = file.presets.len();
+        for shared in file.presets {
+            let mut aircraft = Vec::new();
+            for name in shared.aircraft {
+                match self
+                    .all_aircraft
+                    .iter()
+                    .find(|known| shared_aircraft_name(known).eq_ignore_ascii_case(&name))
+                {
+                    Some(known) => aircraft.push(known.id),
+                    None => unknown.push(name),
+                }
+            }
+            self.add_generation_preset(GenerationPreset {
+                name: shared.name,
+                aircraft,
+                range_reserve_percent: shared.range_reserve_percent,
+                min_leg_nm: shared.min_leg_nm,
+                max_leg_nm: shared.max_leg_nm,
+                direction: shared.direction,
+                region: shared.region,
+            });
+        }
+        self.save_settings();
+
+        if unknown.is_empty() {
+            format!("Imported {} presets", count)
+        } else {
+            format!(
+                "Imported {} presets, skipping unknown aircraft: {}",
+                count,
+                unknown.join(", ")
+            )
+        }
+    }
+
+    /// Applies a preset and generates routes with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the preset in the settings.
+    fn apply_generation_preset(&mut self, index: usize) {
+        let preset = self.settings.generation_presets[index].clone();
+
+        self.route_aircraft_state.selected = self
+            .all_aircraft
+            .iter()
+            .filter(|aircraft| preset.aircraft.contains(&aircraft.id))
+            .map(Arc::clone)
+            .collect();
+        self.settings.range_reserve_percent = preset.range_reserve_percent;
+        self.settings.min_leg_nm = preset.min_leg_nm;
+        self.settings.max_leg_nm = preset.max_leg_nm;
+        self.route_direction = preset.direction;
+        self.seasonal_preset = preset.region.as_ref().and_then(|region| {
+            SEASONAL_PRESETS
+                .iter()
+                .find(|seasonal| seasonal.name == region)
+        });
+        self.save_settings();
+
+        if self.route_aircraft_state.selected.is_empty() {
+            self.run_action(Action::RandomRoutes);
+        } else {
+            self.run_action(Action::SelectedAircraftRoutes);
+        }
+    }
+
+    /// Writes the settings to disk, logging a failure.
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save(Path::new(SETTINGS_FILENAME)) {
+            log::warn!("Failed to save settings: {}", e);
+        }
+    }
+}
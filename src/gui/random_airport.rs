@@ -0,0 +1,123 @@
+use super::{Gui, TableItem};
+use crate::modules::routes::AirportConstraints;
+use crate::modules::runway::AirportKind;
+use crate::modules::trim::parse_regions;
+use eframe::egui;
+use std::sync::Arc;
+
+/// The longest runway the random airport filter can ask for, in feet.
+const MAX_MIN_RUNWAY_FT: u32 = 15_000;
+
+/// The limits on the airport picked by "Get random airport".
+#[derive(Default)]
+pub struct RandomAirportState {
+    /// Comma separated continents and ICAO prefixes, any region if empty.
+    regions: String,
+    /// The shortest length the longest land runway may have, in feet.
+    min_runway_ft: u32,
+    /// The kind of airport to pick, any kind if `None`.
+    kind: Option<AirportKind>,
+    /// The message shown when the last pick failed.
+    error: Option<String>,
+}
+
+impl RandomAirportState {
+    /// Returns the constraints of the filter.
+    ///
+    /// # Returns
+    ///
+    /// The constraints, or a message if the regions cannot be parsed.
+    fn constraints(&self) -> Result<AirportConstraints, String> {
+        let icao_prefixes = if self.regions.trim().is_empty() {
+            Vec::new()
+        } else {
+            parse_regions(&self.regions).map_err(|e| e.to_string())?
+        };
+
+        Ok(AirportConstraints {
+            icao_prefixes,
+            min_runway_ft: self.min_runway_ft,
+            kind: self.kind,
+        })
+    }
+}
+
+impl Gui<'_> {
+    /// Picks a random airport within the filter and shows it in the table.
+    pub(super) fn show_random_airport(&mut self) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let state = &mut self.random_airport_state;
+
+        let airport = state.constraints().and_then(|constraints| {
+            route_generator
+                .random_airport(&constraints)
+                .cloned()
+                .ok_or_else(|| "No airport matches the filter".to_string())
+        });
+        match airport {
+            Ok(airport) => {
+                state.error = None;
+                self.displayed_items = vec![Arc::new(TableItem::Airport(airport))];
+                self.search_state.query.clear();
+            }
+            Err(e) => state.error = Some(e),
+        }
+    }
+
+    /// Shows the filter of "Get random airport", which limits the pick to
+    /// regions, a runway length and a kind of airport.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    pub(super) fn show_random_airport_filter(&mut self, ui: &mut egui::Ui) {
+        let state = &mut self.random_airport_state;
+
+        egui::CollapsingHeader::new("Airport filter")
+            .id_salt("random_airport_filter")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let label = ui.label("Regions:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.regions)
+                            .hint_text("any")
+                            .desired_width(120.0),
+                    )
+                    .labelled_by(label.id)
+                    .on_hover_text("Continents or ICAO prefixes, for example europe,KS,CY");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Runway:");
+                    ui.add(
+                        egui::DragValue::new(&mut state.min_runway_ft)
+                            .range(0..=MAX_MIN_RUNWAY_FT)
+                            .speed(50)
+                            .prefix("≥ ")
+                            .suffix(" ft"),
+                    )
+                    .on_hover_text("Shortest length of the longest land runway");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Kind:");
+                    egui::ComboBox::from_id_salt("random_airport_kind")
+                        .selected_text(state.kind.map_or("Any", |kind| kind.display_name()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut state.kind, None, "Any");
+                            for kind in AirportKind::ALL {
+                                ui.selectable_value(
+                                    &mut state.kind,
+                                    Some(kind),
+                                    kind.display_name(),
+                                );
+                            }
+                        });
+                });
+            });
+
+        if let Some(e) = &state.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::modules::region::{Continent, Region, COUNTRIES};
+use eframe::egui;
+
+/// Shows a dropdown for picking the continent or country generated routes
+/// are kept in. Each continent is followed by its countries.
+///
+/// # Arguments
+///
+/// * `ui` - The UI context.
+/// * `id_salt` - The unique ID of the dropdown.
+/// * `region` - The selected region, or `None` for anywhere.
+///
+/// # Returns
+///
+/// Whether the selection changed.
+pub fn region_picker(ui: &mut egui::Ui, id_salt: &str, region: &mut Option<Region>) -> bool {
+    let mut changed = false;
+
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(region.map_or("Anywhere", Region::name))
+        .height(320.0)
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(region, None, "Anywhere").changed();
+            for continent in Continent::ALL {
+                let selected = Some(Region::Continent(continent));
+                changed |= ui
+                    .selectable_value(
+                        region,
+                        selected,
+                        egui::RichText::new(continent.name()).strong(),
+                    )
+                    .changed();
+                ui.indent(continent.key(), |ui| {
+                    for country in COUNTRIES
+                        .iter()
+                        .filter(|country| country.continent() == Some(continent))
+                    {
+                        changed |= ui
+                            .selectable_value(region, Some(Region::Country(country)), country.name)
+                            .on_hover_text(country.code)
+                            .changed();
+                    }
+                });
+            }
+        });
+
+    changed
+}
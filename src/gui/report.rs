@@ -0,0 +1,167 @@
+use super::{show_field_error, Gui};
+use crate::modules::report::{FlightReport, ReportFormat};
+use crate::modules::validation::{parse_period, FieldErrors};
+use crate::traits::HistoryOperations;
+use chrono::{Datelike, NaiveDate};
+use eframe::egui;
+use std::path::PathBuf;
+
+/// The file name of a saved report, without its extension.
+const REPORT_FILE_STEM: &str = "flight-report";
+
+/// State of the report window.
+pub struct ReportState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The first day of the period, `YYYY-MM-DD`.
+    start: String,
+    /// The last day of the period, `YYYY-MM-DD`.
+    end: String,
+    /// The format of the report.
+    format: ReportFormat,
+    /// The outcome of the last save, the path written or the error.
+    saved: Option<Result<PathBuf, String>>,
+}
+
+impl Default for ReportState {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        ReportState {
+            open: false,
+            start: format!("{}-01-01", today.year()),
+            end: today.format("%Y-%m-%d").to_string(),
+            format: ReportFormat::default(),
+            saved: None,
+        }
+    }
+}
+
+impl ReportState {
+    /// Returns the period, or the errors of its days.
+    fn period(&self) -> Result<(NaiveDate, NaiveDate), FieldErrors> {
+        parse_period(&self.start, &self.end)
+    }
+}
+
+impl Gui<'_> {
+    /// Renders the report of the chosen period in the chosen format.
+    pub(super) fn render_report(&mut self) -> Option<String> {
+        let (start, end) = self.report_state.period().ok()?;
+        let history = match self.database_pool.get_history() {
+            Ok(history) => history,
+            Err(e) => {
+                self.show_error(format!("Failed to load history: {}", e));
+                return None;
+            }
+        };
+        let route_generator = self.route_generator.as_ref()?;
+
+        let report = FlightReport::compute(
+            &history,
+            &route_generator.all_airports,
+            &self.all_aircraft,
+            start,
+            end,
+            self.home_base().as_deref(),
+        );
+        Some(report.render(self.report_state.format))
+    }
+
+    /// Shows the report window, where a summary of the flights in a period is
+    /// saved as Markdown or HTML, or copied to the clipboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_report_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.report_state.open;
+        let mut save = false;
+        let mut copy = false;
+
+        egui::Window::new("Generate report")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let state = &mut self.report_state;
+                let errors = state.period().err().unwrap_or_default();
+                egui::Grid::new("report_period")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("From");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut state.start)
+                                .hint_text("YYYY-MM-DD")
+                                .desired_width(100.0),
+                        );
+                        ui.end_row();
+                        show_field_error(ui, &errors, "start");
+                        ui.label("Until");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut state.end)
+                                .hint_text("YYYY-MM-DD")
+                                .desired_width(100.0),
+                        );
+                        ui.end_row();
+                        show_field_error(ui, &errors, "end");
+                        ui.label("Format");
+                        ui.horizontal(|ui| {
+                            for format in ReportFormat::ALL {
+                                ui.selectable_value(
+                                    &mut state.format,
+                                    format,
+                                    format.display_name(),
+                                );
+                            }
+                        });
+                        ui.end_row();
+                    });
+
+                let valid = errors.is_empty();
+                ui.horizontal(|ui| {
+                    save = ui
+                        .add_enabled(valid, egui::Button::new("Save"))
+                        .on_hover_text(format!(
+                            "Save as {}.{} in the working directory",
+                            REPORT_FILE_STEM,
+                            state.format.extension()
+                        ))
+                        .clicked();
+                    copy = ui
+                        .add_enabled(valid, egui::Button::new("Copy"))
+                        .on_hover_text("Copy the report to the clipboard")
+                        .clicked();
+                });
+
+                match &state.saved {
+                    Some(Ok(path)) => {
+                        ui.label(format!("Saved to {}", path.display()));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => {}
+                }
+            });
+
+        if save || copy {
+            if let Some(report) = self.render_report() {
+                if copy {
+                    ctx.copy_text(report.clone());
+                }
+                if save {
+                    let path = PathBuf::from(format!(
+                        "{}.{}",
+                        REPORT_FILE_STEM,
+                        self.report_state.format.extension()
+                    ));
+                    self.report_state.saved = Some(
+                        std::fs::write(&path, report)
+                            .map(|()| std::path::absolute(&path).unwrap_or(path))
+                            .map_err(|e| format!("Failed to save the report: {}", e)),
+                    );
+                }
+            }
+        }
+        self.report_state.open = open;
+    }
+}
@@ -0,0 +1,133 @@
+use super::custom_route::{
+    aircraft_label, aircraft_manufacturer, aircraft_matches, aircraft_not_flown,
+};
+use super::searchable_dropdown::SearchableDropdown;
+use super::{Gui, GENERATE_AMOUNT};
+use crate::models::Aircraft;
+use crate::modules::routes::Route;
+use chrono::NaiveDate;
+use eframe::egui;
+use rand::prelude::SliceRandom;
+use std::sync::Arc;
+
+/// The aircraft picked for generating routes from a chosen set of aircraft.
+#[derive(Default)]
+pub struct RouteAircraftState {
+    /// The selected aircraft.
+    pub selected: Vec<Arc<Aircraft>>,
+    /// Search query of the aircraft dropdown.
+    search: String,
+    /// Whether the aircraft dropdown only lists aircraft that have not been flown.
+    not_flown_only: bool,
+    /// Why the suggested aircraft was picked, while it is still selected.
+    suggestion: Option<(Arc<Aircraft>, String)>,
+}
+
+impl Gui<'_> {
+    /// Shows the dropdown for picking the aircraft to generate routes for.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    pub(super) fn show_route_aircraft_picker(&mut self, ui: &mut egui::Ui) {
+        let state = &mut self.route_aircraft_state;
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Aircraft:");
+            SearchableDropdown::new(
+                "route_aircraft_selection",
+                &self.all_aircraft,
+                aircraft_label,
+                &aircraft_matches,
+            )
+            .grouped_by(aircraft_manufacturer)
+            .filter(
+                "Not flown only",
+                aircraft_not_flown,
+                &mut state.not_flown_only,
+            )
+            .labelled_by(label.id)
+            .show_multiple(ui, &mut state.search, &mut state.selected);
+
+            if !state.selected.is_empty() && ui.small_button("Clear").clicked() {
+                state.selected.clear();
+            }
+        });
+
+        if let Some((aircraft, reason)) = &state.suggestion {
+            if state.selected.len() == 1 && state.selected[0].id == aircraft.id {
+                ui.label(format!(
+                    "Suggested {} {}: {}",
+                    aircraft.manufacturer, aircraft.variant, reason
+                ));
+            } else {
+                state.suggestion = None;
+            }
+        }
+    }
+
+    /// Selects the aircraft that has gone the longest without a flight, preferring
+    /// aircraft that have never been flown.
+    ///
+    /// # Returns
+    ///
+    /// The suggested aircraft, or `None` if there are no aircraft.
+    pub(super) fn suggest_aircraft(&mut self) -> Option<Arc<Aircraft>> {
+        let never_flown: Vec<&Arc<Aircraft>> = self
+            .all_aircraft
+            .iter()
+            .filter(|aircraft| aircraft.flown == 0)
+            .collect();
+
+        let (aircraft, reason) = match never_flown.choose(&mut rand::thread_rng()) {
+            Some(aircraft) => (
+                Arc::clone(aircraft),
+                format!("never flown, like {} other aircraft", never_flown.len() - 1),
+            ),
+            None => {
+                let aircraft = self
+                    .all_aircraft
+                    .iter()
+                    .min_by(|a, b| a.date_flown.cmp(&b.date_flown))?;
+                let reason = match aircraft.date_flown.as_deref() {
+                    Some(date_flown) => match NaiveDate::parse_from_str(date_flown, "%Y-%m-%d") {
+                        Ok(date) => format!(
+                            "longest without a flight, last flown {} days ago on {}",
+                            (chrono::Local::now().date_naive() - date).num_days(),
+                            date_flown
+                        ),
+                        Err(_) => format!("longest without a flight, last flown on {}", date_flown),
+                    },
+                    None => "flown, but no date was recorded".to_string(),
+                };
+                (Arc::clone(aircraft), reason)
+            }
+        };
+
+        let state = &mut self.route_aircraft_state;
+        state.selected = vec![Arc::clone(&aircraft)];
+        state.suggestion = Some((Arc::clone(&aircraft), reason));
+        Some(aircraft)
+    }
+
+    /// Points the selected aircraft at the current aircraft list after it was reloaded.
+    pub(super) fn refresh_route_aircraft_selection(&mut self) {
+        let all_aircraft = &self.all_aircraft;
+        self.route_aircraft_state.selected = self
+            .route_aircraft_state
+            .selected
+            .iter()
+            .filter_map(|selected| {
+                all_aircraft
+                    .iter()
+                    .find(|aircraft| aircraft.id == selected.id)
+                    .cloned()
+            })
+            .collect();
+    }
+
+    /// Generates random routes for the selected aircraft.
+    pub(super) fn generate_selected_aircraft_routes(&self) -> Result<Vec<Route>, String> {
+        self.generate_random_routes_generic(&self.route_aircraft_state.selected, GENERATE_AMOUNT)
+    }
+}
@@ -0,0 +1,48 @@
+use super::custom_route::{airport_label, airport_matches};
+use super::searchable_dropdown::SearchableDropdown;
+use super::Gui;
+use crate::models::Airport;
+use eframe::egui;
+use std::sync::Arc;
+
+/// The airport generated routes arrive at, when one is fixed.
+#[derive(Default)]
+pub struct RouteDestinationState {
+    /// The fixed destination, random destinations when `None`.
+    pub airport: Option<Arc<Airport>>,
+    /// Search query of the airport dropdown.
+    search: String,
+}
+
+impl Gui<'_> {
+    /// Shows the dropdown for fixing the destination of generated routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    pub(super) fn show_route_destination_picker(&mut self, ui: &mut egui::Ui) {
+        let Some(route_generator) = &self.route_generator else {
+            return;
+        };
+        let airport_codes = &self.airport_codes;
+        let state = &mut self.route_destination_state;
+
+        ui.horizontal(|ui| {
+            let label = ui
+                .label("Arrive at:")
+                .on_hover_text("Generate routes to this airport instead of random destinations");
+            SearchableDropdown::new(
+                "route_destination",
+                &route_generator.all_airports,
+                airport_label,
+                &|airport: &Airport, query: &str| airport_matches(airport_codes, airport, query),
+            )
+            .labelled_by(label.id)
+            .show(ui, &mut state.search, &mut state.airport);
+
+            if state.airport.is_some() && ui.small_button("Clear").clicked() {
+                state.airport = None;
+            }
+        });
+    }
+}
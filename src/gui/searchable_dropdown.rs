@@ -0,0 +1,373 @@
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The maximum number of matches listed at once, to keep large lists responsive.
+const MAX_VISIBLE_MATCHES: usize = 200;
+
+/// A combo box with a search field for picking an item from a large list.
+pub struct SearchableDropdown<'a, T> {
+    /// The unique ID of the combo box.
+    id_salt: &'a str,
+    /// The items to choose from.
+    items: &'a [Arc<T>],
+    /// Formats an item for display.
+    display: fn(&T) -> String,
+    /// Checks whether an item matches the lowercase search query.
+    matches: &'a dyn Fn(&T, &str) -> bool,
+    /// The label that names the dropdown for screen readers.
+    labelled_by: Option<egui::Id>,
+    /// Titled groups of items listed above all items while nothing is searched.
+    sections: Vec<(&'a str, &'a [Arc<T>])>,
+    /// Returns the group of an item, to list items under collapsible headers.
+    group_by: Option<fn(&T) -> String>,
+    /// A checkbox that hides the items not passing a predicate while checked.
+    filter: Option<ItemFilter<'a, T>>,
+}
+
+/// A checkbox in the dropdown that limits the listed items.
+struct ItemFilter<'a, T> {
+    /// The text of the checkbox.
+    label: &'a str,
+    /// Returns whether an item is listed while the checkbox is checked.
+    keep: fn(&T) -> bool,
+    /// Whether the checkbox is checked, kept between frames.
+    enabled: &'a mut bool,
+}
+
+impl<'a, T> SearchableDropdown<'a, T> {
+    /// Creates a new dropdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_salt` - The unique ID of the combo box.
+    /// * `items` - The items to choose from.
+    /// * `display` - Formats an item for display.
+    /// * `matches` - Checks whether an item matches the lowercase search query.
+    pub fn new(
+        id_salt: &'a str,
+        items: &'a [Arc<T>],
+        display: fn(&T) -> String,
+        matches: &'a dyn Fn(&T, &str) -> bool,
+    ) -> Self {
+        SearchableDropdown {
+            id_salt,
+            items,
+            display,
+            matches,
+            labelled_by: None,
+            sections: Vec::new(),
+            group_by: None,
+            filter: None,
+        }
+    }
+
+    /// Lists the items under collapsible headers, one per group. The headers
+    /// are opened while searching.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_by` - Returns the group of an item.
+    pub fn grouped_by(mut self, group_by: fn(&T) -> String) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+
+    /// Adds a checkbox that hides the items not passing a predicate while checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The text of the checkbox.
+    /// * `keep` - Returns whether an item is listed while the checkbox is checked.
+    /// * `enabled` - Whether the checkbox is checked.
+    pub fn filter(mut self, label: &'a str, keep: fn(&T) -> bool, enabled: &'a mut bool) -> Self {
+        self.filter = Some(ItemFilter {
+            label,
+            keep,
+            enabled,
+        });
+        self
+    }
+
+    /// Adds a titled group of items, such as recent picks, shown above all items
+    /// while the search is empty. Empty sections are not shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The heading of the section.
+    /// * `items` - The items in the section.
+    pub fn section(mut self, title: &'a str, items: &'a [Arc<T>]) -> Self {
+        self.sections.push((title, items));
+        self
+    }
+
+    /// Names the dropdown and its search field after a label for screen readers.
+    ///
+    /// # Arguments
+    ///
+    /// * `label_id` - The ID of the label widget.
+    pub fn labelled_by(mut self, label_id: egui::Id) -> Self {
+        self.labelled_by = Some(label_id);
+        self
+    }
+
+    /// Shows the dropdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    /// * `search` - The search query, kept between frames.
+    /// * `selected` - The selected item.
+    ///
+    /// # Returns
+    ///
+    /// Whether the selection changed.
+    pub fn show(
+        self,
+        ui: &mut egui::Ui,
+        search: &mut String,
+        selected: &mut Option<Arc<T>>,
+    ) -> bool {
+        let mut changed = false;
+        let display = self.display;
+        let selected_text = selected
+            .as_ref()
+            .map_or_else(|| "Select...".to_string(), |item| display(item));
+
+        self.show_popup(ui, search, selected_text, |ui, item| {
+            let is_selected = selected
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, item));
+            if ui.selectable_label(is_selected, display(item)).clicked() {
+                *selected = Some(Arc::clone(item));
+                changed = true;
+                ui.memory_mut(|memory| memory.close_popup());
+            }
+        });
+
+        changed
+    }
+
+    /// Shows the dropdown with a checkbox per item, so several items can be picked.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    /// * `search` - The search query, kept between frames.
+    /// * `selected` - The selected items.
+    ///
+    /// # Returns
+    ///
+    /// Whether the selection changed.
+    pub fn show_multiple(
+        self,
+        ui: &mut egui::Ui,
+        search: &mut String,
+        selected: &mut Vec<Arc<T>>,
+    ) -> bool {
+        let mut changed = false;
+        let display = self.display;
+        let selected_text = match selected.as_slice() {
+            [] => "Select...".to_string(),
+            [item] => display(item),
+            items => format!("{} selected", items.len()),
+        };
+
+        self.show_popup(ui, search, selected_text, |ui, item| {
+            let position = selected
+                .iter()
+                .position(|current| Arc::ptr_eq(current, item));
+            let mut checked = position.is_some();
+            if ui.checkbox(&mut checked, display(item)).changed() {
+                match position {
+                    Some(position) => {
+                        selected.remove(position);
+                    }
+                    None => selected.push(Arc::clone(item)),
+                }
+                changed = true;
+            }
+        });
+
+        changed
+    }
+
+    /// Shows the combo box with the search field, filter, sections and matching items.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    /// * `search` - The search query, kept between frames.
+    /// * `selected_text` - The text shown on the closed combo box.
+    /// * `add_item` - Adds the widget for one item to the list.
+    fn show_popup(
+        self,
+        ui: &mut egui::Ui,
+        search: &mut String,
+        selected_text: String,
+        mut add_item: impl FnMut(&mut egui::Ui, &Arc<T>),
+    ) {
+        let mut filter = self.filter;
+
+        let response = egui::ComboBox::from_id_salt(self.id_salt)
+            .selected_text(selected_text)
+            .width(300.0)
+            .height(400.0)
+            // Keep the popup open while typing in the search field or ticking items.
+            .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+            .show_ui(ui, |ui| {
+                let search_response =
+                    ui.add(egui::TextEdit::singleline(search).hint_text("Type to search..."));
+                if let Some(label_id) = self.labelled_by {
+                    search_response.labelled_by(label_id);
+                }
+                if let Some(filter) = &mut filter {
+                    ui.checkbox(filter.enabled, filter.label);
+                }
+                ui.separator();
+
+                let query = search.to_lowercase();
+                if query.is_empty() {
+                    for (title, items) in
+                        self.sections.iter().filter(|(_, items)| !items.is_empty())
+                    {
+                        ui.strong(*title);
+                        for item in *items {
+                            add_item(ui, item);
+                        }
+                        ui.separator();
+                    }
+                }
+
+                let visible = self
+                    .items
+                    .iter()
+                    .filter(|item| {
+                        filter
+                            .as_ref()
+                            .is_none_or(|filter| !*filter.enabled || (filter.keep)(item))
+                    })
+                    .filter(|item| query.is_empty() || (self.matches)(item, &query))
+                    .take(MAX_VISIBLE_MATCHES);
+
+                let Some(group_by) = self.group_by else {
+                    for item in visible {
+                        add_item(ui, item);
+                    }
+                    return;
+                };
+
+                let mut groups: BTreeMap<String, Vec<&Arc<T>>> = BTreeMap::new();
+                for item in visible {
+                    groups.entry(group_by(item)).or_default().push(item);
+                }
+                for (group, items) in groups {
+                    egui::CollapsingHeader::new(format!("{} ({})", group, items.len()))
+                        .id_salt((self.id_salt, &group))
+                        .default_open(false)
+                        .open((!query.is_empty()).then_some(true))
+                        .show(ui, |ui| {
+                            for item in items {
+                                add_item(ui, item);
+                            }
+                        });
+                }
+            })
+            .response;
+
+        if let Some(label_id) = self.labelled_by {
+            response.labelled_by(label_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchableDropdown;
+    use eframe::egui::{self, accesskit};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const CAPTIONS: [&str; 3] = ["Departure:", "Destination:", "Aircraft:"];
+
+    /// Runs a frame of labelled dropdowns laid out like the custom route
+    /// window and returns its accessibility tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    /// * `items` - The items of the dropdowns.
+    /// * `events` - The input events of the frame.
+    fn show_form(
+        ctx: &egui::Context,
+        items: &[Arc<String>],
+        events: Vec<egui::Event>,
+    ) -> HashMap<accesskit::NodeId, accesskit::Node> {
+        let input = egui::RawInput {
+            events,
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                egui::Grid::new("form").num_columns(2).show(ui, |ui| {
+                    for caption in CAPTIONS {
+                        let label = ui.label(caption);
+                        SearchableDropdown::new(
+                            caption,
+                            items,
+                            |item| item.to_string(),
+                            &|_, _| true,
+                        )
+                        .labelled_by(label.id)
+                        .show(ui, &mut String::new(), &mut None);
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+
+        output
+            .platform_output
+            .accesskit_update
+            .expect("accesskit is enabled")
+            .nodes
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn tab_visits_dropdowns_in_reading_order_under_their_captions() {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let items = vec![Arc::new("EHAM".to_string())];
+        let tab = egui::Event::Key {
+            key: egui::Key::Tab,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        // The grid hides its cells while it measures them in the first frame.
+        show_form(&ctx, &items, Vec::new());
+
+        let mut visited = Vec::new();
+        for _ in CAPTIONS {
+            let tree = show_form(&ctx, &items, vec![tab.clone()]);
+            let focused = ctx
+                .memory(|memory| memory.focused())
+                .expect("Tab focuses a widget");
+            let node = &tree[&accesskit::NodeId::from(focused.value())];
+            assert_eq!(node.role(), accesskit::Role::ComboBox);
+
+            let captions: Vec<&str> = node
+                .labelled_by()
+                .iter()
+                .filter_map(|label| tree[label].value())
+                .collect();
+            visited.push(captions.concat());
+        }
+
+        assert_eq!(visited, CAPTIONS);
+    }
+}
@@ -0,0 +1,121 @@
+use super::{Gui, RouteSource};
+use crate::modules::routes::CompassSector;
+use crate::modules::seasons::SEASONAL_PRESETS;
+use crate::SESSION_FILENAME;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The window size used when no session was saved.
+const DEFAULT_WINDOW_SIZE: [f32; 2] = [1200.0, 768.0];
+
+/// The choices of the last session, restored at startup.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Session {
+    /// The inner size of the window in points.
+    window_size: Option<[f32; 2]>,
+    /// The IDs of the aircraft picked in the side panel.
+    selected_aircraft: Vec<i32>,
+    /// The ICAO code of the airport generated routes arrive at.
+    destination: Option<String>,
+    /// The compass sector generated destinations have to lie in.
+    direction: Option<CompassSector>,
+    /// The name of the seasonal suggestion generated destinations have to lie in.
+    seasonal_preset: Option<String>,
+    /// The aircraft the displayed routes were generated for, which more
+    /// routes are generated for as well.
+    route_source: RouteSource,
+}
+
+impl Session {
+    /// Loads the session, returning an empty one if it is missing or unreadable.
+    pub fn load() -> Session {
+        let path = Path::new(SESSION_FILENAME);
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Session::default();
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable session {}: {}", path.display(), e);
+            Session::default()
+        })
+    }
+
+    /// Writes the session to disk.
+    fn save(&self) -> Result<(), String> {
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(SESSION_FILENAME, contents).map_err(|e| e.to_string())
+    }
+
+    /// Returns the size to open the window with.
+    pub fn window_size(&self) -> egui::Vec2 {
+        self.window_size.unwrap_or(DEFAULT_WINDOW_SIZE).into()
+    }
+}
+
+impl Gui<'_> {
+    /// Restores the choices of the last session that do not need the airports.
+    ///
+    /// The destination is restored by `restore_session_destination` once the
+    /// airports are loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The last session.
+    pub(super) fn restore_session(&mut self, session: Session) {
+        self.route_aircraft_state.selected = self
+            .all_aircraft
+            .iter()
+            .filter(|aircraft| session.selected_aircraft.contains(&aircraft.id))
+            .map(Arc::clone)
+            .collect();
+        self.route_direction = session.direction;
+        self.seasonal_preset = session
+            .seasonal_preset
+            .and_then(|name| SEASONAL_PRESETS.iter().find(|preset| preset.name == name));
+        self.popup_state.route_source = session.route_source;
+        self.session_destination = session.destination;
+    }
+
+    /// Restores the destination of the last session, once the airports are loaded.
+    pub(super) fn restore_session_destination(&mut self) {
+        let (Some(icao), Some(route_generator)) =
+            (self.session_destination.take(), &self.route_generator)
+        else {
+            return;
+        };
+
+        self.route_destination_state.airport = route_generator
+            .find_airport_by_icao(self.airport_codes.current_icao(&icao))
+            .cloned();
+    }
+
+    /// Saves the choices of this session for the next startup.
+    pub(super) fn save_session(&self) {
+        let session = Session {
+            window_size: self.window_size.map(Into::into),
+            selected_aircraft: self
+                .route_aircraft_state
+                .selected
+                .iter()
+                .map(|aircraft| aircraft.id)
+                .collect(),
+            destination: self
+                .route_destination_state
+                .airport
+                .as_ref()
+                .map(|airport| airport.ICAO.clone())
+                // Keep a destination restored before the airports finished loading.
+                .or_else(|| self.session_destination.clone()),
+            direction: self.route_direction,
+            seasonal_preset: self.seasonal_preset.map(|preset| preset.name.to_string()),
+            route_source: self.popup_state.route_source,
+        };
+
+        if let Err(e) = session.save() {
+            log::warn!("Failed to save session: {}", e);
+        }
+    }
+}
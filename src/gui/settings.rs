@@ -0,0 +1,965 @@
+use super::goals::FleetGoal;
+use super::presets::GenerationPreset;
+use super::Gui;
+use crate::models::DataSource;
+use crate::modules::airac::AiracCycle;
+use crate::modules::callsign::CallsignStyle;
+use crate::modules::data_source::oldest_data_age_days;
+use crate::modules::routes::RouteConstraints;
+use crate::SETTINGS_FILENAME;
+use chrono::{TimeDelta, Utc};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+/// How tightly the table and popups are laid out.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// Smaller rows, padding and text, for small laptop screens.
+    Compact,
+    /// The default layout.
+    #[default]
+    Comfortable,
+}
+
+impl Density {
+    pub const ALL: [Density; 2] = [Density::Compact, Density::Comfortable];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Density::Compact => "Compact",
+            Density::Comfortable => "Comfortable",
+        }
+    }
+
+    /// Returns the height of a table row.
+    pub fn row_height(&self) -> f32 {
+        match self {
+            Density::Compact => 22.0,
+            Density::Comfortable => 30.0,
+        }
+    }
+
+    /// Returns the height of the table header.
+    pub fn header_height(&self) -> f32 {
+        match self {
+            Density::Compact => 16.0,
+            Density::Comfortable => 20.0,
+        }
+    }
+
+    /// Returns the factor applied to the default font sizes.
+    fn font_scale(&self) -> f32 {
+        match self {
+            Density::Compact => 0.9,
+            Density::Comfortable => 1.0,
+        }
+    }
+
+    /// Returns the factor applied to the default spacing and padding.
+    fn spacing_scale(&self) -> f32 {
+        match self {
+            Density::Compact => 0.6,
+            Density::Comfortable => 1.0,
+        }
+    }
+}
+
+/// A virtual airline the flights are flown for.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct VirtualAirline {
+    /// The name recorded with the flights in the history.
+    pub name: String,
+    /// The airline code used for callsigns, such as `KLM`.
+    pub callsign: String,
+    /// The ICAO codes of the airports most routes depart from.
+    pub hubs: Vec<String>,
+}
+
+/// How many rows before the end of the route list more routes are generated.
+const DISTANCE_FROM_BOTTOM_TO_LOAD_MORE: usize = 0;
+/// The smallest route list that is extended automatically when scrolled to the end.
+const MIN_ITEMS_FOR_LAZY_LOAD: usize = 1;
+
+/// The range offered by the UI scale slider.
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.75..=2.5;
+/// The allowed rows from the end of the route list at which more routes are generated.
+const LOAD_MORE_DISTANCE_RANGE: RangeInclusive<usize> = 0..=100;
+/// The allowed smallest route list that is extended automatically.
+const MIN_ITEMS_FOR_LAZY_LOAD_RANGE: RangeInclusive<usize> = 1..=1000;
+/// The allowed range reserve in percent.
+const RANGE_RESERVE_RANGE: RangeInclusive<u32> = 0..=50;
+/// The allowed time in minutes added to the time en route for the block time.
+const BLOCK_OVERHEAD_RANGE: RangeInclusive<u32> = 0..=120;
+/// The allowed distance to the nearest usable airport in nautical miles, 0 to skip the check.
+const DIVERSION_LIMIT_RANGE: RangeInclusive<u32> = 0..=3000;
+/// The allowed shortest generated route in nautical miles.
+const MIN_LEG_RANGE: RangeInclusive<u32> = 0..=5000;
+/// The allowed longest generated route in nautical miles, 0 for no limit.
+const MAX_LEG_RANGE: RangeInclusive<u32> = 0..=10000;
+/// The allowed age in days above which the airport data is out of date.
+const MAX_DATA_AGE_RANGE: RangeInclusive<u32> = 1..=3650;
+
+/// The version of the settings file written by this build.
+///
+/// Raise it together with a new entry in [`MIGRATIONS`] whenever a setting is
+/// renamed, removed or changes meaning.
+const SETTINGS_VERSION: u32 = 1;
+
+/// Upgrades a settings table to the next version, indexed by the version it upgrades from.
+const MIGRATIONS: [fn(&mut toml::Table); SETTINGS_VERSION as usize] = [migrate_unversioned];
+
+/// Upgrades a file written before the settings were versioned.
+///
+/// Those files use the same keys as version 1, so there is nothing to change.
+fn migrate_unversioned(_table: &mut toml::Table) {}
+
+/// User preferences that persist between sessions.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The version of the file the settings were read from, see [`SETTINGS_VERSION`].
+    pub version: u32,
+    /// The layout density of the table and popups.
+    pub density: Density,
+    /// The zoom factor applied on top of the scaling reported by the system.
+    pub ui_scale: f32,
+    /// Whether to use stronger text and outline colors.
+    pub high_contrast: bool,
+    /// Whether scrolling to the end of the route list generates more routes.
+    /// When off, a "Load more" button is shown instead.
+    pub infinite_scroll: bool,
+    /// How many rows before the end of the route list more routes are generated.
+    pub load_more_distance: usize,
+    /// The smallest route list that is extended automatically.
+    pub min_items_for_lazy_load: usize,
+    /// The share of the aircraft range not used for generated routes, in percent.
+    pub range_reserve_percent: u32,
+    /// The minutes of taxiing, climbing and descending added to the time
+    /// en route for the block time of a route.
+    pub block_overhead_minutes: u32,
+    /// The farthest a route may stray from an airport its aircraft can divert
+    /// to, in nautical miles, or 0 to skip the check.
+    pub diversion_limit_nm: u32,
+    /// The shortest generated route in nautical miles.
+    pub min_leg_nm: u32,
+    /// The longest generated route in nautical miles, limited by the aircraft range only when 0.
+    pub max_leg_nm: u32,
+    /// Whether to keep gliders and other light aircraft away from major airports.
+    pub light_aircraft_avoid_major_airports: bool,
+    /// Whether generated routes have to arrive in daylight, for pilots flying
+    /// by visual flight rules only.
+    pub daylight_arrivals_only: bool,
+    /// The age in days above which the airport data is reported as out of date.
+    pub max_data_age_days: u32,
+    /// The goal of flying every aircraft within a period, if one is set.
+    pub fleet_goal: Option<FleetGoal>,
+    /// How the callsigns of routes are formed.
+    pub callsign_style: CallsignStyle,
+    /// The airline code used for airline callsigns.
+    pub airline_prefix: String,
+    /// The nationality prefix used for registration callsigns.
+    pub registration_prefix: String,
+    /// The ICAO code of the airport flights start from by default, if one is set.
+    pub home_base: Option<String>,
+    /// The virtual airline the flights are flown for, if any.
+    pub virtual_airline: Option<VirtualAirline>,
+    /// The saved generation presets, in the order they are shown.
+    pub generation_presets: Vec<GenerationPreset>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            density: Density::default(),
+            ui_scale: 1.0,
+            high_contrast: false,
+            infinite_scroll: true,
+            load_more_distance: DISTANCE_FROM_BOTTOM_TO_LOAD_MORE,
+            min_items_for_lazy_load: MIN_ITEMS_FOR_LAZY_LOAD,
+            range_reserve_percent: 0,
+            block_overhead_minutes: 20,
+            diversion_limit_nm: 400,
+            min_leg_nm: 0,
+            max_leg_nm: 0,
+            light_aircraft_avoid_major_airports: false,
+            daylight_arrivals_only: false,
+            max_data_age_days: 365,
+            fleet_goal: None,
+            callsign_style: CallsignStyle::default(),
+            airline_prefix: "FPL".to_string(),
+            registration_prefix: "PH".to_string(),
+            home_base: None,
+            virtual_airline: None,
+            generation_presets: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings, upgrading files of older versions and correcting invalid values.
+    ///
+    /// Settings that cannot be read are replaced by their defaults one by one,
+    /// keeping the others. Whenever anything is upgraded, dropped or corrected,
+    /// the original file is first copied next to it with a `.bak` extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The settings file.
+    pub fn load(path: &Path) -> Settings {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Settings::default();
+        };
+        let mut table = match contents.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(e) => {
+                log::warn!("Ignoring unreadable settings {}: {}", path.display(), e);
+                backup_settings(path);
+                return Settings::default();
+            }
+        };
+
+        let mut changed = false;
+        let version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .unwrap_or(0);
+        match u32::try_from(version) {
+            Ok(version) if version <= SETTINGS_VERSION => {
+                for migration in &MIGRATIONS[version as usize..] {
+                    migration(&mut table);
+                }
+                changed |= version < SETTINGS_VERSION;
+            }
+            _ => {
+                log::warn!(
+                    "Settings {} were written by a newer version ({}), settings unknown to this version are ignored",
+                    path.display(),
+                    version
+                );
+                changed = true;
+            }
+        }
+        table.insert("version".to_string(), SETTINGS_VERSION.into());
+
+        let (mut settings, rejected) = Settings::from_table(table);
+        for key in &rejected {
+            log::warn!("Ignoring invalid setting {} in {}", key, path.display());
+        }
+        let corrected = settings.validate();
+        for name in &corrected {
+            log::warn!(
+                "Corrected out of range setting {} in {}",
+                name,
+                path.display()
+            );
+        }
+
+        if changed || !rejected.is_empty() || !corrected.is_empty() {
+            backup_settings(path);
+        }
+        settings
+    }
+
+    /// Reads the settings from a table, keeping the default of every setting
+    /// that cannot be read.
+    ///
+    /// # Returns
+    ///
+    /// The settings and the keys that were ignored.
+    fn from_table(table: toml::Table) -> (Settings, Vec<String>) {
+        if let Ok(settings) = toml::Value::Table(table.clone()).try_into() {
+            return (settings, Vec::new());
+        }
+
+        let mut accepted = toml::Table::new();
+        let mut rejected = Vec::new();
+        for (key, value) in table {
+            let mut candidate = accepted.clone();
+            candidate.insert(key.clone(), value);
+            if toml::Value::Table(candidate.clone())
+                .try_into::<Settings>()
+                .is_ok()
+            {
+                accepted = candidate;
+            } else {
+                rejected.push(key);
+            }
+        }
+        let settings = toml::Value::Table(accepted).try_into().unwrap_or_default();
+
+        (settings, rejected)
+    }
+
+    /// Clamps the settings to the values the settings window allows.
+    ///
+    /// # Returns
+    ///
+    /// The names of the settings that were corrected.
+    fn validate(&mut self) -> Vec<&'static str> {
+        fn clamp<T: PartialOrd + Copy>(
+            value: &mut T,
+            range: &RangeInclusive<T>,
+            name: &'static str,
+            corrected: &mut Vec<&'static str>,
+        ) {
+            if !range.contains(value) {
+                *value = if *value > *range.end() {
+                    *range.end()
+                } else {
+                    *range.start()
+                };
+                corrected.push(name);
+            }
+        }
+
+        let mut corrected = Vec::new();
+        if self.ui_scale.is_nan() {
+            self.ui_scale = 1.0;
+            corrected.push("ui_scale");
+        }
+        clamp(
+            &mut self.ui_scale,
+            &UI_SCALE_RANGE,
+            "ui_scale",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.load_more_distance,
+            &LOAD_MORE_DISTANCE_RANGE,
+            "load_more_distance",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.min_items_for_lazy_load,
+            &MIN_ITEMS_FOR_LAZY_LOAD_RANGE,
+            "min_items_for_lazy_load",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.range_reserve_percent,
+            &RANGE_RESERVE_RANGE,
+            "range_reserve_percent",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.block_overhead_minutes,
+            &BLOCK_OVERHEAD_RANGE,
+            "block_overhead_minutes",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.diversion_limit_nm,
+            &DIVERSION_LIMIT_RANGE,
+            "diversion_limit_nm",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.min_leg_nm,
+            &MIN_LEG_RANGE,
+            "min_leg_nm",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.max_leg_nm,
+            &MAX_LEG_RANGE,
+            "max_leg_nm",
+            &mut corrected,
+        );
+        clamp(
+            &mut self.max_data_age_days,
+            &MAX_DATA_AGE_RANGE,
+            "max_data_age_days",
+            &mut corrected,
+        );
+
+        corrected
+    }
+
+    /// Returns the limits on the length of generated routes.
+    pub fn route_constraints(&self) -> RouteConstraints {
+        RouteConstraints {
+            range_reserve_percent: self.range_reserve_percent,
+            min_distance_nm: self.min_leg_nm,
+            max_distance_nm: (self.max_leg_nm > 0).then_some(self.max_leg_nm),
+            direction: None,
+            area: None,
+            departure_area: None,
+            region: None,
+            min_path_latitude: None,
+            avoid_major_airports: self.light_aircraft_avoid_major_airports,
+            // Routes leave the gate now.
+            daylight_arrivals: self
+                .daylight_arrivals_only
+                .then(|| Utc::now() + TimeDelta::minutes(i64::from(self.block_overhead_minutes))),
+        }
+    }
+
+    /// Returns the prefix of the selected callsign style.
+    ///
+    /// Airline callsigns use the code of the virtual airline when one is set.
+    pub fn callsign_prefix(&self) -> &str {
+        match self.callsign_style {
+            CallsignStyle::Airline => self
+                .virtual_airline
+                .as_ref()
+                .map(|airline| airline.callsign.as_str())
+                .filter(|callsign| !callsign.trim().is_empty())
+                .unwrap_or(&self.airline_prefix),
+            CallsignStyle::Registration => &self.registration_prefix,
+        }
+    }
+
+    /// Writes the settings to disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The settings file.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+        // Writing next to the file and renaming keeps the old settings if writing fails.
+        let temporary = path.with_extension("toml.tmp");
+        std::fs::write(&temporary, contents).map_err(|e| e.to_string())?;
+        std::fs::rename(&temporary, path).map_err(|e| e.to_string())
+    }
+
+    /// Applies the settings to the dark and light egui styles, starting from the defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub fn apply(&self, ctx: &egui::Context) {
+        let defaults = egui::Style::default();
+        let font_scale = self.density.font_scale();
+        let spacing_scale = self.density.spacing_scale();
+
+        ctx.set_zoom_factor(
+            self.ui_scale
+                .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end()),
+        );
+        ctx.all_styles_mut(|style| {
+            style.visuals = if style.visuals.dark_mode {
+                egui::Visuals::dark()
+            } else {
+                egui::Visuals::light()
+            };
+            if self.high_contrast {
+                apply_high_contrast(&mut style.visuals);
+            }
+
+            for (text_style, font_id) in &mut style.text_styles {
+                font_id.size = defaults.text_styles[text_style].size * font_scale;
+            }
+
+            let spacing = &mut style.spacing;
+            spacing.item_spacing = defaults.spacing.item_spacing * spacing_scale;
+            spacing.button_padding = defaults.spacing.button_padding * spacing_scale;
+            spacing.window_margin = defaults.spacing.window_margin * spacing_scale;
+            spacing.menu_margin = defaults.spacing.menu_margin * spacing_scale;
+            spacing.interact_size.y = defaults.spacing.interact_size.y * font_scale;
+        });
+    }
+}
+
+/// Makes text and widget outlines stand out against the background.
+///
+/// # Arguments
+///
+/// * `visuals` - The visuals to adjust.
+fn apply_high_contrast(visuals: &mut egui::Visuals) {
+    let (foreground, background) = if visuals.dark_mode {
+        (egui::Color32::WHITE, egui::Color32::BLACK)
+    } else {
+        (egui::Color32::BLACK, egui::Color32::WHITE)
+    };
+
+    visuals.override_text_color = Some(foreground);
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.extreme_bg_color = background;
+    visuals.window_stroke = egui::Stroke::new(1.0, foreground);
+    visuals.selection.stroke = egui::Stroke::new(2.0, foreground);
+
+    let widgets = &mut visuals.widgets;
+    for widget in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        widget.fg_stroke = egui::Stroke::new(widget.fg_stroke.width.max(1.0), foreground);
+        widget.bg_stroke = egui::Stroke::new(widget.bg_stroke.width.max(1.0), foreground);
+    }
+}
+
+/// Returns a warning if the oldest airport data is older than the configured age.
+///
+/// # Arguments
+///
+/// * `data_sources` - The data sources recorded in the airport database.
+/// * `settings` - The settings holding the maximum age.
+pub fn data_age_warning(data_sources: &[DataSource], settings: &Settings) -> Option<String> {
+    let age = oldest_data_age_days(data_sources, chrono::Local::now().date_naive())?;
+
+    (age > i64::from(settings.max_data_age_days))
+        .then(|| format!("⚠ Airport data is {} days old", age))
+}
+
+impl Gui<'_> {
+    /// Shows the shortest and longest route to generate, saving any change.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI context.
+    pub(super) fn show_route_distance(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.settings;
+        let mut changed = false;
+
+        ui.label("Route distance:");
+        ui.horizontal(|ui| {
+            let response = ui
+                .add(
+                    egui::DragValue::new(&mut settings.min_leg_nm)
+                        .range(MIN_LEG_RANGE)
+                        .speed(10)
+                        .suffix(" NM"),
+                )
+                .on_hover_text("Shortest route to generate");
+            if response.changed() && settings.max_leg_nm > 0 {
+                settings.max_leg_nm = settings.max_leg_nm.max(settings.min_leg_nm);
+            }
+            changed |= response.drag_stopped() || (response.changed() && !response.dragged());
+
+            ui.label("to");
+            let response = ui
+                .add(
+                    egui::DragValue::new(&mut settings.max_leg_nm)
+                        .range(MAX_LEG_RANGE)
+                        .speed(10)
+                        .custom_formatter(|value, _| match value {
+                            0.0 => "range".to_string(),
+                            value => format!("{} NM", value),
+                        })
+                        .custom_parser(|text| {
+                            let text = text.trim().trim_end_matches("NM").trim();
+                            match text {
+                                "range" => Some(0.0),
+                                text => text.parse().ok(),
+                            }
+                        }),
+                )
+                .on_hover_text("Longest route to generate, 0 for the aircraft range");
+            if response.changed() && settings.max_leg_nm > 0 {
+                settings.min_leg_nm = settings.min_leg_nm.min(settings.max_leg_nm);
+            }
+            changed |= response.drag_stopped() || (response.changed() && !response.dragged());
+        });
+
+        if changed {
+            if let Err(e) = settings.save(Path::new(SETTINGS_FILENAME)) {
+                log::warn!("Failed to save settings: {}", e);
+            }
+        }
+    }
+
+    /// Shows the settings window, saving and applying any change.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_settings_window(&mut self, ctx: &egui::Context) {
+        let mut changed = false;
+        let mut open = self.show_settings;
+
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("UI scale:");
+                    let response = ui.add(
+                        egui::Slider::new(&mut self.settings.ui_scale, UI_SCALE_RANGE)
+                            .step_by(0.05)
+                            .fixed_decimals(2),
+                    );
+                    // Rescaling while dragging moves the slider away from the cursor.
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                });
+                changed |= ui
+                    .checkbox(&mut self.settings.high_contrast, "High contrast")
+                    .changed();
+                ui.horizontal(|ui| {
+                    ui.label("Density:");
+                    for density in Density::ALL {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.settings.density,
+                                density,
+                                density.display_name(),
+                            )
+                            .changed();
+                    }
+                });
+
+                ui.separator();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.infinite_scroll,
+                        "Load more routes when scrolling to the end",
+                    )
+                    .changed();
+                ui.add_enabled_ui(self.settings.infinite_scroll, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Rows from the end:");
+                        let response = ui.add(
+                            egui::DragValue::new(&mut self.settings.load_more_distance)
+                                .range(LOAD_MORE_DISTANCE_RANGE),
+                        );
+                        changed |=
+                            response.drag_stopped() || (response.changed() && !response.dragged());
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Minimum list length:");
+                        let response = ui.add(
+                            egui::DragValue::new(&mut self.settings.min_items_for_lazy_load)
+                                .range(MIN_ITEMS_FOR_LAZY_LOAD_RANGE),
+                        );
+                        changed |=
+                            response.drag_stopped() || (response.changed() && !response.dragged());
+                    });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Range reserve:");
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.range_reserve_percent)
+                                .range(RANGE_RESERVE_RANGE)
+                                .suffix(" %"),
+                        )
+                        .on_hover_text(
+                            "Share of the aircraft range kept unused by generated routes",
+                        );
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Taxi and climb time:");
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.block_overhead_minutes)
+                                .range(BLOCK_OVERHEAD_RANGE)
+                                .suffix(" min"),
+                        )
+                        .on_hover_text("Time added to the time en route for the block time");
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Diversion distance:");
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.diversion_limit_nm)
+                                .range(DIVERSION_LIMIT_RANGE)
+                                .speed(10)
+                                .custom_formatter(|value, _| match value {
+                                    0.0 => "off".to_string(),
+                                    value => format!("{} NM", value),
+                                })
+                                .custom_parser(|text| {
+                                    let text = text.trim().trim_end_matches("NM").trim();
+                                    match text {
+                                        "off" => Some(0.0),
+                                        text => text.parse().ok(),
+                                    }
+                                }),
+                        )
+                        .on_hover_text(
+                            "Farthest a route may be from an airport to divert to, \
+                             as for twin engines over water. 0 turns the check off",
+                        );
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                });
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.light_aircraft_avoid_major_airports,
+                        "Keep light aircraft away from major airports",
+                    )
+                    .on_hover_text(
+                        "Gliders and ultralights with a range up to 300 NM avoid airports \
+                         with a runway of 11,000 ft or longer",
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.daylight_arrivals_only,
+                        "Daylight arrivals only",
+                    )
+                    .on_hover_text(
+                        "Only generate routes arriving between sunrise and sunset, \
+                         leaving the gate now",
+                    )
+                    .changed();
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Callsign:");
+                    for style in CallsignStyle::ALL {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.settings.callsign_style,
+                                style,
+                                style.display_name(),
+                            )
+                            .changed();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let (label, prefix) = match self.settings.callsign_style {
+                        CallsignStyle::Airline => {
+                            ("Airline code:", &mut self.settings.airline_prefix)
+                        }
+                        CallsignStyle::Registration => (
+                            "Registration prefix:",
+                            &mut self.settings.registration_prefix,
+                        ),
+                    };
+                    let label = ui.label(label);
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(prefix).desired_width(60.0))
+                        .labelled_by(label.id)
+                        .lost_focus();
+                });
+
+                ui.separator();
+                changed |= self.home_base_settings(ui);
+
+                ui.separator();
+                changed |= self.virtual_airline_settings(ui);
+
+                ui.separator();
+                ui.label("Airport data:");
+                if self.data_sources.is_empty() {
+                    ui.weak("No data sources recorded");
+                }
+                egui::Grid::new("data_sources")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for source in &self.data_sources {
+                            ui.label(&source.name);
+                            ui.label(&source.version);
+                            ui.label(&source.date);
+                            ui.label(format!("{} airports", source.airports));
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("Warn when older than:");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.settings.max_data_age_days)
+                            .range(MAX_DATA_AGE_RANGE)
+                            .suffix(" days"),
+                    );
+                    changed |=
+                        response.drag_stopped() || (response.changed() && !response.dragged());
+                });
+                if let Some(warning) = data_age_warning(&self.data_sources, &self.settings) {
+                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                }
+                let today = chrono::Local::now().date_naive();
+                match self.airac_cycle {
+                    Some(cycle) => ui.label(format!(
+                        "AIRAC cycle: {} (effective {}), current cycle {}",
+                        cycle,
+                        cycle.effective_date(),
+                        AiracCycle::at(today)
+                    )),
+                    None => ui.label(format!(
+                        "AIRAC cycle: not set, current cycle {}",
+                        AiracCycle::at(today)
+                    )),
+                }
+                .on_hover_text("Set with `flight-planner airac set <cycle>`");
+            });
+
+        self.show_settings = open;
+        if changed {
+            self.settings.apply(ctx);
+            if let Err(e) = self.settings.save(Path::new(SETTINGS_FILENAME)) {
+                log::warn!("Failed to save settings: {}", e);
+            }
+        }
+    }
+
+    /// Shows the home base part of the settings window.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI of the settings window.
+    ///
+    /// # Returns
+    ///
+    /// Whether a setting changed.
+    fn home_base_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Home base:");
+            let mut code = self.settings.home_base.clone().unwrap_or_default();
+            let response = ui
+                .add(
+                    egui::TextEdit::singleline(&mut code)
+                        .hint_text("none")
+                        .desired_width(60.0),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(
+                    "Default departure of routes, marked on the map and measured from in reports",
+                );
+            if response.changed() {
+                let code = code.trim().to_uppercase();
+                self.settings.home_base = (!code.is_empty()).then_some(code);
+            }
+            changed |= response.lost_focus();
+        });
+
+        if let (Some(code), Some(route_generator)) =
+            (&self.settings.home_base, &self.route_generator)
+        {
+            if route_generator
+                .find_airport_by_icao(self.airport_codes.current_icao(code))
+                .is_none()
+            {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ Unknown home base: {}", code),
+                );
+            }
+        }
+
+        changed
+    }
+
+    /// Shows the virtual airline part of the settings window.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI of the settings window.
+    ///
+    /// # Returns
+    ///
+    /// Whether a setting changed.
+    fn virtual_airline_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        let mut enabled = self.settings.virtual_airline.is_some();
+        if ui
+            .checkbox(&mut enabled, "Fly for a virtual airline")
+            .on_hover_text("Depart mostly from the hubs and record the airline with flights")
+            .changed()
+        {
+            self.settings.virtual_airline = enabled.then(VirtualAirline::default);
+            changed = true;
+        }
+        let Some(airline) = &mut self.settings.virtual_airline else {
+            return changed;
+        };
+
+        egui::Grid::new("virtual_airline")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let label = ui.label("Name:");
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut airline.name).desired_width(160.0))
+                    .labelled_by(label.id)
+                    .lost_focus();
+                ui.end_row();
+
+                let label = ui.label("Callsign:");
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut airline.callsign)
+                            .hint_text("KLM")
+                            .desired_width(60.0),
+                    )
+                    .labelled_by(label.id)
+                    .lost_focus();
+                ui.end_row();
+
+                // The hubs are edited as text and only split once editing is done,
+                // so separators being typed are kept.
+                let label = ui.label("Hubs:");
+                let id = label.id.with("hubs");
+                let mut hubs = ui
+                    .data_mut(|data| data.get_temp::<String>(id))
+                    .unwrap_or_else(|| airline.hubs.join(", "));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut hubs)
+                            .hint_text("EHAM, EHRD")
+                            .desired_width(160.0),
+                    )
+                    .labelled_by(label.id);
+                if response.lost_focus() {
+                    airline.hubs = hubs
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|code| !code.is_empty())
+                        .map(str::to_uppercase)
+                        .collect();
+                    ui.data_mut(|data| data.remove::<String>(id));
+                    changed = true;
+                } else if response.has_focus() {
+                    ui.data_mut(|data| data.insert_temp(id, hubs));
+                }
+                ui.end_row();
+            });
+
+        if let Some(route_generator) = &self.route_generator {
+            let unknown: Vec<&str> = airline
+                .hubs
+                .iter()
+                .filter(|code| {
+                    route_generator
+                        .find_airport_by_icao(self.airport_codes.current_icao(code))
+                        .is_none()
+                })
+                .map(String::as_str)
+                .collect();
+            if !unknown.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ Unknown hubs: {}", unknown.join(", ")),
+                );
+            }
+        }
+
+        changed
+    }
+}
+
+/// Copies the settings file next to itself with a `.bak` extension, before it
+/// is overwritten with upgraded or corrected settings.
+///
+/// # Arguments
+///
+/// * `path` - The settings file.
+fn backup_settings(path: &Path) {
+    let mut backup = PathBuf::from(path);
+    backup.as_mut_os_string().push(".bak");
+    match std::fs::copy(path, &backup) {
+        Ok(_) => log::info!(
+            "Kept a copy of the previous settings in {}",
+            backup.display()
+        ),
+        Err(e) => log::warn!("Failed to back up settings {}: {}", path.display(), e),
+    }
+}
@@ -0,0 +1,198 @@
+use super::searchable_dropdown::SearchableDropdown;
+use super::Gui;
+use crate::models::{Aircraft, AircraftSimTitle};
+use crate::modules::simulator::{find_aircraft_for_sim_title, Simulator};
+use crate::traits::SimulatorMappingOperations;
+use eframe::egui;
+use std::sync::Arc;
+
+/// State of the simulator aircraft mapping window.
+pub struct SimMappingState {
+    /// Whether the window is open.
+    pub open: bool,
+    /// The mappings loaded from the database.
+    mappings: Vec<AircraftSimTitle>,
+    /// The aircraft for the new mapping.
+    aircraft: Option<Arc<Aircraft>>,
+    /// Search query of the aircraft dropdown.
+    aircraft_search: String,
+    /// The simulator for the new mapping.
+    simulator: Simulator,
+    /// The simulator title for the new mapping.
+    title: String,
+    /// A title to test the mappings against.
+    test_title: String,
+}
+
+impl Default for SimMappingState {
+    fn default() -> Self {
+        SimMappingState {
+            open: false,
+            mappings: Vec::new(),
+            aircraft: None,
+            aircraft_search: String::new(),
+            simulator: Simulator::Msfs,
+            title: String::new(),
+            test_title: String::new(),
+        }
+    }
+}
+
+impl Gui<'_> {
+    /// Opens the simulator mapping window, loading the current mappings.
+    pub(super) fn open_sim_mapping_window(&mut self) {
+        match self.database_pool.get_sim_titles() {
+            Ok(mappings) => {
+                self.sim_mapping_state.mappings = mappings;
+                self.sim_mapping_state.open = true;
+            }
+            Err(e) => log::error!("Failed to load simulator titles: {}", e),
+        }
+    }
+
+    /// Shows the window for mapping simulator aircraft titles to aircraft records.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The egui context.
+    pub(super) fn show_sim_mapping_window(&mut self, ctx: &egui::Context) {
+        let state = &mut self.sim_mapping_state;
+        let all_aircraft = &self.all_aircraft;
+        let aircraft_name = |aircraft_id: i32| {
+            all_aircraft
+                .iter()
+                .find(|a| a.id == aircraft_id)
+                .map_or_else(
+                    || format!("Unknown aircraft ({})", aircraft_id),
+                    |a| format!("{} {}", a.manufacturer, a.variant),
+                )
+        };
+
+        let mut open = state.open;
+        let mut delete_id = None;
+        let mut add = false;
+
+        egui::Window::new("Simulator aircraft")
+            .open(&mut open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("sim_mapping_grid")
+                            .striped(true)
+                            .num_columns(4)
+                            .show(ui, |ui| {
+                                for mapping in &state.mappings {
+                                    let simulator = Simulator::ALL
+                                        .iter()
+                                        .find(|s| s.as_str() == mapping.simulator)
+                                        .map_or(mapping.simulator.as_str(), |s| s.display_name());
+                                    ui.label(simulator);
+                                    ui.label(&mapping.title);
+                                    ui.label(aircraft_name(mapping.aircraft));
+                                    let response = ui.small_button("Remove");
+                                    response.widget_info(|| {
+                                        egui::WidgetInfo::labeled(
+                                            egui::WidgetType::Button,
+                                            true,
+                                            format!("Remove {}", mapping.title),
+                                        )
+                                    });
+                                    if response.clicked() {
+                                        delete_id = Some(mapping.id);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let simulator_label = ui.label("Simulator:");
+                    egui::ComboBox::from_id_salt("sim_mapping_simulator")
+                        .selected_text(state.simulator.display_name())
+                        .show_ui(ui, |ui| {
+                            for simulator in Simulator::ALL {
+                                ui.selectable_value(
+                                    &mut state.simulator,
+                                    simulator,
+                                    simulator.display_name(),
+                                );
+                            }
+                        })
+                        .response
+                        .labelled_by(simulator_label.id);
+                    let title_label = ui.label("Title:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.title)
+                            .hint_text("aircraft.cfg title or .acf name"),
+                    )
+                    .labelled_by(title_label.id);
+                });
+                ui.horizontal(|ui| {
+                    let aircraft_label = ui.label("Aircraft:");
+                    SearchableDropdown::new(
+                        "sim_mapping_aircraft",
+                        all_aircraft,
+                        |a: &Aircraft| format!("{} {}", a.manufacturer, a.variant),
+                        &|a: &Aircraft, query: &str| {
+                            a.manufacturer.to_lowercase().contains(query)
+                                || a.variant.to_lowercase().contains(query)
+                        },
+                    )
+                    .labelled_by(aircraft_label.id)
+                    .grouped_by(|a: &Aircraft| a.manufacturer.clone())
+                    .show(ui, &mut state.aircraft_search, &mut state.aircraft);
+
+                    let can_add = state.aircraft.is_some() && !state.title.trim().is_empty();
+                    if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                        add = true;
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let test_label = ui.label("Test title:");
+                    ui.text_edit_singleline(&mut state.test_title)
+                        .labelled_by(test_label.id);
+                });
+                if !state.test_title.trim().is_empty() {
+                    match find_aircraft_for_sim_title(
+                        &state.mappings,
+                        state.simulator,
+                        &state.test_title,
+                    ) {
+                        Some(aircraft_id) => {
+                            ui.label(format!("Matches {}", aircraft_name(aircraft_id)))
+                        }
+                        None => ui.label("No matching aircraft"),
+                    };
+                }
+            });
+
+        state.open = open;
+
+        if let Some(sim_title_id) = delete_id {
+            if let Err(e) = self.database_pool.delete_sim_title(sim_title_id) {
+                log::error!("Failed to remove simulator title: {}", e);
+            }
+            self.open_sim_mapping_window();
+        }
+
+        if add {
+            let state = &mut self.sim_mapping_state;
+            if let Some(aircraft) = &state.aircraft {
+                if let Err(e) = self.database_pool.add_sim_title(
+                    aircraft,
+                    state.simulator.as_str(),
+                    &state.title,
+                ) {
+                    log::error!("Failed to add simulator title: {}", e);
+                }
+                state.title.clear();
+            }
+            self.open_sim_mapping_window();
+        }
+    }
+}
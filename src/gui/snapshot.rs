@@ -0,0 +1,80 @@
+use crate::models::{Aircraft, Airport, Runway};
+use crate::modules::routes::Route;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A small copy of the last session's data, shown while the database loads.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Snapshot {
+    /// The aircraft list at the time the snapshot was taken.
+    pub aircraft: Vec<Aircraft>,
+    /// The routes that were displayed when the snapshot was taken.
+    pub routes: Vec<RouteSnapshot>,
+}
+
+/// A serializable copy of a generated route.
+#[derive(Serialize, Deserialize)]
+pub struct RouteSnapshot {
+    pub departure: Airport,
+    pub destination: Airport,
+    pub aircraft: Aircraft,
+    pub departure_runways: Vec<Runway>,
+    pub destination_runways: Vec<Runway>,
+}
+
+impl From<&Route> for RouteSnapshot {
+    fn from(route: &Route) -> Self {
+        RouteSnapshot {
+            departure: (*route.departure).clone(),
+            destination: (*route.destination).clone(),
+            aircraft: (*route.aircraft).clone(),
+            departure_runways: (*route.departure_runway).clone(),
+            destination_runways: (*route.destination_runway).clone(),
+        }
+    }
+}
+
+impl From<RouteSnapshot> for Route {
+    fn from(snapshot: RouteSnapshot) -> Self {
+        Route {
+            departure: Arc::new(snapshot.departure),
+            destination: Arc::new(snapshot.destination),
+            aircraft: Arc::new(snapshot.aircraft),
+            departure_runway: Arc::new(snapshot.departure_runways),
+            destination_runway: Arc::new(snapshot.destination_runways),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Loads the snapshot, returning an empty one if it is missing or unreadable.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The snapshot file.
+    pub fn load(path: &Path) -> Snapshot {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Snapshot::default();
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable snapshot {}: {}", path.display(), e);
+            Snapshot::default()
+        })
+    }
+
+    /// Writes the snapshot to disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The snapshot file.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+        // Writing next to the file and renaming keeps the old snapshot if the app
+        // crashes while writing.
+        let temporary = path.with_extension("toml.tmp");
+        std::fs::write(&temporary, contents).map_err(|e| e.to_string())?;
+        std::fs::rename(&temporary, path).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,185 @@
+use super::TableItem;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A column the table can be sorted by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortColumn {
+    /// The ICAO code of the departure of a route.
+    Departure,
+    /// The ICAO code of the destination of a route.
+    Destination,
+    /// The aircraft of a route.
+    Aircraft,
+    /// The distance of a route.
+    Distance,
+    /// The block time of a route.
+    BlockTime,
+    /// The date of a flight in the history.
+    Date,
+    /// The longest land runway of an airport.
+    LongestRunway,
+}
+
+/// The order of a sorted column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// The column the table is sorted by and in which order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableSort {
+    pub column: SortColumn,
+    pub order: SortOrder,
+}
+
+impl TableSort {
+    /// Returns the sort after clicking the header of a column: ascending
+    /// first, then descending, then back to the order the items were listed in.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The current sort, if any.
+    /// * `column` - The column whose header was clicked.
+    pub fn toggle(current: Option<TableSort>, column: SortColumn) -> Option<TableSort> {
+        match current {
+            Some(sort) if sort.column == column => match sort.order {
+                SortOrder::Ascending => Some(TableSort {
+                    column,
+                    order: SortOrder::Descending,
+                }),
+                SortOrder::Descending => None,
+            },
+            _ => Some(TableSort {
+                column,
+                order: SortOrder::Ascending,
+            }),
+        }
+    }
+
+    /// Returns the arrow shown after the header of a column.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column of the header.
+    pub fn arrow(current: Option<TableSort>, column: SortColumn) -> &'static str {
+        match current {
+            Some(sort) if sort.column == column => match sort.order {
+                SortOrder::Ascending => " ⏶",
+                SortOrder::Descending => " ⏷",
+            },
+            _ => "",
+        }
+    }
+}
+
+/// The value an item is sorted by.
+enum SortKey<'a> {
+    Number(f64),
+    Text(&'a str),
+}
+
+impl SortKey<'_> {
+    fn compare(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+impl TableItem {
+    /// Returns the column the header at an index sorts by, if it can be sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the column in [`TableItem::get_columns`].
+    pub(super) fn sort_column(&self, index: usize) -> Option<SortColumn> {
+        match (self, index) {
+            (TableItem::Route(_), 1) => Some(SortColumn::Departure),
+            (TableItem::Route(_), 4) => Some(SortColumn::Destination),
+            (TableItem::Route(_), 7) => Some(SortColumn::Aircraft),
+            (TableItem::Route(_), 8) => Some(SortColumn::Distance),
+            (TableItem::Route(_), 9) => Some(SortColumn::BlockTime),
+            (TableItem::History(_), 4) => Some(SortColumn::Date),
+            (TableItem::Airport(_), 4) => Some(SortColumn::LongestRunway),
+            _ => None,
+        }
+    }
+
+    /// Returns the value the item is sorted by in a column, `None` if the
+    /// column does not belong to the item or the value is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column to sort by.
+    /// * `longest_runways` - The longest land runway of every airport, in feet.
+    fn sort_key<'a>(
+        &'a self,
+        column: SortColumn,
+        longest_runways: Option<&HashMap<i32, i32>>,
+    ) -> Option<SortKey<'a>> {
+        match (self, column) {
+            (TableItem::Route(route), SortColumn::Departure) => {
+                Some(SortKey::Text(&route.departure.ICAO))
+            }
+            (TableItem::Route(route), SortColumn::Destination) => {
+                Some(SortKey::Text(&route.destination.ICAO))
+            }
+            (TableItem::Route(route), SortColumn::Aircraft) => {
+                Some(SortKey::Text(&route.aircraft.variant))
+            }
+            (TableItem::Route(route), SortColumn::Distance) => {
+                Some(SortKey::Number(route.distance_nm()))
+            }
+            // The overhead is the same for every route, so the time en route
+            // orders them like the block time.
+            (TableItem::Route(route), SortColumn::BlockTime) => {
+                route.ete_hours().map(SortKey::Number)
+            }
+            (TableItem::History(history), SortColumn::Date) => Some(SortKey::Text(&history.date)),
+            (TableItem::Airport(airport), SortColumn::LongestRunway) => longest_runways?
+                .get(&airport.ID)
+                .map(|&length| SortKey::Number(f64::from(length))),
+            _ => None,
+        }
+    }
+}
+
+/// Sorts the items of the table. The sort is stable, so items with the same
+/// value keep the order they were listed in, also after more are appended.
+/// Items without a value go last.
+///
+/// # Arguments
+///
+/// * `items` - The items to sort.
+/// * `sort` - The column and order to sort by.
+/// * `longest_runways` - The longest land runway of every airport, in feet.
+pub fn sort_items(
+    items: &mut Vec<Arc<TableItem>>,
+    sort: TableSort,
+    longest_runways: Option<&HashMap<i32, i32>>,
+) {
+    let keys: Vec<Option<SortKey>> = items
+        .iter()
+        .map(|item| item.sort_key(sort.column, longest_runways))
+        .collect();
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| match (&keys[a], &keys[b]) {
+        (Some(a), Some(b)) => match sort.order {
+            SortOrder::Ascending => a.compare(b),
+            SortOrder::Descending => b.compare(a),
+        },
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    });
+
+    let sorted = order
+        .into_iter()
+        .map(|index| Arc::clone(&items[index]))
+        .collect();
+    *items = sorted;
+}
@@ -0,0 +1,133 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+/// The local port the running instance listens on for activation requests.
+const INSTANCE_PORT: u16 = 47625;
+/// Sent by the running instance so a foreign program on the port is not mistaken for it.
+const GREETING: &str = "flight-planner";
+/// Sent instead of arguments to check for a running instance without bringing it to the front.
+const PROBE: &str = "--probe";
+/// How long to wait for the running instance to answer.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The result of trying to become the only running instance.
+pub enum InstanceLock {
+    /// This is the only instance; activation requests arrive on the listener.
+    Primary(TcpListener),
+    /// Another instance is running and has been asked to come to the front.
+    Forwarded,
+    /// The port is used by another program, so instances cannot be detected.
+    Unavailable,
+}
+
+/// Claims the instance port, or forwards the arguments to the instance that holds it.
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments to forward, without the program name.
+pub fn acquire(args: &[String]) -> InstanceLock {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, INSTANCE_PORT));
+
+    if let Ok(listener) = TcpListener::bind(address) {
+        return InstanceLock::Primary(listener);
+    }
+
+    match forward(&address, args) {
+        Ok(true) => InstanceLock::Forwarded,
+        Ok(false) => {
+            log::warn!(
+                "Port {} is used by another program, not checking for other instances",
+                INSTANCE_PORT
+            );
+            InstanceLock::Unavailable
+        }
+        Err(e) => {
+            log::warn!("Failed to contact the running instance: {}", e);
+            InstanceLock::Unavailable
+        }
+    }
+}
+
+/// Claims the instance port for work that must not run beside another
+/// instance, without bringing a running instance to the front.
+///
+/// # Returns
+///
+/// The lock to hold until the work is done, or `None` if another instance is running.
+pub fn acquire_quietly() -> Option<InstanceLock> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, INSTANCE_PORT));
+
+    if let Ok(listener) = TcpListener::bind(address) {
+        return Some(InstanceLock::Primary(listener));
+    }
+
+    match connect(&address) {
+        Ok(Some(mut stream)) => {
+            let _ = writeln!(stream, "{}", PROBE);
+            None
+        }
+        Ok(None) => Some(InstanceLock::Unavailable),
+        Err(e) => {
+            log::warn!("Failed to contact the running instance: {}", e);
+            Some(InstanceLock::Unavailable)
+        }
+    }
+}
+
+/// Sends the arguments to the running instance.
+///
+/// # Returns
+///
+/// Whether the program on the port is a running instance of the planner.
+fn forward(address: &SocketAddr, args: &[String]) -> std::io::Result<bool> {
+    let Some(mut stream) = connect(address)? else {
+        return Ok(false);
+    };
+
+    for arg in args {
+        writeln!(stream, "{}", arg)?;
+    }
+
+    Ok(true)
+}
+
+/// Connects to the program on the instance port.
+///
+/// # Returns
+///
+/// The connection, or `None` if the program is not an instance of the planner.
+fn connect(address: &SocketAddr) -> std::io::Result<Option<TcpStream>> {
+    let stream = TcpStream::connect_timeout(address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut greeting = String::new();
+    BufReader::new(&stream).read_line(&mut greeting)?;
+
+    Ok((greeting.trim_end() == GREETING).then_some(stream))
+}
+
+/// Handles activation requests from instances started later on a background thread.
+///
+/// # Arguments
+///
+/// * `listener` - The listener returned by [`acquire`].
+/// * `on_activate` - Called with the forwarded arguments of every request.
+pub fn listen(listener: TcpListener, on_activate: impl Fn(Vec<String>) + Send + 'static) {
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            if writeln!(stream, "{}", GREETING).is_err() {
+                continue;
+            }
+
+            let _ = stream.set_read_timeout(Some(TIMEOUT));
+            let args: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map_while(Result::ok)
+                .collect();
+            if args != [PROBE] {
+                on_activate(args);
+            }
+        }
+    });
+}
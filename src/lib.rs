@@ -0,0 +1,145 @@
+//! The route generation engine of the flight planner.
+//!
+//! The aircraft and airport databases are opened with [`DatabasePool`] or
+//! [`DatabaseConnections`], whose operations are the traits in [`traits`].
+//! Routes are generated in memory by [`modules::routes::RouteGenerator`].
+//!
+//! The graphical interface and the command line program are built with the
+//! default `gui` feature. Tools embedding only the engine leave it out with
+//! `default-features = false`, which drops egui and its renderers.
+//!
+//! ```no_run
+//! use flight_planner::modules::routes::{RouteConstraints, RouteGenerator};
+//! use flight_planner::traits::{AircraftOperations, AirportOperations};
+//! use flight_planner::DatabasePool;
+//! use std::sync::Arc;
+//!
+//! let mut database_pool = DatabasePool::open("data.db", "airports.db3").unwrap();
+//! database_pool.run_migrations().unwrap();
+//!
+//! let aircraft: Vec<_> = database_pool
+//!     .get_all_aircraft()
+//!     .unwrap()
+//!     .into_iter()
+//!     .map(Arc::new)
+//!     .collect();
+//! let route_generator = RouteGenerator::new(
+//!     database_pool.get_airports().unwrap(),
+//!     database_pool.get_runways().unwrap(),
+//! );
+//! for route in route_generator.generate_random_routes(&aircraft, 10, &RouteConstraints::default(), &[]) {
+//!     println!("{} to {}", route.departure.ICAO, route.destination.ICAO);
+//! }
+//! ```
+
+use diesel::prelude::*;
+use diesel::r2d2::ConnectionManager;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use r2d2::Pool;
+
+pub mod errors;
+pub mod models;
+pub mod modules;
+pub mod schema;
+pub mod traits;
+
+use traits::DatabaseOperations;
+
+/// The aircraft database in the working directory, opened by `new`.
+pub const AIRCRAFT_DB_FILENAME: &str = "data.db";
+/// The airport database in the working directory, opened by `new`.
+pub const AIRPORT_DB_FILENAME: &str = "airports.db3";
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// A single connection to each database, for short command line runs.
+pub struct DatabaseConnections {
+    aircraft_connection: SqliteConnection,
+    airport_connection: SqliteConnection,
+}
+
+impl Default for DatabaseConnections {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DatabaseOperations for DatabaseConnections {}
+
+impl DatabaseConnections {
+    /// Opens the databases in the working directory, panicking if either cannot be opened.
+    pub fn new() -> Self {
+        Self::open(AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME)
+            .unwrap_or_else(|e| panic!("Error connecting to the databases: {}", e))
+    }
+
+    /// Opens the databases at the given paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft_db` - The aircraft database, which also holds the history.
+    /// * `airport_db` - The airport database.
+    pub fn open(aircraft_db: &str, airport_db: &str) -> ConnectionResult<Self> {
+        Ok(DatabaseConnections {
+            aircraft_connection: SqliteConnection::establish(aircraft_db)?,
+            airport_connection: SqliteConnection::establish(airport_db)?,
+        })
+    }
+
+    /// Applies the pending migrations of the aircraft database.
+    pub fn run_migrations(&mut self) -> diesel::migration::Result<()> {
+        self.aircraft_connection
+            .run_pending_migrations(MIGRATIONS)?;
+        Ok(())
+    }
+}
+
+/// A pool of connections to each database, shared between threads.
+#[derive(Clone)]
+pub struct DatabasePool {
+    aircraft_pool: Pool<ConnectionManager<SqliteConnection>>,
+    airport_pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl DatabasePool {
+    /// Opens the databases in the working directory, panicking if either cannot be opened.
+    pub fn new() -> Self {
+        Self::open(AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME).unwrap()
+    }
+
+    /// Opens the databases at the given paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft_db` - The aircraft database, which also holds the history.
+    /// * `airport_db` - The airport database.
+    pub fn open(aircraft_db: &str, airport_db: &str) -> Result<Self, r2d2::Error> {
+        fn establish_database_pool(
+            database_name: &str,
+        ) -> Result<Pool<ConnectionManager<SqliteConnection>>, r2d2::Error> {
+            let manager = ConnectionManager::<SqliteConnection>::new(database_name);
+            Pool::builder().build(manager)
+        }
+
+        Ok(DatabasePool {
+            aircraft_pool: establish_database_pool(aircraft_db)?,
+            airport_pool: establish_database_pool(airport_db)?,
+        })
+    }
+
+    /// Applies the pending migrations of the aircraft database.
+    pub fn run_migrations(&mut self) -> diesel::migration::Result<()> {
+        self.aircraft_pool
+            .get()?
+            .run_pending_migrations(MIGRATIONS)?;
+        Ok(())
+    }
+}
+
+impl Default for DatabasePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DatabaseOperations for DatabasePool {}
@@ -1,116 +1,429 @@
 use diesel::prelude::*;
-use diesel::r2d2::ConnectionManager;
 use diesel::result::Error;
-use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use geo::{Distance, Haversine};
 use std::path;
 use std::sync::Arc;
 
-mod errors;
+mod bench;
+mod cli;
+mod completions;
 mod gui;
-mod models;
-mod modules;
-mod schema;
-mod traits;
+mod instance;
 
 use eframe::AppCreator;
 use egui::ViewportBuilder;
-use gui::Gui;
-use r2d2::Pool;
-
-use crate::models::Aircraft;
+use flight_planner::{
+    errors, models, modules, schema, traits, DatabaseConnections, DatabasePool,
+    AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME,
+};
+use gui::{Gui, Session};
+use instance::InstanceLock;
+
+use crate::models::{Aircraft, Airport};
+use cli::CliError;
 use errors::ValidationError;
 use modules::aircraft::*;
 use modules::airport::*;
+use modules::export::{export_routes, ExportFormat};
+use modules::merge::{merge_airport_databases, MergeStrategy, DEFAULT_TOLERANCE_NM};
 use modules::runway::*;
+use modules::trim::{parse_regions, trim_airport_database};
+use modules::validation::parse_number;
 use traits::*;
 
 define_sql_function! {fn random() -> Text }
 
-const AIRCRAFT_DB_FILENAME: &str = "data.db";
-const AIRPORT_DB_FILENAME: &str = "airports.db3";
+const SNAPSHOT_FILENAME: &str = "snapshot.toml";
+const SETTINGS_FILENAME: &str = "settings.toml";
+const SESSION_FILENAME: &str = "session.toml";
+const SYNC_STATE_FILENAME: &str = "sync_state.toml";
 const KM_TO_NM: f64 = 0.53995680345572;
 
-const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+const HELP: &str = "\
+Flight planner
+
+Usage:
+  flight-planner                     Start the console menu
+  flight-planner --gui               Start the graphical interface
+  flight-planner history list [--since YYYY-MM-DD] [--until YYYY-MM-DD]
+                             [--aircraft ICAO|ID] [--airport ICAO] [--json]
+  flight-planner history add [--from ICAO|IATA] [--to ICAO|IATA] [--aircraft ICAO|ID]
+                             [--date YYYY-MM-DD] [--note TEXT]
+                             [--planned-arrival ICAO|IATA] [--airline NAME]
+                             [--allow-duplicate]
+  flight-planner history recount
+  flight-planner history merge <other aircraft database>
+  flight-planner history export <file.csv|file.json>
+  flight-planner route random [--count N] [--aircraft ICAO|ID]
+                             [--min-distance NM] [--max-distance NM] [--json]
+  flight-planner aircraft list [--json]
+  flight-planner airport info <ICAO|IATA> [--json]
+  flight-planner airport import-codes <airports.csv>
+  flight-planner alias list
+  flight-planner alias add <former ICAO> <current ICAO>
+  flight-planner sources list
+  flight-planner sources add <name> <version> [--date YYYY-MM-DD]
+  flight-planner frequencies import <airport-frequencies.csv>
+  flight-planner frequencies list <ICAO|IATA>
+  flight-planner airac show
+  flight-planner airac set <cycle, such as 2401>
+  flight-planner sync <folder> [--keep-local|--keep-remote]
+  flight-planner --export <file.gpx|file.kml> [--count N] [--aircraft ICAO|ID]
+                             [--min-distance NM] [--max-distance NM]
+  flight-planner --trim-airports <output file> <continents or ICAO prefixes>
+  flight-planner --merge-airports <output file> <airport database>...
+                             [--strategy first|most-runways] [--tolerance NM]
+  flight-planner completions <bash|zsh|fish|powershell>
+  flight-planner --bench-routes
+
+Options:
+  --quiet    Only print requested data; never prompt
+  --help     Show this help
+
+Airports are found by IATA code once the codes are imported from the
+airports.csv of OurAirports with `airport import-codes`.
+
+Errors are printed to stderr as `error[<kind>]: <message>`.
+
+Exit codes:
+  0  Success
+  1  Database or I/O error (kind: database, io)
+  2  Invalid arguments (kind: usage)
+  3  Database file missing (kind: database-missing)
+  4  Airport or aircraft not found (kind: not-found)
+  5  File changed on two computers since the last sync (kind: conflict)
+  6  Flight planner is running and has to be closed first (kind: instance-running)
+";
 
-pub struct DatabaseConnections {
-    aircraft_connection: SqliteConnection,
-    airport_connection: SqliteConnection,
-}
+fn main() {
+    env_logger::init();
+
+    let mut args: Vec<String> = std::env::args().collect();
+    let quiet = args.iter().any(|arg| arg == "--quiet");
+    args.retain(|arg| arg != "--quiet");
 
-impl Default for DatabaseConnections {
-    fn default() -> Self {
-        Self::new()
+    if let Err(e) = run_command(&args, quiet) {
+        eprintln!("error[{}]: {}", e.kind(), e);
+        std::process::exit(e.exit_code());
     }
 }
 
-impl DatabaseOperations for DatabaseConnections {}
+/// Runs the command given on the command line.
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments, without `--quiet`.
+/// * `quiet` - Whether to leave out messages and prompts.
+fn run_command(args: &[String], quiet: bool) -> Result<(), CliError> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", HELP);
+        return Ok(());
+    }
+
+    if args.iter().any(|arg| arg == "--bench-routes") {
+        bench::bench_routes();
+        return Ok(());
+    }
 
-impl DatabaseConnections {
-    pub fn new() -> Self {
-        fn establish_database_connection(database_name: &str) -> SqliteConnection {
-            SqliteConnection::establish(database_name).unwrap_or_else(|_| {
-                panic!("Error connecting to {}", database_name);
-            })
-        }
+    if args.get(1).is_some_and(|arg| arg == "completions") {
+        let shell = args.get(2).map_or("", String::as_str);
+        let script = completions::completion_script(shell, path::Path::new(AIRCRAFT_DB_FILENAME))?;
+        print!("{}", script);
+        return Ok(());
+    }
 
-        let aircraft_connection = establish_database_connection(AIRCRAFT_DB_FILENAME);
-        let airport_connection = establish_database_connection(AIRPORT_DB_FILENAME);
+    if let Some(position) = args.iter().position(|arg| arg == "--merge-airports") {
+        return merge_airports(&args[position + 1..], quiet);
+    }
 
-        DatabaseConnections {
-            aircraft_connection,
-            airport_connection,
-        }
+    // Syncing replaces the database files, so it runs before any connection is made
+    // and never beside another instance, which would go on writing to the replaced files.
+    if args.get(1).is_some_and(|arg| arg == "sync") {
+        let Some(_instance_lock) = instance::acquire_quietly() else {
+            return Err(CliError::InstanceRunning);
+        };
+        return cli::sync_command(&args[2..], quiet);
+    }
+
+    if !path::Path::new(AIRPORT_DB_FILENAME).exists() {
+        return Err(CliError::DatabaseMissing(AIRPORT_DB_FILENAME.to_string()));
+    }
+
+    if let Some(position) = args.iter().position(|arg| arg == "--export") {
+        return export_routes_to_file(&args[position + 1..], quiet);
+    }
+
+    if let Some(position) = args.iter().position(|arg| arg == "--trim-airports") {
+        return trim_airports(args.get(position + 1), args.get(position + 2), quiet);
     }
+
+    if args.get(1).is_some_and(|arg| arg == "history") {
+        let mut database_connections = open_databases()?;
+        return cli::history_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "route") {
+        return route_command(&args[2..]);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "aircraft") {
+        let mut database_connections = open_databases()?;
+        return cli::aircraft_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "airport") {
+        let mut database_connections = open_databases()?;
+        return cli::airport_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "alias") {
+        let mut database_connections = open_databases()?;
+        return cli::alias_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "sources") {
+        let mut database_connections = open_databases()?;
+        return cli::sources_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "frequencies") {
+        let mut database_connections = open_databases()?;
+        return cli::frequencies_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "airac") {
+        let mut database_connections = open_databases()?;
+        return cli::airac_command(&mut database_connections, &args[2..], quiet);
+    }
+
+    run()
 }
 
-pub struct DatabasePool {
-    aircraft_pool: Pool<ConnectionManager<SqliteConnection>>,
-    airport_pool: Pool<ConnectionManager<SqliteConnection>>,
+/// Opens the databases for a subcommand and brings the aircraft database up to date.
+fn open_databases() -> Result<DatabaseConnections, CliError> {
+    let mut database_connections =
+        DatabaseConnections::open(AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME)?;
+    database_connections
+        .run_migrations()
+        .map_err(CliError::Database)?;
+    Ok(database_connections)
 }
 
-impl DatabasePool {
-    pub fn new() -> Self {
-        fn establish_database_pool(
-            database_name: &str,
-        ) -> Pool<ConnectionManager<SqliteConnection>> {
-            let manager = ConnectionManager::<SqliteConnection>::new(database_name);
-            Pool::builder().build(manager).unwrap()
-        }
+fn trim_airports(
+    output: Option<&String>,
+    regions: Option<&String>,
+    quiet: bool,
+) -> Result<(), CliError> {
+    let (Some(output), Some(regions)) = (output, regions) else {
+        return Err(CliError::Usage(
+            "Usage: --trim-airports <output file> <continents or ICAO prefixes>".to_string(),
+        ));
+    };
 
-        let aircraft_pool = establish_database_pool(AIRCRAFT_DB_FILENAME);
-        let airport_pool = establish_database_pool(AIRPORT_DB_FILENAME);
+    let prefixes = parse_regions(regions)?;
+    let (airports, runways) = trim_airport_database(
+        path::Path::new(AIRPORT_DB_FILENAME),
+        path::Path::new(output),
+        &prefixes,
+    )?;
+    if !quiet {
+        println!(
+            "Wrote {} airports and {} runways to {}",
+            airports, runways, output
+        );
+    }
 
-        DatabasePool {
-            aircraft_pool,
-            airport_pool,
-        }
+    Ok(())
+}
+
+/// Generates random routes and writes them to a GPX or KML file.
+///
+/// # Arguments
+///
+/// * `args` - The arguments after `--export`.
+/// * `quiet` - Whether to leave out messages.
+fn export_routes_to_file(args: &[String], quiet: bool) -> Result<(), CliError> {
+    const USAGE: &str = "Usage: --export <file.gpx|file.kml> [--count N] [--aircraft ICAO|ID] \
+        [--min-distance NM] [--max-distance NM]";
+
+    let Some((output, flags)) = args.split_first() else {
+        return Err(CliError::Usage(USAGE.to_string()));
+    };
+    cli::check_flags(flags, &cli::ROUTE_VALUE_FLAGS, &[])?;
+    let output = path::Path::new(output);
+    let format = ExportFormat::from_path(output).ok_or_else(|| {
+        ValidationError::InvalidData(format!(
+            "Unknown export format of {}, expected a .gpx or .kml file",
+            output.display()
+        ))
+    })?;
+
+    let routes = generate_routes_from_flags(flags)?;
+    let routes: Vec<&modules::routes::Route> = routes.iter().collect();
+    let sim_titles = open_databases()?.get_sim_titles()?;
+    std::fs::write(output, export_routes(&routes, format, &sim_titles))?;
+    if !quiet {
+        println!("Wrote {} routes to {}", routes.len(), output.display());
     }
+
+    Ok(())
 }
 
-impl Default for DatabasePool {
-    fn default() -> Self {
-        Self::new()
+/// Runs a `route` subcommand, which prints random routes.
+///
+/// # Arguments
+///
+/// * `args` - The arguments after `route`.
+fn route_command(args: &[String]) -> Result<(), CliError> {
+    let Some((command, flags)) = args.split_first() else {
+        return Err(CliError::Usage(cli::ROUTE_USAGE.to_string()));
+    };
+    if command != "random" {
+        return Err(CliError::Usage(cli::ROUTE_USAGE.to_string()));
     }
+    cli::check_flags(flags, &cli::ROUTE_VALUE_FLAGS, &["--json"])?;
+
+    let routes = generate_routes_from_flags(flags)?;
+    cli::print_routes(&routes, cli::has_flag(flags, "--json"));
+
+    Ok(())
 }
 
-impl DatabaseOperations for DatabasePool {}
+/// Generates random routes limited by the flags of `route random` and `--export`.
+///
+/// # Arguments
+///
+/// * `flags` - The flags, already checked against `cli::ROUTE_VALUE_FLAGS`.
+fn generate_routes_from_flags(flags: &[String]) -> Result<Vec<modules::routes::Route>, CliError> {
+    const DEFAULT_COUNT: usize = 10;
+
+    let count = match cli::flag_value(flags, "--count") {
+        Some(value) => parse_number(value, 1)
+            .map_err(|e| ValidationError::InvalidData(format!("Invalid count {}: {}", value, e)))?,
+        None => DEFAULT_COUNT,
+    };
+    let distance_flag = |flag: &str| -> Result<Option<u32>, ValidationError> {
+        cli::flag_value(flags, flag)
+            .map(|value| {
+                parse_number(value, 0).map_err(|e| {
+                    ValidationError::InvalidData(format!("Invalid distance {}: {}", value, e))
+                })
+            })
+            .transpose()
+    };
+    let min_distance_nm = distance_flag("--min-distance")?;
+    let max_distance_nm = distance_flag("--max-distance")?;
+    if let (Some(min), Some(max)) = (min_distance_nm, max_distance_nm) {
+        if min > max {
+            return Err(ValidationError::InvalidData(format!(
+                "The minimum distance {} NM is longer than the maximum {} NM",
+                min, max
+            ))
+            .into());
+        }
+    }
 
-fn main() {
-    env_logger::init();
+    let mut database_pool = DatabasePool::open(AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME)?;
+    database_pool.run_migrations().map_err(CliError::Database)?;
+
+    let aircraft: Vec<Arc<Aircraft>> = database_pool
+        .get_all_aircraft()?
+        .into_iter()
+        .filter(|aircraft| {
+            cli::flag_value(flags, "--aircraft")
+                .is_none_or(|query| cli::aircraft_matches(aircraft, query))
+        })
+        .map(Arc::new)
+        .collect();
+    if aircraft.is_empty() {
+        return Err(CliError::NotFound("No matching aircraft".to_string()));
+    }
 
-    if !path::Path::new(AIRPORT_DB_FILENAME).exists() {
-        log::error!("Airports database not found at {}", AIRPORT_DB_FILENAME);
-        return;
+    let route_generator = modules::routes::RouteGenerator::new(
+        database_pool.get_airports()?,
+        database_pool.get_runways()?,
+    );
+    Ok(route_generator.generate_random_routes(
+        &aircraft,
+        count,
+        &modules::routes::RouteConstraints {
+            min_distance_nm: min_distance_nm.unwrap_or(0),
+            max_distance_nm,
+            ..Default::default()
+        },
+        &[],
+    ))
+}
+
+/// Merges airport databases into a new one, dropping duplicate airports.
+///
+/// # Arguments
+///
+/// * `args` - The arguments after `--merge-airports`.
+/// * `quiet` - Whether to leave out messages.
+fn merge_airports(args: &[String], quiet: bool) -> Result<(), CliError> {
+    const USAGE: &str = "Usage: --merge-airports <output file> <airport database>... \
+        [--strategy first|most-runways] [--tolerance NM]";
+
+    let mut files = Vec::new();
+    let mut strategy = MergeStrategy::MostRunways;
+    let mut tolerance_nm = DEFAULT_TOLERANCE_NM;
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        match arg.as_str() {
+            "--strategy" => {
+                let name = remaining.next().ok_or(CliError::Usage(USAGE.to_string()))?;
+                strategy = MergeStrategy::parse(name)?;
+            }
+            "--tolerance" => {
+                let value = remaining.next().ok_or(CliError::Usage(USAGE.to_string()))?;
+                tolerance_nm = parse_number(value, 0.0).map_err(|e| {
+                    ValidationError::InvalidData(format!("Invalid tolerance {}: {}", value, e))
+                })?;
+            }
+            _ => files.push(path::Path::new(arg.as_str())),
+        }
+    }
+
+    let Some((output, sources)) = files.split_first() else {
+        return Err(CliError::Usage(USAGE.to_string()));
+    };
+    if sources.is_empty() {
+        return Err(CliError::Usage(USAGE.to_string()));
+    }
+    if let Some(missing) = sources.iter().find(|source| !source.exists()) {
+        return Err(CliError::DatabaseMissing(missing.display().to_string()));
     }
 
-    if let Err(e) = run() {
-        log::error!("Application error: {}", e);
+    let summary = merge_airport_databases(sources, output, strategy, tolerance_nm)?;
+    if !quiet {
+        for duplicate in &summary.duplicates {
+            println!("Merged {} into {}", duplicate.dropped, duplicate.kept);
+        }
+        println!(
+            "Wrote {} airports and {} runways to {}, merged {} duplicates",
+            summary.airports,
+            summary.runways,
+            output.display(),
+            summary.duplicates.len()
+        );
     }
+
+    Ok(())
 }
 
-fn run() -> Result<(), Error> {
-    let mut database_pool = DatabasePool::new();
+fn run() -> Result<(), CliError> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let instance_listener = match instance::acquire(&args) {
+        InstanceLock::Primary(listener) => Some(listener),
+        InstanceLock::Forwarded => {
+            println!("Flight planner is already running");
+            return Ok(());
+        }
+        InstanceLock::Unavailable => None,
+    };
+
+    let mut database_pool = DatabasePool::open(AIRCRAFT_DB_FILENAME, AIRPORT_DB_FILENAME)?;
     let mut use_gui = false;
 
     for arg in std::env::args() {
@@ -119,14 +432,10 @@ fn run() -> Result<(), Error> {
         }
     }
 
-    database_pool
-        .aircraft_pool
-        .get()
-        .unwrap()
-        .run_pending_migrations(MIGRATIONS)
-        .expect("Failed to run migrations");
+    database_pool.run_migrations().map_err(CliError::Database)?;
 
     if use_gui {
+        let session = Session::load();
         let icon = include_bytes!("../icon.png");
         let image = image::load_from_memory(icon)
             .expect("Failed to load icon")
@@ -135,7 +444,7 @@ fn run() -> Result<(), Error> {
 
         let native_options = eframe::NativeOptions {
             viewport: ViewportBuilder {
-                inner_size: Some(egui::vec2(1200.0, 768.0)),
+                inner_size: Some(session.window_size()),
                 close_button: Some(true),
                 icon: Some(Arc::from(egui::IconData {
                     rgba: image.into_raw(),
@@ -147,10 +456,21 @@ fn run() -> Result<(), Error> {
             ..Default::default()
         };
 
-        let app_creator: AppCreator<'_> =
-            Box::new(|cc| Ok(Box::new(Gui::new(cc, &mut database_pool))));
+        let app_creator: AppCreator<'_> = Box::new(|cc| {
+            Ok(Box::new(Gui::new(
+                cc,
+                &mut database_pool,
+                instance_listener,
+                session,
+            )))
+        });
         _ = eframe::run_native("Flight planner", native_options, app_creator);
     } else {
+        if let Some(listener) = instance_listener {
+            instance::listen(listener, |_| {
+                log::info!("Another instance was started while the console planner is running");
+            });
+        }
         console_main(database_pool)?;
     }
     Ok(())
@@ -230,7 +550,7 @@ fn mark_all_not_flown<T: AircraftOperations, F: Fn() -> Result<char, std::io::Er
 ) -> Result<(), Error> {
     match read_yn(confirm_fn) {
         Ok(true) => {
-           database_connections.mark_all_aircraft_not_flown()?;
+            database_connections.mark_all_aircraft_not_flown()?;
         }
         Ok(false) => {
             log::info!("Not marking all aircraft as flown");
@@ -340,15 +660,17 @@ fn show_random_not_flown_aircraft_and_route<T: DatabaseOperations>(
     random_not_flown_aircraft_and_route(database_connections, ask_char_fn)
 }
 
-fn ask_mark_flown<T: AircraftOperations, F: Fn() -> Result<char, std::io::Error>>(
+/// Asks whether the route was flown and adds it to the history if so, which
+/// also counts the flight for the aircraft.
+fn ask_mark_flown<T: HistoryOperations, F: Fn() -> Result<char, std::io::Error>>(
     database_connections: &mut T,
-    aircraft: &mut Aircraft,
+    departure: &Airport,
+    destination: &Airport,
+    aircraft: &Aircraft,
     ask_char_fn: F,
 ) -> Result<(), Error> {
     if let Ok('y') = ask_char_fn() {
-        aircraft.date_flown = Some(chrono::Local::now().format("%Y-%m-%d").to_string());
-        aircraft.flown = 1;
-        database_connections.update_aircraft(aircraft)?;
+        database_connections.add_to_history(departure, destination, aircraft)?;
     }
 
     Ok(())
@@ -361,7 +683,7 @@ fn random_not_flown_aircraft_and_route<
     database_connections: &mut T,
     ask_char_fn: F,
 ) -> Result<(), Error> {
-    let mut aircraft = database_connections.random_not_flown_aircraft()?;
+    let aircraft = database_connections.random_not_flown_aircraft()?;
     let departure = database_connections.get_random_airport_for_aircraft(&aircraft)?;
     let destination = database_connections.get_destination_airport(&aircraft, &departure)?;
 
@@ -384,8 +706,13 @@ fn random_not_flown_aircraft_and_route<
         println!("{}", format_runway(&runway));
     }
 
-    ask_mark_flown(database_connections, &mut aircraft, ask_char_fn)?;
-    database_connections.add_to_history(&departure, &destination, &aircraft)?;
+    ask_mark_flown(
+        database_connections,
+        &departure,
+        &destination,
+        &aircraft,
+        ask_char_fn,
+    )?;
 
     Ok(())
 }
@@ -401,25 +728,7 @@ fn show_history<T: HistoryOperations + AircraftOperations>(
         return Ok(());
     }
 
-    for record in history_data {
-        let aircraft = match aircraft_data.iter().find(|a| a.id == record.aircraft) {
-            Some(a) => a,
-            None => {
-                log::warn!("Aircraft not found for id: {}", record.aircraft);
-                return Err(Error::NotFound);
-            }
-        };
-
-        println!(
-            "Date: {}\nDeparture: {}\nDestination: {}\nAircraft: {} {} ({})\n",
-            record.date,
-            record.departure_icao,
-            record.arrival_icao,
-            aircraft.manufacturer,
-            aircraft.variant,
-            aircraft.icao_code
-        );
-    }
+    cli::print_history_table(&history_data, &aircraft_data);
 
     Ok(())
 }
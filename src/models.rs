@@ -1,7 +1,18 @@
 use crate::schema::*;
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Debug, PartialEq, Clone, Insertable, Identifiable, AsChangeset)]
+#[derive(
+    Queryable,
+    Debug,
+    PartialEq,
+    Clone,
+    Insertable,
+    Identifiable,
+    AsChangeset,
+    Serialize,
+    Deserialize,
+)]
 #[diesel(table_name = aircraft)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct Aircraft {
@@ -15,6 +26,13 @@ pub struct Aircraft {
     pub cruise_speed: i32,
     pub date_flown: Option<String>,
     pub takeoff_distance: Option<i32>,
+    pub service_ceiling: Option<i32>,
+    /// Whether the aircraft is a helicopter, which can use helipads.
+    #[serde(default)]
+    pub helicopter: bool,
+    /// Whether the aircraft can land on water.
+    #[serde(default)]
+    pub amphibian: bool,
 }
 
 #[derive(Queryable, Identifiable, Insertable, Debug, Clone)]
@@ -26,9 +44,82 @@ pub struct History {
     pub arrival_icao: String,
     pub aircraft: i32,
     pub date: String,
+    pub notes: Option<String>,
+    /// The arrival the flight was planned for, when it diverted to `arrival_icao`.
+    pub planned_arrival_icao: Option<String>,
+    /// The virtual airline the flight was flown for.
+    pub airline: Option<String>,
 }
 
-#[derive(Queryable, Identifiable, Debug, PartialEq, Clone, Insertable, Default)]
+/// Optional details recorded with a flight added to the history.
+#[derive(Default, Clone, Copy)]
+pub struct FlightDetails<'a> {
+    /// A note about the flight.
+    pub notes: Option<&'a str>,
+    /// The arrival the flight was planned for, when it diverted.
+    pub planned_arrival: Option<&'a Airport>,
+    /// The virtual airline the flight was flown for.
+    pub airline: Option<&'a str>,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct DataSource {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub name: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub version: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub date: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub airports: i64,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Frequency {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub airport_id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub kind: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub description: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub frequency_khz: i32,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = airport_icao_aliases)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AirportAlias {
+    pub alias: String,
+    pub icao: String,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AirportIataCode {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub icao: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub iata: String,
+}
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = aircraft_sim_titles)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AircraftSimTitle {
+    pub id: i32,
+    pub aircraft: i32,
+    pub simulator: String,
+    pub title: String,
+}
+
+#[derive(
+    Queryable, Identifiable, Debug, PartialEq, Clone, Insertable, Default, Serialize, Deserialize,
+)]
 #[diesel(primary_key(ID))]
 #[diesel(table_name = Airports)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -47,7 +138,17 @@ pub struct Airport {
     pub SpeedLimitAltitude: Option<i32>,
 }
 
-#[derive(Associations, Queryable, Identifiable, PartialEq, Debug, Insertable, Clone)]
+#[derive(
+    Associations,
+    Queryable,
+    Identifiable,
+    PartialEq,
+    Debug,
+    Insertable,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
 #[diesel(primary_key(ID))]
 #[diesel(belongs_to(Airport, foreign_key = AirportID))]
 #[diesel(table_name = Runways)]
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::models::{Aircraft, Airport, History};
+use crate::modules::routes::airport_distance_nm;
+
+/// The distance of the long haul achievement in nautical miles.
+const LONG_HAUL_NM: f64 = 1000.0;
+/// The number of countries of the world traveller achievement.
+const COUNTRIES: usize = 10;
+/// The latitude of the Arctic and Antarctic Circles in degrees.
+const POLAR_CIRCLE_LATITUDE: f64 = 66.5628;
+
+/// A milestone unlocked by the flights in the history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    LongHaul,
+    WorldTraveller,
+    WholeFleet,
+    PolarCircle,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [
+        Achievement::LongHaul,
+        Achievement::WorldTraveller,
+        Achievement::WholeFleet,
+        Achievement::PolarCircle,
+    ];
+
+    /// Returns the name of the achievement.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::LongHaul => "Long haul",
+            Achievement::WorldTraveller => "World traveller",
+            Achievement::WholeFleet => "Whole fleet",
+            Achievement::PolarCircle => "Polar circle",
+        }
+    }
+
+    /// Returns what has to be flown to unlock the achievement.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::LongHaul => "Fly a flight of 1000 NM or more",
+            Achievement::WorldTraveller => "Visit airports in 10 countries",
+            Achievement::WholeFleet => "Fly every aircraft at least once",
+            Achievement::PolarCircle => "Cross the Arctic or Antarctic Circle",
+        }
+    }
+}
+
+/// An achievement together with the flight that unlocked it.
+#[derive(Clone, Debug)]
+pub struct Unlock {
+    pub achievement: Achievement,
+    /// The date of the flight that unlocked the achievement.
+    pub date: String,
+}
+
+/// Returns the country of an airport as far as its ICAO code tells.
+///
+/// The first two letters identify the country, except for the regions where
+/// one letter covers a whole country, such as `K` for the United States.
+fn icao_country(icao: &str) -> Option<&str> {
+    let prefix = icao.get(..2)?;
+    let country = match prefix.as_bytes() {
+        [b'K' | b'C' | b'Y', _] => &prefix[..1],
+        // China, apart from North Korea and Mongolia.
+        [b'Z', second] if !matches!(second, b'K' | b'M') => &prefix[..1],
+        // Russia, apart from the other former Soviet states.
+        [b'U', b'E' | b'H' | b'I' | b'L' | b'N' | b'O' | b'R' | b'U' | b'W'] => &prefix[..1],
+        _ => prefix,
+    };
+
+    Some(country)
+}
+
+/// Returns whether a flight goes from one side of a polar circle to the other.
+fn crosses_polar_circle(from: &Airport, to: &Airport) -> bool {
+    let inside = |airport: &Airport| airport.Latitude.abs() >= POLAR_CIRCLE_LATITUDE;
+    inside(from) != inside(to)
+}
+
+/// Returns the achievements unlocked by the history, in the order they were unlocked.
+///
+/// Flights from or to airports that are not in the airport database are skipped
+/// for the achievements that need their position.
+///
+/// # Arguments
+///
+/// * `history` - The flown flights.
+/// * `airports` - All airports, to look up the airports of the flights.
+/// * `all_aircraft` - All aircraft, for the whole fleet achievement.
+pub fn unlocked_achievements(
+    history: &[History],
+    airports: &[Arc<Airport>],
+    all_aircraft: &[Arc<Aircraft>],
+) -> Vec<Unlock> {
+    let by_icao: HashMap<String, &Airport> = airports
+        .iter()
+        .map(|airport| (airport.ICAO.to_uppercase(), airport.as_ref()))
+        .collect();
+    let fleet: HashSet<i32> = all_aircraft.iter().map(|aircraft| aircraft.id).collect();
+
+    let mut flights: Vec<&History> = history.iter().collect();
+    flights.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+
+    let mut unlocks: Vec<Unlock> = Vec::new();
+    let mut countries = HashSet::new();
+    let mut flown_aircraft = HashSet::new();
+    for flight in flights {
+        let mut reached = Vec::new();
+
+        flown_aircraft.insert(flight.aircraft);
+        if !fleet.is_empty() && fleet.is_subset(&flown_aircraft) {
+            reached.push(Achievement::WholeFleet);
+        }
+
+        for icao in [&flight.departure_icao, &flight.arrival_icao] {
+            if let Some(country) = icao_country(&icao.to_uppercase()) {
+                countries.insert(country.to_string());
+            }
+        }
+        if countries.len() >= COUNTRIES {
+            reached.push(Achievement::WorldTraveller);
+        }
+
+        let departure = by_icao.get(&flight.departure_icao.to_uppercase());
+        let arrival = by_icao.get(&flight.arrival_icao.to_uppercase());
+        if let (Some(departure), Some(arrival)) = (departure, arrival) {
+            if airport_distance_nm(departure, arrival) >= LONG_HAUL_NM {
+                reached.push(Achievement::LongHaul);
+            }
+            if crosses_polar_circle(departure, arrival) {
+                reached.push(Achievement::PolarCircle);
+            }
+        }
+
+        for achievement in reached {
+            if !unlocks
+                .iter()
+                .any(|unlock| unlock.achievement == achievement)
+            {
+                unlocks.push(Unlock {
+                    achievement,
+                    date: flight.date.clone(),
+                });
+            }
+        }
+    }
+
+    unlocks
+}
@@ -0,0 +1,160 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use std::fmt;
+
+use crate::errors::ValidationError;
+
+/// The length of an AIRAC cycle in days.
+const CYCLE_DAYS: i64 = 28;
+
+/// The effective date of a known cycle, 2401, from which all others are counted.
+fn reference_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 25).expect("valid reference date")
+}
+
+/// An AIRAC cycle, written as `YYNN` with the year and the number of the cycle in that year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AiracCycle {
+    /// The date the cycle becomes effective.
+    effective: NaiveDate,
+}
+
+impl AiracCycle {
+    /// Returns the cycle in effect on a date.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date to look up.
+    pub fn at(date: NaiveDate) -> AiracCycle {
+        let cycles = (date - reference_date()).num_days().div_euclid(CYCLE_DAYS);
+
+        AiracCycle {
+            effective: reference_date() + Duration::days(cycles * CYCLE_DAYS),
+        }
+    }
+
+    /// Parses a cycle such as `2401`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle` - Four digits, the last two digits of the year followed by the cycle number.
+    pub fn parse(cycle: &str) -> Result<AiracCycle, ValidationError> {
+        let invalid = || {
+            ValidationError::InvalidData(format!(
+                "Invalid AIRAC cycle {}, expected YYNN such as 2401",
+                cycle
+            ))
+        };
+
+        if cycle.len() != 4 || !cycle.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let year = 2000 + cycle[..2].parse::<i32>().map_err(|_| invalid())?;
+        let number = cycle[2..].parse::<i64>().map_err(|_| invalid())?;
+        if number == 0 {
+            return Err(invalid());
+        }
+
+        // The first cycle of a year is the first one effective on or after 1 January.
+        let new_year = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid)?;
+        let last_of_previous_year = AiracCycle::at(new_year - Duration::days(1));
+        let effective = last_of_previous_year.effective + Duration::days(number * CYCLE_DAYS);
+
+        if effective.year() != year {
+            return Err(invalid());
+        }
+
+        Ok(AiracCycle { effective })
+    }
+
+    /// Returns the date the cycle becomes effective.
+    pub fn effective_date(&self) -> NaiveDate {
+        self.effective
+    }
+
+    /// Returns how many cycles this cycle is behind the one in effect on a date.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date to compare with, usually today.
+    pub fn cycles_behind(&self, date: NaiveDate) -> i64 {
+        (AiracCycle::at(date).effective - self.effective).num_days() / CYCLE_DAYS
+    }
+}
+
+impl fmt::Display for AiracCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = (self.effective.ordinal0() as i64) / CYCLE_DAYS + 1;
+        write!(f, "{:02}{:02}", self.effective.year() % 100, number)
+    }
+}
+
+/// Returns a warning if the cycle is older than the one in effect today.
+///
+/// # Arguments
+///
+/// * `cycle` - The AIRAC cycle of the airport database.
+pub fn stale_cycle_warning(cycle: AiracCycle) -> Option<String> {
+    let today = chrono::Local::now().date_naive();
+    let behind = cycle.cycles_behind(today);
+
+    (behind > 0).then(|| {
+        format!(
+            "⚠ AIRAC {} is {} cycle{} behind the current cycle {}",
+            cycle,
+            behind,
+            if behind == 1 { "" } else { "s" },
+            AiracCycle::at(today)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_known_cycles() {
+        let cases = [
+            ("2401", date(2024, 1, 25)),
+            ("2413", date(2024, 12, 26)),
+            ("2501", date(2025, 1, 23)),
+            // 2020 had a 14th cycle.
+            ("2014", date(2020, 12, 31)),
+            ("2101", date(2021, 1, 28)),
+        ];
+
+        for (cycle, effective) in cases {
+            let parsed = AiracCycle::parse(cycle).unwrap();
+            assert_eq!(parsed.effective_date(), effective, "{}", cycle);
+            assert_eq!(parsed.to_string(), cycle);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_cycles() {
+        for cycle in ["2414", "2400", "240", "24011", "24a1"] {
+            assert!(AiracCycle::parse(cycle).is_err(), "{}", cycle);
+        }
+    }
+
+    #[test]
+    fn finds_the_cycle_in_effect() {
+        assert_eq!(AiracCycle::at(date(2024, 1, 25)).to_string(), "2401");
+        assert_eq!(AiracCycle::at(date(2024, 2, 21)).to_string(), "2401");
+        assert_eq!(AiracCycle::at(date(2024, 2, 22)).to_string(), "2402");
+        assert_eq!(AiracCycle::at(date(2021, 1, 27)).to_string(), "2014");
+        assert_eq!(AiracCycle::at(date(2024, 1, 1)).to_string(), "2313");
+    }
+
+    #[test]
+    fn counts_cycles_behind() {
+        let cycle = AiracCycle::parse("2401").unwrap();
+        assert_eq!(cycle.cycles_behind(date(2024, 2, 21)), 0);
+        assert_eq!(cycle.cycles_behind(date(2024, 2, 22)), 1);
+        assert_eq!(cycle.cycles_behind(date(2025, 1, 23)), 13);
+    }
+}
@@ -35,14 +35,6 @@ impl AircraftOperations for DatabaseConnections {
         Ok(records)
     }
 
-    fn update_aircraft(&mut self, record: &Aircraft) -> Result<(), Error> {
-        diesel::update(aircraft.find(record.id))
-            .set(record)
-            .execute(&mut self.aircraft_connection)?;
-
-        Ok(())
-    }
-
     fn random_aircraft(&mut self) -> Result<Aircraft, Error> {
         let record: Aircraft = aircraft
             .order(random())
@@ -66,6 +58,10 @@ impl AircraftOperations for DatabaseConnections {
     fn mark_all_aircraft_not_flown(&mut self) -> Result<(), Error> {
         mark_all_aircraft_not_flown(&mut self.aircraft_connection)
     }
+
+    fn recount_flights(&mut self) -> Result<usize, Error> {
+        recount_flights(&mut self.aircraft_connection)
+    }
 }
 
 impl AircraftOperations for DatabasePool {
@@ -94,15 +90,6 @@ impl AircraftOperations for DatabasePool {
         Ok(records)
     }
 
-    fn update_aircraft(&mut self, record: &Aircraft) -> Result<(), Error> {
-        let conn = &mut self.aircraft_pool.get().unwrap();
-        diesel::update(aircraft.find(record.id))
-            .set(record)
-            .execute(conn)?;
-
-        Ok(())
-    }
-
     fn random_aircraft(&mut self) -> Result<Aircraft, Error> {
         let conn = &mut self.aircraft_pool.get().unwrap();
         let record: Aircraft = aircraft.order(random()).limit(1).get_result(conn)?;
@@ -123,6 +110,10 @@ impl AircraftOperations for DatabasePool {
     fn mark_all_aircraft_not_flown(&mut self) -> Result<(), Error> {
         mark_all_aircraft_not_flown(&mut self.aircraft_pool.get().unwrap())
     }
+
+    fn recount_flights(&mut self) -> Result<usize, Error> {
+        recount_flights(&mut self.aircraft_pool.get().unwrap())
+    }
 }
 
 fn mark_all_aircraft_not_flown(conn: &mut SqliteConnection) -> Result<(), Error> {
@@ -133,9 +124,26 @@ fn mark_all_aircraft_not_flown(conn: &mut SqliteConnection) -> Result<(), Error>
     Ok(())
 }
 
+/// Sets the flight count and last flown date of every aircraft from the history.
+///
+/// # Returns
+///
+/// The number of aircraft whose count or date changed.
+fn recount_flights(conn: &mut SqliteConnection) -> Result<usize, Error> {
+    diesel::sql_query(
+        "UPDATE aircraft SET \
+             flown = (SELECT COUNT(*) FROM history WHERE history.aircraft = aircraft.id), \
+             date_flown = (SELECT MAX(date) FROM history WHERE history.aircraft = aircraft.id) \
+         WHERE flown != (SELECT COUNT(*) FROM history WHERE history.aircraft = aircraft.id) \
+             OR date_flown IS NOT (SELECT MAX(date) FROM history WHERE history.aircraft = aircraft.id)",
+    )
+    .execute(conn)
+}
+
 pub fn format_aircraft(ac: &Aircraft) -> String {
     format!(
-        "id: {}, {} {}{}, range: {}, category: {}, cruise speed: {} knots, takeoff distance: {}",
+        "id: {}, {} {}{}, range: {}, category: {}{}, cruise speed: {} knots, takeoff distance: {}, \
+         flights: {}",
         ac.id,
         ac.manufacturer,
         ac.variant,
@@ -146,9 +154,15 @@ pub fn format_aircraft(ac: &Aircraft) -> String {
         },
         ac.aircraft_range,
         ac.category,
+        match (ac.helicopter, ac.amphibian) {
+            (true, _) => " (helicopter)",
+            (false, true) => " (amphibian)",
+            (false, false) => "",
+        },
         ac.cruise_speed,
         ac.takeoff_distance
             .map_or("unknown".to_string(), |d| format!("{} m", d)),
+        ac.flown,
     )
 }
 
@@ -1,5 +1,9 @@
-use crate::gui::SpatialAirport;
 use crate::models::*;
+use crate::modules::iata::{get_iata_codes, import_iata_codes};
+use crate::modules::routes::{
+    airport_bearing, airport_distance_nm, GeoArea, RouteConstraints, SpatialAirport,
+};
+use crate::modules::runway::can_use_runway;
 use crate::schema::Airports::dsl::*;
 use crate::traits::{AircraftOperations, AirportOperations};
 use crate::DatabaseConnections;
@@ -8,7 +12,7 @@ use diesel::prelude::*;
 use diesel::result::Error;
 use geo::{Distance, Haversine};
 use rand::seq::SliceRandom;
-use rstar::{RTree, AABB};
+use rstar::RTree;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -81,6 +85,34 @@ impl AirportOperations for DatabaseConnections {
 
         Ok(airports)
     }
+
+    fn get_airport_by_icao(&mut self, icao: &str) -> Result<Airport, Error> {
+        let airport = Airports
+            .filter(ICAO.eq(icao.to_uppercase()))
+            .first::<Airport>(&mut self.airport_connection)?;
+
+        Ok(airport)
+    }
+
+    fn get_iata_codes(&mut self) -> Result<Vec<AirportIataCode>, Error> {
+        get_iata_codes(&mut self.airport_connection)
+    }
+
+    fn import_iata_codes(&mut self, records: &[AirportIataCode]) -> Result<usize, Error> {
+        import_iata_codes(&mut self.airport_connection, records)
+    }
+
+    fn get_airport_aliases(&mut self) -> Result<Vec<AirportAlias>, Error> {
+        use crate::schema::airport_icao_aliases::dsl::*;
+
+        airport_icao_aliases
+            .order(alias)
+            .load::<AirportAlias>(&mut self.aircraft_connection)
+    }
+
+    fn add_airport_alias(&mut self, record: &AirportAlias) -> Result<(), Error> {
+        add_airport_alias(&mut self.aircraft_connection, record)
+    }
 }
 
 impl AirportOperations for DatabasePool {
@@ -157,6 +189,160 @@ impl AirportOperations for DatabasePool {
 
         Ok(airports)
     }
+
+    fn get_airport_by_icao(&mut self, icao: &str) -> Result<Airport, Error> {
+        let conn = &mut self.airport_pool.get().unwrap();
+        let airport = Airports
+            .filter(ICAO.eq(icao.to_uppercase()))
+            .first::<Airport>(conn)?;
+
+        Ok(airport)
+    }
+
+    fn get_iata_codes(&mut self) -> Result<Vec<AirportIataCode>, Error> {
+        get_iata_codes(&mut self.airport_pool.get().unwrap())
+    }
+
+    fn import_iata_codes(&mut self, records: &[AirportIataCode]) -> Result<usize, Error> {
+        import_iata_codes(&mut self.airport_pool.get().unwrap(), records)
+    }
+
+    fn get_airport_aliases(&mut self) -> Result<Vec<AirportAlias>, Error> {
+        use crate::schema::airport_icao_aliases::dsl::*;
+        let conn = &mut self.aircraft_pool.get().unwrap();
+
+        airport_icao_aliases.order(alias).load::<AirportAlias>(conn)
+    }
+
+    fn add_airport_alias(&mut self, record: &AirportAlias) -> Result<(), Error> {
+        add_airport_alias(&mut self.aircraft_pool.get().unwrap(), record)
+    }
+}
+
+/// Finds an airport by its IATA code, such as `AMS`.
+///
+/// # Arguments
+///
+/// * `db` - The database to look the code and airport up in.
+/// * `iata` - The IATA code, matched case-insensitively.
+pub fn airport_by_iata<T: AirportOperations>(db: &mut T, iata: &str) -> Result<Airport, Error> {
+    let code = db
+        .get_iata_codes()?
+        .into_iter()
+        .find(|code| code.iata.eq_ignore_ascii_case(iata))
+        .ok_or(Error::NotFound)?;
+
+    db.get_airport_by_icao(&code.icao)
+}
+
+/// Finds an airport by its ICAO code, a former ICAO code or its IATA code.
+///
+/// # Arguments
+///
+/// * `db` - The database to look the code and airport up in.
+/// * `code` - The code, matched case-insensitively.
+pub fn airport_by_code<T: AirportOperations>(db: &mut T, code: &str) -> Result<Airport, Error> {
+    match db.get_airport_by_icao(code) {
+        Err(Error::NotFound) => {}
+        result => return result,
+    }
+
+    let alias = db
+        .get_airport_aliases()?
+        .into_iter()
+        .find(|alias| alias.alias.eq_ignore_ascii_case(code));
+    match alias {
+        Some(alias) => db.get_airport_by_icao(&alias.icao),
+        None if code.len() == 3 => airport_by_iata(db, code),
+        None => Err(Error::NotFound),
+    }
+}
+
+fn add_airport_alias(db: &mut SqliteConnection, record: &AirportAlias) -> Result<(), Error> {
+    use crate::schema::airport_icao_aliases;
+
+    diesel::replace_into(airport_icao_aliases::table)
+        .values(record)
+        .execute(db)?;
+
+    Ok(())
+}
+
+/// Alternative codes of airports: IATA codes and former ICAO codes.
+#[derive(Default)]
+pub struct AirportCodes {
+    /// IATA codes keyed by ICAO code.
+    iata_codes: HashMap<String, String>,
+    /// Current ICAO codes keyed by former ICAO code.
+    aliases: HashMap<String, String>,
+    /// Former ICAO codes keyed by current ICAO code.
+    aliases_by_icao: HashMap<String, Vec<String>>,
+}
+
+impl AirportCodes {
+    /// Loads the IATA codes and aliases.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database to load the codes from.
+    pub fn load<T: AirportOperations>(db: &mut T) -> Result<AirportCodes, Error> {
+        let mut codes = AirportCodes {
+            iata_codes: db
+                .get_iata_codes()?
+                .into_iter()
+                .map(|code| (code.icao, code.iata))
+                .collect(),
+            ..AirportCodes::default()
+        };
+
+        for record in db.get_airport_aliases()? {
+            codes
+                .aliases_by_icao
+                .entry(record.icao.clone())
+                .or_default()
+                .push(record.alias.clone());
+            codes.aliases.insert(record.alias, record.icao);
+        }
+
+        Ok(codes)
+    }
+
+    /// Returns the IATA code of an airport.
+    ///
+    /// # Arguments
+    ///
+    /// * `icao` - The ICAO code of the airport.
+    pub fn iata(&self, icao: &str) -> Option<&str> {
+        self.iata_codes.get(icao).map(String::as_str)
+    }
+
+    /// Returns the current ICAO code for a code that may have been replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `icao` - The ICAO code, for example from an old logbook entry.
+    pub fn current_icao<'a>(&'a self, icao: &'a str) -> &'a str {
+        self.aliases.get(icao).map_or(icao, String::as_str)
+    }
+
+    /// Returns whether the IATA code or a former ICAO code of an airport contains the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to check.
+    /// * `query` - The lowercase search query.
+    pub fn matches(&self, airport: &Airport, query: &str) -> bool {
+        self.iata(&airport.ICAO)
+            .is_some_and(|iata| iata.to_lowercase().contains(query))
+            || self
+                .aliases_by_icao
+                .get(&airport.ICAO)
+                .is_some_and(|aliases| {
+                    aliases
+                        .iter()
+                        .any(|alias| alias.to_lowercase().contains(query))
+                })
+    }
 }
 
 pub fn format_airport(airport: &Airport) -> String {
@@ -220,6 +406,9 @@ fn get_destination_airport_with_suitable_runway(
         .filter(Longtitude.le(max_lon))
         .filter(ID.ne(departure.ID))
         .filter(Runways::Length.ge(min_takeoff_distance_ft))
+        // Water runways and helipads, named like `H1`, are no use to fixed-wing aircraft.
+        .filter(Runways::Surface.ne("W"))
+        .filter(Runways::Ident.not_like("H%"))
         .order(random())
         .select(Airports::all_columns())
         .first::<Airport>(db)?;
@@ -281,6 +470,8 @@ fn get_random_airport_for_aircraft(
         let airport = Airports
             .inner_join(Runways::table)
             .filter(Runways::Length.ge(min_takeoff_distance_ft))
+            .filter(Runways::Surface.ne("W"))
+            .filter(Runways::Ident.not_like("H%"))
             .select(Airports::all_columns())
             .distinct()
             .order(random())
@@ -297,38 +488,64 @@ pub fn get_destination_airport_with_suitable_runway_fast(
     departure: &Airport,
     spatial_airports: &RTree<SpatialAirport>,
     runways_by_airport: &HashMap<i32, Arc<Vec<Runway>>>,
+    constraints: &RouteConstraints,
+) -> Result<Arc<Airport>, std::io::Error> {
+    get_airport_with_suitable_runway_where(
+        aircraft,
+        departure,
+        spatial_airports,
+        runways_by_airport,
+        constraints,
+        |_| true,
+    )
+}
+
+/// Picks a random airport within range of `departure` that the aircraft can use
+/// and that passes `accept` as well as the constraints.
+///
+/// # Arguments
+///
+/// * `accept` - An extra check on each candidate, applied before one is picked.
+pub fn get_airport_with_suitable_runway_where(
+    aircraft: &Aircraft,
+    departure: &Airport,
+    spatial_airports: &RTree<SpatialAirport>,
+    runways_by_airport: &HashMap<i32, Arc<Vec<Runway>>>,
+    constraints: &RouteConstraints,
+    accept: impl Fn(&Airport) -> bool,
 ) -> Result<Arc<Airport>, std::io::Error> {
-    const M_TO_FT: f64 = 3.28084;
-
-    let max_distance_nm = aircraft.aircraft_range;
-    let search_radius_deg = max_distance_nm as f64 / 60.0;
-
-    let min_point = [
-        departure.Latitude - search_radius_deg,
-        departure.Longtitude - search_radius_deg,
-    ];
-    let max_point = [
-        departure.Latitude + search_radius_deg,
-        departure.Longtitude + search_radius_deg,
-    ];
-    let search_envelope = AABB::from_corners(min_point, max_point);
-    let candidate_airports = spatial_airports.locate_in_envelope(&search_envelope);
+    let departure_area = GeoArea {
+        min_latitude: departure.Latitude,
+        max_latitude: departure.Latitude,
+        min_longitude: departure.Longtitude,
+        max_longitude: departure.Longtitude,
+    };
+    // The envelopes cover the range in degrees, so the distance is checked below.
+    let envelopes = departure_area.envelopes_around(constraints.max_distance_nm(aircraft));
+    let candidate_airports = envelopes
+        .iter()
+        .flat_map(|envelope| spatial_airports.locate_in_envelope(envelope));
 
     let mut suitable_airports = Vec::new();
     for spatial_airport in candidate_airports {
         let airport = &spatial_airport.airport;
-        // (same checks as before)
-        if let Some(runways) = runways_by_airport.get(&airport.ID) {
-            if let Some(longest_runway) = runways.iter().max_by_key(|r| r.Length) {
-                if let Some(takeoff_distance_m) = aircraft.takeoff_distance {
-                    let takeoff_distance_ft = (takeoff_distance_m as f64 * M_TO_FT) as i32;
-                    if longest_runway.Length >= takeoff_distance_ft {
-                        suitable_airports.push(Arc::clone(airport));
-                    }
-                } else {
-                    suitable_airports.push(Arc::clone(airport));
-                }
-            }
+        if airport.ID == departure.ID
+            || !constraints.allows(aircraft, airport_distance_nm(departure, airport))
+            || !constraints.allows_bearing(airport_bearing(departure, airport))
+            || !constraints.allows_position(airport)
+            || !constraints.allows_path(departure, airport)
+            || !constraints.allows_arrival(aircraft, departure, airport)
+            || !accept(airport)
+        {
+            continue;
+        }
+        if runways_by_airport.get(&airport.ID).is_some_and(|runways| {
+            constraints.allows_runways(aircraft, runways)
+                && runways
+                    .iter()
+                    .any(|runway| can_use_runway(aircraft, runway))
+        }) {
+            suitable_airports.push(Arc::clone(airport));
         }
     }
 
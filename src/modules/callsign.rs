@@ -0,0 +1,69 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::checksum::checksum;
+use crate::modules::routes::Route;
+
+/// How the callsigns of generated routes are formed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CallsignStyle {
+    /// An airline prefix followed by a flight number, such as `KLM1234`.
+    #[default]
+    Airline,
+    /// A general aviation registration, such as `PH-ABC` or `N123AB`.
+    Registration,
+}
+
+impl CallsignStyle {
+    pub const ALL: [CallsignStyle; 2] = [CallsignStyle::Airline, CallsignStyle::Registration];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CallsignStyle::Airline => "Airline",
+            CallsignStyle::Registration => "Registration",
+        }
+    }
+}
+
+/// Returns a random callsign for the route.
+///
+/// The callsign is drawn from a generator seeded with the airports and the
+/// aircraft with a hash that is stable across Rust releases, so the same
+/// route keeps the same callsign.
+///
+/// # Arguments
+///
+/// * `route` - The route to name.
+/// * `style` - Whether to form a flight number or a registration.
+/// * `prefix` - The airline code or the nationality prefix of the registration.
+pub fn route_callsign(route: &Route, style: CallsignStyle, prefix: &str) -> String {
+    let key = format!(
+        "{} {} {}",
+        route.departure.ICAO, route.destination.ICAO, route.aircraft.id
+    );
+    let mut rng = StdRng::seed_from_u64(checksum(key.as_bytes()));
+
+    let prefix = prefix.trim().to_uppercase();
+    match style {
+        CallsignStyle::Airline => {
+            let number: u16 = rng.gen_range(1..=9999);
+            format!("{}{}", prefix, number)
+        }
+        // United States registrations are numbers, optionally followed by letters.
+        CallsignStyle::Registration if prefix == "N" => {
+            let number: u16 = rng.gen_range(1..=999);
+            format!("N{}{}", number, random_letters(&mut rng, 2))
+        }
+        CallsignStyle::Registration => format!("{}-{}", prefix, random_letters(&mut rng, 3)),
+    }
+}
+
+/// Returns a string of random capital letters.
+fn random_letters(rng: &mut StdRng, count: usize) -> String {
+    (0..count)
+        .map(|_| char::from(rng.gen_range(b'A'..=b'Z')))
+        .collect()
+}
@@ -0,0 +1,44 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Computes a FNV-1a checksum of bytes. Unlike the hashers of the standard
+/// library, the result never changes between Rust releases.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to checksum.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    fnv1a(FNV_OFFSET_BASIS, bytes)
+}
+
+/// Computes a FNV-1a checksum of a file's contents.
+///
+/// # Arguments
+///
+/// * `path` - The file to checksum.
+pub fn file_checksum(path: &Path) -> io::Result<u64> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = [0u8; 64 * 1024];
+    let mut hash = FNV_OFFSET_BASIS;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hash = fnv1a(hash, &buffer[..read]);
+    }
+
+    Ok(hash)
+}
+
+/// Continues a FNV-1a checksum with more bytes.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
@@ -0,0 +1,250 @@
+use chrono::NaiveDate;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{BigInt, Integer, Text};
+use std::collections::HashMap;
+
+use crate::models::DataSource;
+use crate::modules::airac::AiracCycle;
+use crate::traits::DataSourceOperations;
+use crate::DatabaseConnections;
+use crate::DatabasePool;
+
+/// Tables of the airport database recording where the airports came from.
+pub const DATA_SOURCE_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS `data_sources`(
+	`id` INTEGER NOT NULL PRIMARY KEY,
+	`name` TEXT NOT NULL,
+	`version` TEXT NOT NULL,
+	`date` TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS `airport_sources`(
+	`airport_id` INTEGER NOT NULL PRIMARY KEY,
+	`source_id` INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS `airac_cycle`(
+	`id` INTEGER NOT NULL PRIMARY KEY CHECK (`id` = 1),
+	`cycle` TEXT NOT NULL
+);
+";
+
+impl DataSourceOperations for SqliteConnection {
+    fn get_data_sources(&mut self) -> Result<Vec<DataSource>, Error> {
+        get_data_sources(self)
+    }
+
+    fn add_data_source(&mut self, name: &str, version: &str, date: &str) -> Result<usize, Error> {
+        add_data_source(self, name, version, date)
+    }
+
+    fn get_airac_cycle(&mut self) -> Result<Option<AiracCycle>, Error> {
+        get_airac_cycle(self)
+    }
+
+    fn set_airac_cycle(&mut self, cycle: AiracCycle) -> Result<(), Error> {
+        set_airac_cycle(self, cycle)
+    }
+}
+
+impl DataSourceOperations for DatabaseConnections {
+    fn get_data_sources(&mut self) -> Result<Vec<DataSource>, Error> {
+        get_data_sources(&mut self.airport_connection)
+    }
+
+    fn add_data_source(&mut self, name: &str, version: &str, date: &str) -> Result<usize, Error> {
+        add_data_source(&mut self.airport_connection, name, version, date)
+    }
+
+    fn get_airac_cycle(&mut self) -> Result<Option<AiracCycle>, Error> {
+        get_airac_cycle(&mut self.airport_connection)
+    }
+
+    fn set_airac_cycle(&mut self, cycle: AiracCycle) -> Result<(), Error> {
+        set_airac_cycle(&mut self.airport_connection, cycle)
+    }
+}
+
+impl DataSourceOperations for DatabasePool {
+    fn get_data_sources(&mut self) -> Result<Vec<DataSource>, Error> {
+        get_data_sources(&mut self.airport_pool.get().unwrap())
+    }
+
+    fn add_data_source(&mut self, name: &str, version: &str, date: &str) -> Result<usize, Error> {
+        add_data_source(&mut self.airport_pool.get().unwrap(), name, version, date)
+    }
+
+    fn get_airac_cycle(&mut self) -> Result<Option<AiracCycle>, Error> {
+        get_airac_cycle(&mut self.airport_pool.get().unwrap())
+    }
+
+    fn set_airac_cycle(&mut self, cycle: AiracCycle) -> Result<(), Error> {
+        set_airac_cycle(&mut self.airport_pool.get().unwrap(), cycle)
+    }
+}
+
+/// Returns the age in days of the oldest data source, or `None` if no source
+/// with a readable date is recorded.
+///
+/// # Arguments
+///
+/// * `sources` - The recorded data sources.
+/// * `today` - The date to measure the age at.
+pub fn oldest_data_age_days(sources: &[DataSource], today: NaiveDate) -> Option<i64> {
+    sources
+        .iter()
+        .filter_map(|source| NaiveDate::parse_from_str(&source.date, "%Y-%m-%d").ok())
+        .min()
+        .map(|date| (today - date).num_days())
+}
+
+/// Returns whether a table exists in the database.
+///
+/// # Arguments
+///
+/// * `db` - The database connection.
+/// * `schema` - The schema to look in, `main` or the name of an attached database.
+/// * `table` - The name of the table.
+pub fn table_exists(db: &mut SqliteConnection, schema: &str, table: &str) -> Result<bool, Error> {
+    let count = diesel::sql_query(format!(
+        "SELECT COUNT(*) AS count FROM {}.sqlite_master WHERE type = 'table' AND name = ?",
+        schema
+    ))
+    .bind::<Text, _>(table)
+    .get_result::<TableCount>(db)?;
+
+    Ok(count.count > 0)
+}
+
+/// Returns the data source ID of every airport that has one, keyed by airport ID.
+///
+/// # Arguments
+///
+/// * `db` - An airport database.
+pub fn airport_source_ids(db: &mut SqliteConnection) -> Result<HashMap<i32, i32>, Error> {
+    if !table_exists(db, "main", "airport_sources")? {
+        return Ok(HashMap::new());
+    }
+
+    let rows = diesel::sql_query("SELECT airport_id, source_id FROM airport_sources")
+        .load::<AirportSource>(db)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.airport_id, row.source_id))
+        .collect())
+}
+
+#[derive(QueryableByName)]
+struct AirportSource {
+    #[diesel(sql_type = Integer)]
+    airport_id: i32,
+    #[diesel(sql_type = Integer)]
+    source_id: i32,
+}
+
+#[derive(QueryableByName)]
+struct TableCount {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Returns the recorded data sources with their number of airports, oldest first.
+fn get_data_sources(db: &mut SqliteConnection) -> Result<Vec<DataSource>, Error> {
+    if !table_exists(db, "main", "data_sources")? {
+        return Ok(Vec::new());
+    }
+
+    diesel::sql_query(
+        "SELECT d.id, d.name, d.version, d.date, COUNT(a.airport_id) AS airports \
+         FROM data_sources d LEFT JOIN airport_sources a ON a.source_id = d.id \
+         GROUP BY d.id ORDER BY d.date, d.id",
+    )
+    .load::<DataSource>(db)
+}
+
+/// Records a data source and attributes every airport without a source to it.
+///
+/// # Returns
+///
+/// The number of airports attributed to the source.
+fn add_data_source(
+    db: &mut SqliteConnection,
+    name: &str,
+    version: &str,
+    date: &str,
+) -> Result<usize, Error> {
+    db.batch_execute(DATA_SOURCE_SCHEMA)?;
+
+    db.transaction(|db| {
+        let source_id = insert_data_source(db, name, version, date)?;
+        diesel::sql_query(
+            "INSERT INTO airport_sources (airport_id, source_id) \
+             SELECT ID, ? FROM Airports \
+             WHERE ID NOT IN (SELECT airport_id FROM airport_sources)",
+        )
+        .bind::<Integer, _>(source_id)
+        .execute(db)
+    })
+}
+
+/// Returns the AIRAC cycle the airport database is tagged with, if any.
+fn get_airac_cycle(db: &mut SqliteConnection) -> Result<Option<AiracCycle>, Error> {
+    if !table_exists(db, "main", "airac_cycle")? {
+        return Ok(None);
+    }
+
+    let cycle = diesel::sql_query("SELECT cycle FROM airac_cycle")
+        .get_result::<AiracCycleRow>(db)
+        .optional()?;
+
+    Ok(cycle.and_then(|row| match AiracCycle::parse(&row.cycle) {
+        Ok(cycle) => Some(cycle),
+        Err(e) => {
+            log::warn!("Ignoring the AIRAC cycle of the airport database: {}", e);
+            None
+        }
+    }))
+}
+
+/// Tags the airport database with an AIRAC cycle, replacing any previous one.
+fn set_airac_cycle(db: &mut SqliteConnection, cycle: AiracCycle) -> Result<(), Error> {
+    db.batch_execute(DATA_SOURCE_SCHEMA)?;
+
+    diesel::sql_query("REPLACE INTO airac_cycle (id, cycle) VALUES (1, ?)")
+        .bind::<Text, _>(cycle.to_string())
+        .execute(db)?;
+
+    Ok(())
+}
+
+#[derive(QueryableByName)]
+struct AiracCycleRow {
+    #[diesel(sql_type = Text)]
+    cycle: String,
+}
+
+/// Inserts a data source row and returns its ID.
+///
+/// # Arguments
+///
+/// * `db` - A database with the [`DATA_SOURCE_SCHEMA`] tables.
+/// * `name` - The name of the source, such as `OurAirports`.
+/// * `version` - The version or cycle of the data.
+/// * `date` - The date of the data as `YYYY-MM-DD`.
+pub fn insert_data_source(
+    db: &mut SqliteConnection,
+    name: &str,
+    version: &str,
+    date: &str,
+) -> Result<i32, Error> {
+    diesel::sql_query("INSERT INTO data_sources (name, version, date) VALUES (?, ?, ?)")
+        .bind::<Text, _>(name)
+        .bind::<Text, _>(version)
+        .bind::<Text, _>(date)
+        .execute(db)?;
+
+    diesel::select(diesel::dsl::sql::<Integer>("last_insert_rowid()")).get_result(db)
+}
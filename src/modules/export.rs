@@ -0,0 +1,188 @@
+use geo::{Haversine, InterpolatePoint};
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::models::{AircraftSimTitle, Airport};
+use crate::modules::routes::Route;
+use crate::modules::simulator::describe_sim_titles;
+
+/// The longest straight segment drawn between great circle points, in nautical miles.
+const SEGMENT_NM: f64 = 100.0;
+/// The most segments a route is drawn with.
+const MAX_SEGMENTS: usize = 64;
+const FEET_TO_METERS: f64 = 0.3048;
+
+/// A file format routes can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// GPS Exchange Format, read by most GPS and planning tools.
+    #[default]
+    Gpx,
+    /// Keyhole Markup Language, read by Google Earth.
+    Kml,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Gpx, ExportFormat::Kml];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Gpx => "GPX",
+            ExportFormat::Kml => "KML",
+        }
+    }
+
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Gpx => "gpx",
+            ExportFormat::Kml => "kml",
+        }
+    }
+
+    /// Returns the format matching the extension of a file name.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to export to.
+    pub fn from_path(path: &Path) -> Option<ExportFormat> {
+        let extension = path.extension()?.to_str()?;
+        ExportFormat::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+}
+
+/// Serializes routes to a document in the format.
+///
+/// # Arguments
+///
+/// * `routes` - The routes to export.
+/// * `format` - The format of the document.
+/// * `sim_titles` - The simulator titles of the aircraft, named in the route descriptions.
+pub fn export_routes(
+    routes: &[&Route],
+    format: ExportFormat,
+    sim_titles: &[AircraftSimTitle],
+) -> String {
+    match format {
+        ExportFormat::Gpx => routes_to_gpx(routes, sim_titles),
+        ExportFormat::Kml => routes_to_kml(routes, sim_titles),
+    }
+}
+
+/// Returns the name of a route, such as `EHAM-EGLL`.
+fn route_name(route: &Route) -> String {
+    format!("{}-{}", route.departure.ICAO, route.destination.ICAO)
+}
+
+/// Returns the aircraft, its simulator titles and the distance of a route.
+fn route_description(route: &Route, sim_titles: &[AircraftSimTitle]) -> String {
+    let mut description = format!("{} {}", route.aircraft.manufacturer, route.aircraft.variant);
+    if let Some(titles) = describe_sim_titles(sim_titles, route.aircraft.id) {
+        let _ = write!(description, " ({})", titles);
+    }
+    let _ = write!(description, ", {:.0} NM", route.distance_nm());
+
+    description
+}
+
+fn routes_to_gpx(routes: &[&Route], sim_titles: &[AircraftSimTitle]) -> String {
+    let mut gpx = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <gpx version=\"1.1\" creator=\"flight-planner\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+    for route in routes {
+        let _ = writeln!(
+            gpx,
+            "  <rte>\n    <name>{}</name>\n    <desc>{}</desc>",
+            escape_xml(&route_name(route)),
+            escape_xml(&route_description(route, sim_titles))
+        );
+        for airport in [&route.departure, &route.destination] {
+            let _ = writeln!(
+                gpx,
+                "    <rtept lat=\"{:.6}\" lon=\"{:.6}\"><ele>{:.0}</ele><name>{}</name><desc>{}</desc></rtept>",
+                airport.Latitude,
+                airport.Longtitude,
+                f64::from(airport.Elevation) * FEET_TO_METERS,
+                escape_xml(&airport.ICAO),
+                escape_xml(&airport.Name)
+            );
+        }
+        gpx.push_str("  </rte>\n");
+    }
+    gpx.push_str("</gpx>\n");
+
+    gpx
+}
+
+fn routes_to_kml(routes: &[&Route], sim_titles: &[AircraftSimTitle]) -> String {
+    let mut kml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n<name>Flight planner routes</name>\n",
+    );
+    for route in routes {
+        let name = escape_xml(&route_name(route));
+        let _ = writeln!(
+            kml,
+            "<Folder>\n<name>{}</name>\n<description>{}</description>",
+            name,
+            escape_xml(&route_description(route, sim_titles))
+        );
+        for airport in [&route.departure, &route.destination] {
+            let _ = writeln!(
+                kml,
+                "<Placemark><name>{}</name><description>{}</description>\
+                 <Point><coordinates>{:.6},{:.6},0</coordinates></Point></Placemark>",
+                escape_xml(&airport.ICAO),
+                escape_xml(&airport.Name),
+                airport.Longtitude,
+                airport.Latitude
+            );
+        }
+        let coordinates: Vec<String> = great_circle_points(&route.departure, &route.destination)
+            .iter()
+            .map(|point| format!("{:.6},{:.6},0", point.x(), point.y()))
+            .collect();
+        let _ = writeln!(
+            kml,
+            "<Placemark><name>{}</name><LineString><tessellate>1</tessellate>\
+             <coordinates>{}</coordinates></LineString></Placemark>\n</Folder>",
+            name,
+            coordinates.join(" ")
+        );
+    }
+    kml.push_str("</Document>\n</kml>\n");
+
+    kml
+}
+
+/// Returns points along the great circle between two airports, so viewers
+/// drawing straight lines still show the route flown.
+///
+/// # Arguments
+///
+/// * `from` - The departure.
+/// * `to` - The destination.
+pub fn great_circle_points(from: &Airport, to: &Airport) -> Vec<geo::Point> {
+    let start = geo::Point::new(from.Longtitude, from.Latitude);
+    let end = geo::Point::new(to.Longtitude, to.Latitude);
+    let distance_nm = crate::modules::routes::airport_distance_nm(from, to);
+    let segments = ((distance_nm / SEGMENT_NM).ceil() as usize).clamp(1, MAX_SEGMENTS);
+
+    (0..=segments)
+        .map(|segment| {
+            Haversine::point_at_ratio_between(start, end, segment as f64 / segments as f64)
+        })
+        .collect()
+}
+
+/// Escapes the characters with a special meaning in XML and HTML.
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
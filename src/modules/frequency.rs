@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{Integer, Text};
+
+use crate::errors::ValidationError;
+use crate::models::{Airport, Frequency};
+use crate::modules::data_source::table_exists;
+use crate::traits::FrequencyOperations;
+use crate::DatabaseConnections;
+use crate::DatabasePool;
+
+/// The optional table of the airport database holding radio frequencies.
+pub const FREQUENCY_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS `frequencies`(
+	`airport_id` INTEGER NOT NULL,
+	`type` TEXT NOT NULL,
+	`description` TEXT NOT NULL,
+	`frequency_khz` INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS `frequencies_airport_id` ON `frequencies` (`airport_id`);
+";
+
+/// A frequency read from an import file, not yet linked to an airport.
+pub struct FrequencyRecord {
+    /// The ICAO code of the airport.
+    pub icao: String,
+    /// The kind of station, such as `TWR`, `GND` or `ATIS`.
+    pub kind: String,
+    /// The name of the station.
+    pub description: String,
+    /// The frequency in kHz.
+    pub frequency_khz: i32,
+}
+
+impl FrequencyOperations for DatabaseConnections {
+    fn get_frequencies(&mut self, airport: &Airport) -> Result<Vec<Frequency>, Error> {
+        get_frequencies(&mut self.airport_connection, airport.ID)
+    }
+
+    fn import_frequencies(&mut self, records: &[FrequencyRecord]) -> Result<usize, Error> {
+        import_frequencies(&mut self.airport_connection, records)
+    }
+}
+
+impl FrequencyOperations for DatabasePool {
+    fn get_frequencies(&mut self, airport: &Airport) -> Result<Vec<Frequency>, Error> {
+        get_frequencies(&mut self.airport_pool.get().unwrap(), airport.ID)
+    }
+
+    fn import_frequencies(&mut self, records: &[FrequencyRecord]) -> Result<usize, Error> {
+        import_frequencies(&mut self.airport_pool.get().unwrap(), records)
+    }
+}
+
+/// Returns the frequencies of an airport, ordered by kind and frequency.
+///
+/// # Arguments
+///
+/// * `db` - An airport database, which may lack the frequency table.
+/// * `airport_id` - The ID of the airport.
+pub fn get_frequencies(
+    db: &mut SqliteConnection,
+    airport_id: i32,
+) -> Result<Vec<Frequency>, Error> {
+    if !table_exists(db, "main", "frequencies")? {
+        return Ok(Vec::new());
+    }
+
+    diesel::sql_query(
+        "SELECT airport_id, type AS kind, description, frequency_khz FROM frequencies \
+         WHERE airport_id = ? ORDER BY type, frequency_khz",
+    )
+    .bind::<Integer, _>(airport_id)
+    .load::<Frequency>(db)
+}
+
+/// Returns the frequencies of every airport that has some, keyed by airport ID.
+///
+/// # Arguments
+///
+/// * `db` - An airport database, which may lack the frequency table.
+pub fn airport_frequencies(
+    db: &mut SqliteConnection,
+) -> Result<HashMap<i32, Vec<Frequency>>, Error> {
+    let mut frequencies: HashMap<i32, Vec<Frequency>> = HashMap::new();
+    if !table_exists(db, "main", "frequencies")? {
+        return Ok(frequencies);
+    }
+
+    for frequency in diesel::sql_query(
+        "SELECT airport_id, type AS kind, description, frequency_khz FROM frequencies",
+    )
+    .load::<Frequency>(db)?
+    {
+        frequencies
+            .entry(frequency.airport_id)
+            .or_default()
+            .push(frequency);
+    }
+
+    Ok(frequencies)
+}
+
+/// Inserts a frequency of an airport.
+///
+/// # Arguments
+///
+/// * `db` - A database with the [`FREQUENCY_SCHEMA`] table.
+/// * `airport_id` - The ID of the airport.
+/// * `frequency` - The frequency to insert, whose airport ID is ignored.
+pub fn insert_frequency(
+    db: &mut SqliteConnection,
+    airport_id: i32,
+    frequency: &Frequency,
+) -> Result<(), Error> {
+    diesel::sql_query(
+        "INSERT INTO frequencies (airport_id, type, description, frequency_khz) \
+         VALUES (?, ?, ?, ?)",
+    )
+    .bind::<Integer, _>(airport_id)
+    .bind::<Text, _>(&frequency.kind)
+    .bind::<Text, _>(&frequency.description)
+    .bind::<Integer, _>(frequency.frequency_khz)
+    .execute(db)?;
+
+    Ok(())
+}
+
+/// Replaces the frequencies in the airport database with the imported ones.
+///
+/// # Returns
+///
+/// The number of frequencies whose airport was found.
+fn import_frequencies(
+    db: &mut SqliteConnection,
+    records: &[FrequencyRecord],
+) -> Result<usize, Error> {
+    db.batch_execute(FREQUENCY_SCHEMA)?;
+
+    db.transaction(|db| {
+        diesel::sql_query("DELETE FROM frequencies").execute(db)?;
+
+        let mut imported = 0;
+        for record in records {
+            imported += diesel::sql_query(
+                "INSERT INTO frequencies (airport_id, type, description, frequency_khz) \
+                 SELECT ID, ?, ?, ? FROM Airports WHERE ICAO = ? ORDER BY ID LIMIT 1",
+            )
+            .bind::<Text, _>(&record.kind)
+            .bind::<Text, _>(&record.description)
+            .bind::<Integer, _>(record.frequency_khz)
+            .bind::<Text, _>(record.icao.to_uppercase())
+            .execute(db)?;
+        }
+
+        Ok(imported)
+    })
+}
+
+/// Formats a frequency in kHz as MHz with three decimals, as tuned in the cockpit.
+///
+/// # Arguments
+///
+/// * `frequency_khz` - The frequency in kHz.
+pub fn format_frequency(frequency_khz: i32) -> String {
+    format!("{}.{:03}", frequency_khz / 1000, frequency_khz % 1000)
+}
+
+/// Parses an airport frequency file in the CSV format of OurAirports.
+///
+/// The columns are found by name: `airport_ident`, `type`, `description` and
+/// `frequency_mhz`. Other columns are ignored.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the file.
+pub fn parse_frequency_csv(contents: &str) -> Result<Vec<FrequencyRecord>, ValidationError> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().unwrap_or_default());
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field == name)
+            .ok_or_else(|| {
+                ValidationError::InvalidData(format!("The frequency file has no {} column", name))
+            })
+    };
+    let icao_column = column("airport_ident")?;
+    let kind_column = column("type")?;
+    let description_column = column("description")?;
+    let frequency_column = column("frequency_mhz")?;
+
+    let mut records = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let field = |column: usize| fields.get(column).map(String::as_str).unwrap_or_default();
+        let frequency = field(frequency_column);
+        let frequency_mhz: f64 = frequency.parse().map_err(|_| {
+            ValidationError::InvalidData(format!(
+                "Invalid frequency {} on line {}",
+                frequency,
+                index + 2
+            ))
+        })?;
+
+        records.push(FrequencyRecord {
+            icao: field(icao_column).to_string(),
+            kind: field(kind_column).to_string(),
+            description: field(description_column).to_string(),
+            frequency_khz: (frequency_mhz * 1000.0).round() as i32,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Splits a CSV line into its fields, removing the quotes around quoted fields.
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // A doubled quote inside a quoted field is a literal quote.
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
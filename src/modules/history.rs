@@ -1,12 +1,19 @@
 use diesel::prelude::*;
 use diesel::result::Error;
+use diesel::sql_types::{BigInt, Nullable, Text};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
 use crate::models::*;
+use crate::modules::data_source::table_exists;
+use crate::modules::history_export::{export_history, HistoryExportFormat};
 use crate::schema::history::dsl::*;
 use crate::traits::HistoryOperations;
 use crate::DatabaseConnections;
 use crate::DatabasePool;
 
+pub mod import;
+
 #[derive(Insertable)]
 #[diesel(table_name = crate::schema::history)]
 struct HistoryForm<'a> {
@@ -14,21 +21,264 @@ struct HistoryForm<'a> {
     departure_icao: &'a str,
     arrival_icao: &'a str,
     aircraft: i32,
+    notes: Option<&'a str>,
+    planned_arrival_icao: Option<&'a str>,
+    airline: Option<&'a str>,
 }
 
 fn create_history<'a>(
     departure: &'a Airport,
     arrival: &'a Airport,
     aircraft_record: &'a Aircraft,
+    date_string: String,
+    details: FlightDetails<'a>,
 ) -> HistoryForm<'a> {
-    let date_string = chrono::Local::now().format("%Y-%m-%d").to_string();
-
     HistoryForm {
         date: date_string,
         departure_icao: &departure.ICAO,
         arrival_icao: &arrival.ICAO,
         aircraft: aircraft_record.id,
+        notes: details.notes,
+        planned_arrival_icao: details.planned_arrival.map(|airport| airport.ICAO.as_str()),
+        airline: details.airline,
+    }
+}
+
+/// Returns the first history entry of the aircraft flying between the airports on the date.
+fn find_history_entry(
+    conn: &mut SqliteConnection,
+    departure: &Airport,
+    arrival: &Airport,
+    aircraft_record: &Aircraft,
+    flown_date: &str,
+) -> Result<Option<History>, Error> {
+    history
+        .filter(departure_icao.eq(&departure.ICAO))
+        .filter(arrival_icao.eq(&arrival.ICAO))
+        .filter(aircraft.eq(aircraft_record.id))
+        .filter(date.eq(flown_date))
+        .first(conn)
+        .optional()
+}
+
+/// Inserts a history entry and counts the flight for its aircraft.
+fn insert_history(conn: &mut SqliteConnection, record: &HistoryForm) -> Result<(), Error> {
+    use crate::schema::aircraft::dsl as aircraft_dsl;
+
+    conn.transaction(|conn| {
+        diesel::insert_into(history).values(record).execute(conn)?;
+
+        let flown_aircraft = aircraft_dsl::aircraft.find(record.aircraft);
+        diesel::update(flown_aircraft)
+            .set(aircraft_dsl::flown.eq(aircraft_dsl::flown + 1))
+            .execute(conn)?;
+        // A flight logged after the fact does not replace a later date.
+        diesel::update(
+            flown_aircraft.filter(
+                aircraft_dsl::date_flown
+                    .is_null()
+                    .or(aircraft_dsl::date_flown.lt(&record.date)),
+            ),
+        )
+        .set(aircraft_dsl::date_flown.eq(&record.date))
+        .execute(conn)?;
+
+        Ok(())
+    })
+}
+
+/// The outcome of merging the history of another aircraft database.
+pub struct HistoryMergeSummary {
+    /// The number of flights added.
+    pub added: usize,
+    /// The number of flights skipped because they were already in the history.
+    pub duplicates: usize,
+    /// The number of flights skipped because their aircraft is not in this database.
+    pub unmatched: usize,
+    /// The manufacturer and variant of the aircraft not in this database.
+    pub unknown_aircraft: BTreeSet<String>,
+}
+
+/// A flight of the other database, with its aircraft named instead of numbered.
+///
+/// The fields are named apart from the history columns, which are imported above.
+#[derive(QueryableByName)]
+struct OtherFlight {
+    #[diesel(sql_type = Text)]
+    departure: String,
+    #[diesel(sql_type = Text)]
+    arrival: String,
+    #[diesel(sql_type = Text)]
+    flown_on: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    manufacturer: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    variant: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    note: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    planned_arrival: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    airline_name: Option<String>,
+}
+
+#[derive(QueryableByName)]
+struct ColumnCount {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Returns whether a table of an attached database has a column.
+fn column_exists(
+    conn: &mut SqliteConnection,
+    schema: &str,
+    table: &str,
+    column: &str,
+) -> Result<bool, Error> {
+    let count =
+        diesel::sql_query("SELECT COUNT(*) AS count FROM pragma_table_info(?, ?) WHERE name = ?")
+            .bind::<Text, _>(table)
+            .bind::<Text, _>(schema)
+            .bind::<Text, _>(column)
+            .get_result::<ColumnCount>(conn)?;
+
+    Ok(count.count > 0)
+}
+
+/// Adds the flights of another aircraft database to the history.
+///
+/// Aircraft are matched by manufacturer and variant, as the IDs differ between
+/// databases. A flight of the same aircraft between the same airports on the
+/// same date as one already in the history is skipped.
+///
+/// # Arguments
+///
+/// * `conn` - The aircraft database to add the flights to.
+/// * `other` - The aircraft database to read the flights from. It is not changed.
+fn merge_history(conn: &mut SqliteConnection, other: &Path) -> Result<HistoryMergeSummary, Error> {
+    diesel::sql_query(format!(
+        "ATTACH DATABASE '{}' AS other",
+        other.to_string_lossy().replace('\'', "''")
+    ))
+    .execute(conn)?;
+
+    let summary = merge_attached_history(conn);
+    // Pooled connections are reused, so the other database must not stay attached.
+    diesel::sql_query("DETACH DATABASE other").execute(conn)?;
+
+    summary
+}
+
+fn merge_attached_history(conn: &mut SqliteConnection) -> Result<HistoryMergeSummary, Error> {
+    let mut summary = HistoryMergeSummary {
+        added: 0,
+        duplicates: 0,
+        unmatched: 0,
+        unknown_aircraft: BTreeSet::new(),
+    };
+    if !table_exists(conn, "other", "history")? {
+        return Ok(summary);
+    }
+
+    // Older databases lack the columns added since.
+    let mut optional_columns = Vec::new();
+    for (column, alias) in [
+        ("notes", "note"),
+        ("planned_arrival_icao", "planned_arrival"),
+        ("airline", "airline_name"),
+    ] {
+        if column_exists(conn, "other", "history", column)? {
+            optional_columns.push(format!("h.{column} AS {alias}"));
+        } else {
+            optional_columns.push(format!("NULL AS {alias}"));
+        }
     }
+    let flights = diesel::sql_query(format!(
+        "SELECT h.departure_icao AS departure, h.arrival_icao AS arrival, h.date AS flown_on, \
+         a.manufacturer, a.variant, {} \
+         FROM other.history h LEFT JOIN other.aircraft a ON a.id = h.aircraft ORDER BY h.id",
+        optional_columns.join(", ")
+    ))
+    .load::<OtherFlight>(conn)?;
+
+    let aircraft_ids: HashMap<(String, String), i32> = crate::schema::aircraft::table
+        .load::<Aircraft>(conn)?
+        .into_iter()
+        .map(|record| {
+            (
+                (
+                    record.manufacturer.to_lowercase(),
+                    record.variant.to_lowercase(),
+                ),
+                record.id,
+            )
+        })
+        .collect();
+    let mut flown: HashSet<(i32, String, String, String)> = history
+        .load::<History>(conn)?
+        .into_iter()
+        .map(|record| {
+            (
+                record.aircraft,
+                record.departure_icao,
+                record.arrival_icao,
+                record.date,
+            )
+        })
+        .collect();
+
+    conn.transaction(|conn| {
+        for flight in &flights {
+            let (Some(manufacturer), Some(variant)) = (&flight.manufacturer, &flight.variant)
+            else {
+                summary.unmatched += 1;
+                continue;
+            };
+            let Some(&aircraft_id) =
+                aircraft_ids.get(&(manufacturer.to_lowercase(), variant.to_lowercase()))
+            else {
+                summary.unmatched += 1;
+                summary
+                    .unknown_aircraft
+                    .insert(format!("{} {}", manufacturer, variant));
+                continue;
+            };
+
+            let key = (
+                aircraft_id,
+                flight.departure.clone(),
+                flight.arrival.clone(),
+                flight.flown_on.clone(),
+            );
+            if !flown.insert(key) {
+                summary.duplicates += 1;
+                continue;
+            }
+
+            insert_history(
+                conn,
+                &HistoryForm {
+                    date: flight.flown_on.clone(),
+                    departure_icao: &flight.departure,
+                    arrival_icao: &flight.arrival,
+                    aircraft: aircraft_id,
+                    notes: flight.note.as_deref(),
+                    planned_arrival_icao: flight.planned_arrival.as_deref(),
+                    airline: flight.airline_name.as_deref(),
+                },
+            )?;
+            summary.added += 1;
+        }
+
+        Ok::<_, Error>(())
+    })?;
+
+    Ok(summary)
+}
+
+/// Returns today's date in the format stored in the history table.
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
 }
 
 impl HistoryOperations for DatabaseConnections {
@@ -38,13 +288,32 @@ impl HistoryOperations for DatabaseConnections {
         arrival: &Airport,
         aircraft_record: &Aircraft,
     ) -> Result<(), Error> {
-        let record = create_history(departure, arrival, aircraft_record);
+        self.add_to_history_on(
+            departure,
+            arrival,
+            aircraft_record,
+            &today(),
+            FlightDetails::default(),
+        )
+    }
 
-        diesel::insert_into(history)
-            .values(&record)
-            .execute(&mut self.aircraft_connection)?;
+    fn add_to_history_on(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        flown_date: &str,
+        details: FlightDetails,
+    ) -> Result<(), Error> {
+        let record = create_history(
+            departure,
+            arrival,
+            aircraft_record,
+            flown_date.to_string(),
+            details,
+        );
 
-        Ok(())
+        insert_history(&mut self.aircraft_connection, &record)
     }
 
     fn get_history(&mut self) -> Result<Vec<History>, Error> {
@@ -52,6 +321,34 @@ impl HistoryOperations for DatabaseConnections {
 
         Ok(records)
     }
+
+    fn find_history_entry(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        flown_date: &str,
+    ) -> Result<Option<History>, Error> {
+        find_history_entry(
+            &mut self.aircraft_connection,
+            departure,
+            arrival,
+            aircraft_record,
+            flown_date,
+        )
+    }
+
+    fn merge_history(&mut self, other: &Path) -> Result<HistoryMergeSummary, Error> {
+        merge_history(&mut self.aircraft_connection, other)
+    }
+
+    fn export_history(&mut self, format: HistoryExportFormat) -> Result<String, Error> {
+        export_history(
+            &mut self.aircraft_connection,
+            &mut self.airport_connection,
+            format,
+        )
+    }
 }
 
 impl HistoryOperations for DatabasePool {
@@ -61,12 +358,33 @@ impl HistoryOperations for DatabasePool {
         arrival: &Airport,
         aircraft_record: &Aircraft,
     ) -> Result<(), Error> {
-        let conn = &mut self.aircraft_pool.get().unwrap();
-        let record = create_history(departure, arrival, aircraft_record);
+        self.add_to_history_on(
+            departure,
+            arrival,
+            aircraft_record,
+            &today(),
+            FlightDetails::default(),
+        )
+    }
 
-        diesel::insert_into(history).values(&record).execute(conn)?;
+    fn add_to_history_on(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        flown_date: &str,
+        details: FlightDetails,
+    ) -> Result<(), Error> {
+        let conn = &mut self.aircraft_pool.get().unwrap();
+        let record = create_history(
+            departure,
+            arrival,
+            aircraft_record,
+            flown_date.to_string(),
+            details,
+        );
 
-        Ok(())
+        insert_history(conn, &record)
     }
 
     fn get_history(&mut self) -> Result<Vec<History>, Error> {
@@ -75,4 +393,32 @@ impl HistoryOperations for DatabasePool {
 
         Ok(records)
     }
+
+    fn find_history_entry(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        flown_date: &str,
+    ) -> Result<Option<History>, Error> {
+        find_history_entry(
+            &mut self.aircraft_pool.get().unwrap(),
+            departure,
+            arrival,
+            aircraft_record,
+            flown_date,
+        )
+    }
+
+    fn merge_history(&mut self, other: &Path) -> Result<HistoryMergeSummary, Error> {
+        merge_history(&mut self.aircraft_pool.get().unwrap(), other)
+    }
+
+    fn export_history(&mut self, format: HistoryExportFormat) -> Result<String, Error> {
+        export_history(
+            &mut self.aircraft_pool.get().unwrap(),
+            &mut self.airport_pool.get().unwrap(),
+            format,
+        )
+    }
 }
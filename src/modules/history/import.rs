@@ -0,0 +1,301 @@
+use chrono::NaiveDate;
+use diesel::result::Error;
+use std::collections::{HashMap, HashSet};
+
+use crate::errors::ValidationError;
+use crate::models::{Aircraft, Airport, FlightDetails};
+use crate::modules::airport::airport_by_code;
+use crate::modules::frequency::split_csv_line;
+use crate::modules::validation::DATE_FORMAT;
+use crate::traits::{AirportOperations, HistoryOperations};
+
+/// A logbook tool whose CSV export can be imported into the history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LogbookFormat {
+    #[default]
+    Volanta,
+    SimToolkitPro,
+}
+
+/// The names a column may have in an export, matched case-insensitively.
+struct LogbookColumns {
+    date: &'static [&'static str],
+    departure: &'static [&'static str],
+    arrival: &'static [&'static str],
+    aircraft_type: &'static [&'static str],
+}
+
+impl LogbookFormat {
+    pub const ALL: [LogbookFormat; 2] = [LogbookFormat::Volanta, LogbookFormat::SimToolkitPro];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LogbookFormat::Volanta => "Volanta",
+            LogbookFormat::SimToolkitPro => "SimToolkitPro",
+        }
+    }
+
+    /// Returns the column names of the export of the tool.
+    fn columns(&self) -> LogbookColumns {
+        match self {
+            LogbookFormat::Volanta => LogbookColumns {
+                date: &["Date", "Departure Time", "Start Time"],
+                departure: &["Origin", "Origin ICAO", "Departure"],
+                arrival: &["Destination", "Destination ICAO", "Arrival"],
+                aircraft_type: &["Aircraft Type", "Aircraft ICAO", "Aircraft"],
+            },
+            LogbookFormat::SimToolkitPro => LogbookColumns {
+                date: &["Date", "Block Off", "Off Block", "Departure Time"],
+                departure: &["Dep", "Departure", "Departure ICAO"],
+                arrival: &["Arr", "Arrival", "Arrival ICAO"],
+                aircraft_type: &["Aircraft Type", "Aircraft ICAO", "Type", "Aircraft"],
+            },
+        }
+    }
+}
+
+/// A flight read from a logbook export, not yet linked to the database.
+#[derive(Clone, Debug)]
+pub struct LogbookFlight {
+    /// The line of the file the flight was read from.
+    pub line: usize,
+    /// The date of the flight.
+    pub date: NaiveDate,
+    /// The code of the departure airport as written in the export.
+    pub departure: String,
+    /// The code of the arrival airport as written in the export.
+    pub arrival: String,
+    /// The ICAO type code of the aircraft, such as `B738`.
+    pub aircraft_type: String,
+}
+
+/// Parses the CSV export of a logbook tool.
+///
+/// The columns are found by name, trying the names the tool is known to use
+/// in order. Dates may carry a time, which is left out, such as
+/// `2024-05-01T14:30:00Z`. Other columns are ignored.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the file.
+/// * `format` - The tool the file was exported from.
+pub fn parse_logbook_csv(
+    contents: &str,
+    format: LogbookFormat,
+) -> Result<Vec<LogbookFlight>, ValidationError> {
+    let mut lines = contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().map(|(_, line)| line).unwrap_or_default());
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| {
+                header
+                    .iter()
+                    .position(|field| field.trim().eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| {
+                ValidationError::InvalidData(format!(
+                    "The {} export has no {} column",
+                    format.display_name(),
+                    names[0]
+                ))
+            })
+    };
+    let columns = format.columns();
+    let date_column = column(columns.date)?;
+    let departure_column = column(columns.departure)?;
+    let arrival_column = column(columns.arrival)?;
+    let aircraft_column = column(columns.aircraft_type)?;
+
+    let mut flights = Vec::new();
+    for (index, line) in lines {
+        let fields = split_csv_line(line);
+        let field = |column: usize| fields.get(column).map_or("", |field| field.trim());
+        let date_text = field(date_column);
+        let day = date_text.get(..10).unwrap_or(date_text);
+        let date = NaiveDate::parse_from_str(day, DATE_FORMAT).map_err(|_| {
+            ValidationError::InvalidData(format!(
+                "Invalid date {} on line {}",
+                date_text,
+                index + 1
+            ))
+        })?;
+
+        flights.push(LogbookFlight {
+            line: index + 1,
+            date,
+            departure: field(departure_column).to_ascii_uppercase(),
+            arrival: field(arrival_column).to_ascii_uppercase(),
+            aircraft_type: field(aircraft_column).to_ascii_uppercase(),
+        });
+    }
+
+    Ok(flights)
+}
+
+/// Whether a flight of a logbook can be added to the history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImportStatus {
+    /// The flight will be added.
+    Ready,
+    /// The flight is already in the history, or earlier in the file.
+    Duplicate,
+    /// No aircraft in the database has the type of the flight.
+    UnknownAircraft,
+    /// No airport in the database has the code.
+    UnknownAirport(String),
+}
+
+impl ImportStatus {
+    /// Returns the status shown to the user.
+    pub fn describe(&self) -> String {
+        match self {
+            ImportStatus::Ready => "Ready".to_string(),
+            ImportStatus::Duplicate => "Already in the history".to_string(),
+            ImportStatus::UnknownAircraft => "Unknown aircraft type".to_string(),
+            ImportStatus::UnknownAirport(code) => format!("Unknown airport {}", code),
+        }
+    }
+}
+
+/// A flight of a logbook matched against the database.
+#[derive(Clone, Debug)]
+pub struct ImportEntry {
+    /// The flight as read from the logbook.
+    pub flight: LogbookFlight,
+    /// Whether the flight will be added.
+    pub status: ImportStatus,
+    /// The departure, arrival and aircraft, when the flight is ready.
+    matched: Option<(Airport, Airport, Aircraft)>,
+}
+
+/// Matches the flights of a logbook to the airports and aircraft of the
+/// database, without changing it.
+///
+/// Aircraft are matched by ICAO type code. When several aircraft share a
+/// type, the flight is counted for the first. A flight is a duplicate when
+/// the history already has the same aircraft flying between the same
+/// airports on the same date.
+///
+/// # Arguments
+///
+/// * `db` - The database to match against.
+/// * `flights` - The flights read from the logbook.
+pub fn plan_logbook_import<T: HistoryOperations + AirportOperations>(
+    db: &mut T,
+    flights: Vec<LogbookFlight>,
+) -> Result<Vec<ImportEntry>, Error> {
+    let all_aircraft = db.get_all_aircraft()?;
+    let mut flown: HashSet<(i32, String, String, String)> = db
+        .get_history()?
+        .into_iter()
+        .map(|record| {
+            (
+                record.aircraft,
+                record.departure_icao,
+                record.arrival_icao,
+                record.date,
+            )
+        })
+        .collect();
+
+    let mut airports: HashMap<String, Option<Airport>> = HashMap::new();
+    let mut find_airport = |db: &mut T, code: &str| -> Result<Option<Airport>, Error> {
+        if let Some(airport) = airports.get(code) {
+            return Ok(airport.clone());
+        }
+        let airport = match airport_by_code(db, code) {
+            Ok(airport) => Some(airport),
+            Err(Error::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+        airports.insert(code.to_string(), airport.clone());
+        Ok(airport)
+    };
+
+    let mut entries = Vec::with_capacity(flights.len());
+    for flight in flights {
+        let departure = find_airport(db, &flight.departure)?;
+        let arrival = find_airport(db, &flight.arrival)?;
+        let aircraft = all_aircraft
+            .iter()
+            .filter(|aircraft| {
+                aircraft
+                    .icao_code
+                    .eq_ignore_ascii_case(&flight.aircraft_type)
+            })
+            .min_by_key(|aircraft| aircraft.id);
+
+        let (status, matched) = match (departure, arrival, aircraft) {
+            (None, _, _) => (ImportStatus::UnknownAirport(flight.departure.clone()), None),
+            (_, None, _) => (ImportStatus::UnknownAirport(flight.arrival.clone()), None),
+            (_, _, None) => (ImportStatus::UnknownAircraft, None),
+            (Some(departure), Some(arrival), Some(aircraft)) => {
+                let key = (
+                    aircraft.id,
+                    departure.ICAO.clone(),
+                    arrival.ICAO.clone(),
+                    flight.date.format(DATE_FORMAT).to_string(),
+                );
+                if flown.insert(key) {
+                    (
+                        ImportStatus::Ready,
+                        Some((departure, arrival, aircraft.clone())),
+                    )
+                } else {
+                    (ImportStatus::Duplicate, None)
+                }
+            }
+        };
+        entries.push(ImportEntry {
+            flight,
+            status,
+            matched,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Adds the flights of a planned import that are ready to the history.
+///
+/// # Arguments
+///
+/// * `db` - The database to add the flights to.
+/// * `entries` - The flights matched by [`plan_logbook_import`].
+/// * `format` - The tool the flights were exported from, noted with each flight.
+///
+/// # Returns
+///
+/// The number of flights added.
+pub fn import_logbook<T: HistoryOperations>(
+    db: &mut T,
+    entries: &[ImportEntry],
+    format: LogbookFormat,
+) -> Result<usize, Error> {
+    let notes = format!("Imported from {}", format.display_name());
+    let mut imported = 0;
+    for entry in entries {
+        let Some((departure, arrival, aircraft)) = &entry.matched else {
+            continue;
+        };
+        db.add_to_history_on(
+            departure,
+            arrival,
+            aircraft,
+            &entry.flight.date.format(DATE_FORMAT).to_string(),
+            FlightDetails {
+                notes: Some(&notes),
+                ..FlightDetails::default()
+            },
+        )?;
+        imported += 1;
+    }
+
+    Ok(imported)
+}
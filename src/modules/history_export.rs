@@ -0,0 +1,255 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::models::{Aircraft, AirportAlias, History};
+
+/// A file format the flight history can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum HistoryExportFormat {
+    /// Comma separated values, read by spreadsheets.
+    #[default]
+    Csv,
+    /// A JSON array with an object per flight.
+    Json,
+}
+
+impl HistoryExportFormat {
+    pub const ALL: [HistoryExportFormat; 2] = [HistoryExportFormat::Csv, HistoryExportFormat::Json];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HistoryExportFormat::Csv => "CSV",
+            HistoryExportFormat::Json => "JSON",
+        }
+    }
+
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HistoryExportFormat::Csv => "csv",
+            HistoryExportFormat::Json => "json",
+        }
+    }
+
+    /// Returns the format matching the extension of a file name.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to export to.
+    pub fn from_path(path: &Path) -> Option<HistoryExportFormat> {
+        let extension = path.extension()?.to_str()?;
+        HistoryExportFormat::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+}
+
+/// The columns of a CSV export, in order.
+const CSV_HEADER: [&str; 12] = [
+    "id",
+    "date",
+    "departure_icao",
+    "departure_name",
+    "arrival_icao",
+    "arrival_name",
+    "planned_arrival_icao",
+    "planned_arrival_name",
+    "aircraft_id",
+    "aircraft",
+    "airline",
+    "notes",
+];
+
+/// A flight of the history with the names of its aircraft and airports.
+struct ExportedFlight<'a> {
+    record: &'a History,
+    aircraft: String,
+    departure_name: Option<&'a str>,
+    arrival_name: Option<&'a str>,
+    planned_arrival_name: Option<&'a str>,
+}
+
+impl ExportedFlight<'_> {
+    /// Returns the values of the flight in the order of `CSV_HEADER`,
+    /// `None` for missing values.
+    fn fields(&self) -> [Option<String>; 12] {
+        let record = self.record;
+        [
+            Some(record.id.to_string()),
+            Some(record.date.clone()),
+            Some(record.departure_icao.clone()),
+            self.departure_name.map(str::to_string),
+            Some(record.arrival_icao.clone()),
+            self.arrival_name.map(str::to_string),
+            record.planned_arrival_icao.clone(),
+            self.planned_arrival_name.map(str::to_string),
+            Some(record.aircraft.to_string()),
+            Some(self.aircraft.clone()),
+            record.airline.clone(),
+            record.notes.clone(),
+        ]
+    }
+}
+
+/// Loads the history, oldest flight first, and serializes it with the names
+/// of the aircraft and airports.
+///
+/// Airports recorded under a former ICAO code are named after the airport
+/// the code now belongs to.
+///
+/// # Arguments
+///
+/// * `aircraft_conn` - The aircraft database, which holds the history.
+/// * `airport_conn` - The airport database.
+/// * `format` - The format of the document.
+pub(crate) fn export_history(
+    aircraft_conn: &mut SqliteConnection,
+    airport_conn: &mut SqliteConnection,
+    format: HistoryExportFormat,
+) -> Result<String, Error> {
+    use crate::schema::aircraft::dsl::aircraft;
+    use crate::schema::airport_icao_aliases::dsl::airport_icao_aliases;
+    use crate::schema::history::dsl::{date, history, id};
+    use crate::schema::Airports::dsl::{Airports, Name, ICAO, ID};
+
+    let records: Vec<History> = history.order((date, id)).load(aircraft_conn)?;
+    let all_aircraft: Vec<Aircraft> = aircraft.load(aircraft_conn)?;
+    let aliases: HashMap<String, String> = airport_icao_aliases
+        .load::<AirportAlias>(aircraft_conn)?
+        .into_iter()
+        .map(|record| (record.alias, record.icao))
+        .collect();
+
+    let codes: BTreeSet<&str> = records
+        .iter()
+        .flat_map(|record| {
+            [
+                Some(record.departure_icao.as_str()),
+                Some(record.arrival_icao.as_str()),
+                record.planned_arrival_icao.as_deref(),
+            ]
+        })
+        .flatten()
+        .flat_map(|code| [Some(code), aliases.get(code).map(String::as_str)])
+        .flatten()
+        .collect();
+    // Like the lookups by code, the first airport with a code names it.
+    let mut names: HashMap<String, String> = HashMap::new();
+    for (code, name) in Airports
+        .filter(ICAO.eq_any(&codes))
+        .order(ID)
+        .select((ICAO, Name))
+        .load::<(String, String)>(airport_conn)?
+    {
+        names.entry(code).or_insert(name);
+    }
+
+    let airport_name = |code: &str| {
+        names
+            .get(code)
+            .or_else(|| aliases.get(code).and_then(|current| names.get(current)))
+            .map(String::as_str)
+    };
+    let flights: Vec<ExportedFlight> = records
+        .iter()
+        .map(|record| ExportedFlight {
+            record,
+            aircraft: all_aircraft
+                .iter()
+                .find(|a| a.id == record.aircraft)
+                .map_or_else(
+                    || format!("Unknown aircraft ({})", record.aircraft),
+                    |a| format!("{} {}", a.manufacturer, a.variant),
+                ),
+            departure_name: airport_name(&record.departure_icao),
+            arrival_name: airport_name(&record.arrival_icao),
+            planned_arrival_name: record
+                .planned_arrival_icao
+                .as_deref()
+                .and_then(airport_name),
+        })
+        .collect();
+
+    Ok(match format {
+        HistoryExportFormat::Csv => flights_to_csv(&flights),
+        HistoryExportFormat::Json => flights_to_json(&flights),
+    })
+}
+
+fn flights_to_csv(flights: &[ExportedFlight]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push('\n');
+    for flight in flights {
+        let row: Vec<String> = flight
+            .fields()
+            .iter()
+            .map(|field| csv_field(field.as_deref().unwrap_or_default()))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn flights_to_json(flights: &[ExportedFlight]) -> String {
+    let entries: Vec<String> = flights
+        .iter()
+        .map(|flight| {
+            let members: Vec<String> = CSV_HEADER
+                .iter()
+                .zip(flight.fields())
+                .map(|(name, field)| {
+                    let value = match (*name, field) {
+                        (_, None) => "null".to_string(),
+                        ("id" | "aircraft_id", Some(number)) => number,
+                        (_, Some(text)) => json_string(&text),
+                    };
+                    format!("\"{}\": {}", name, value)
+                })
+                .collect();
+            format!("  {{{}}}", members.join(", "))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", entries.join(",\n"))
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Quotes and escapes a string for JSON output.
+///
+/// # Arguments
+///
+/// * `value` - The string to quote.
+pub fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,191 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::Text;
+
+use crate::errors::ValidationError;
+use crate::models::AirportIataCode;
+use crate::modules::data_source::table_exists;
+use crate::modules::frequency::split_csv_line;
+
+/// The optional table of the airport database holding IATA codes.
+pub const IATA_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS `iata_codes`(
+	`icao` TEXT NOT NULL PRIMARY KEY,
+	`iata` TEXT NOT NULL
+);
+";
+
+/// Returns the IATA codes of the airports that have one.
+///
+/// # Arguments
+///
+/// * `db` - An airport database, which may lack the IATA code table.
+pub fn get_iata_codes(db: &mut SqliteConnection) -> Result<Vec<AirportIataCode>, Error> {
+    if !table_exists(db, "main", "iata_codes")? {
+        return Ok(Vec::new());
+    }
+
+    diesel::sql_query("SELECT icao, iata FROM iata_codes").load::<AirportIataCode>(db)
+}
+
+/// Inserts the IATA code of an airport, keeping an existing code.
+///
+/// # Arguments
+///
+/// * `db` - A database with the [`IATA_SCHEMA`] table.
+/// * `code` - The code to insert.
+pub fn insert_iata_code(db: &mut SqliteConnection, code: &AirportIataCode) -> Result<(), Error> {
+    diesel::sql_query("INSERT OR IGNORE INTO iata_codes (icao, iata) VALUES (?, ?)")
+        .bind::<Text, _>(&code.icao)
+        .bind::<Text, _>(&code.iata)
+        .execute(db)?;
+
+    Ok(())
+}
+
+/// Replaces the IATA codes in the airport database with the imported ones.
+///
+/// # Returns
+///
+/// The number of codes whose airport was found.
+pub(crate) fn import_iata_codes(
+    db: &mut SqliteConnection,
+    records: &[AirportIataCode],
+) -> Result<usize, Error> {
+    db.batch_execute(IATA_SCHEMA)?;
+
+    db.transaction(|db| {
+        diesel::sql_query("DELETE FROM iata_codes").execute(db)?;
+
+        let mut imported = 0;
+        for record in records {
+            imported += diesel::sql_query(
+                "INSERT OR IGNORE INTO iata_codes (icao, iata) \
+                 SELECT ICAO, ? FROM Airports WHERE ICAO = ? LIMIT 1",
+            )
+            .bind::<Text, _>(record.iata.to_uppercase())
+            .bind::<Text, _>(record.icao.to_uppercase())
+            .execute(db)?;
+        }
+
+        Ok(imported)
+    })
+}
+
+/// Parses an airport file in the CSV format of OurAirports into the IATA codes
+/// of its airports.
+///
+/// The columns are found by name: `ident` and `iata_code`, and `icao_code`
+/// where the file has it, which is preferred over `ident`. Airports without an
+/// IATA code are left out, and other columns are ignored.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the file.
+pub fn parse_airport_csv(contents: &str) -> Result<Vec<AirportIataCode>, ValidationError> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().unwrap_or_default());
+    let position = |name: &str| header.iter().position(|field| field == name);
+    let column = |name: &str| {
+        position(name).ok_or_else(|| {
+            ValidationError::InvalidData(format!("The airport file has no {} column", name))
+        })
+    };
+    let ident_column = column("ident")?;
+    let iata_column = column("iata_code")?;
+    let icao_column = position("icao_code");
+
+    let mut records = Vec::new();
+    for line in lines {
+        let fields = split_csv_line(line);
+        let field = |column: usize| fields.get(column).map_or("", |field| field.trim());
+        let iata = field(iata_column);
+        if iata.is_empty() {
+            continue;
+        }
+        let icao = icao_column
+            .map(field)
+            .filter(|icao| !icao.is_empty())
+            .unwrap_or_else(|| field(ident_column));
+
+        records.push(AirportIataCode {
+            icao: icao.to_string(),
+            iata: iata.to_string(),
+        });
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Airport;
+    use crate::modules::airport::airport_by_code;
+    use crate::modules::trim::AIRPORT_SCHEMA;
+    use crate::traits::AirportOperations;
+    use crate::DatabaseConnections;
+
+    const AIRPORTS_CSV: &str = "\
+\"id\",\"ident\",\"type\",\"name\",\"icao_code\",\"iata_code\"
+2513,\"EHAM\",\"large_airport\",\"Amsterdam Airport Schiphol\",\"EHAM\",\"AMS\"
+2434,\"EGLL\",\"large_airport\",\"London Heathrow Airport\",\"EGLL\",\"LHR\"
+3632,\"KJFK\",\"large_airport\",\"John F Kennedy International Airport\",\"\",\"JFK\"
+2520,\"EHLE\",\"medium_airport\",\"Lelystad Airport\",\"EHLE\",\"\"
+";
+
+    #[test]
+    fn parses_the_codes_of_airports_that_have_one() {
+        let codes = parse_airport_csv(AIRPORTS_CSV).unwrap();
+        let codes: Vec<(&str, &str)> = codes
+            .iter()
+            .map(|code| (code.icao.as_str(), code.iata.as_str()))
+            .collect();
+
+        assert_eq!(codes, [("EHAM", "AMS"), ("EGLL", "LHR"), ("KJFK", "JFK")]);
+        assert!(parse_airport_csv("\"id\",\"ident\"\n1,\"EHAM\"").is_err());
+    }
+
+    #[test]
+    fn imported_codes_find_airports() {
+        let path = |name: &str| {
+            let path = std::env::temp_dir().join(format!(
+                "flight-planner-iata-{}-{}.db3",
+                std::process::id(),
+                name
+            ));
+            let _ = std::fs::remove_file(&path);
+            path.to_string_lossy().to_string()
+        };
+        let (aircraft_db, airport_db) = (path("aircraft"), path("airports"));
+
+        let mut db = DatabaseConnections::open(&aircraft_db, &airport_db).unwrap();
+        db.run_migrations().unwrap();
+        db.airport_connection.batch_execute(AIRPORT_SCHEMA).unwrap();
+        for (id, icao) in [(1, "EHAM"), (2, "KJFK")] {
+            diesel::insert_into(crate::schema::Airports::table)
+                .values(Airport {
+                    ID: id,
+                    ICAO: icao.to_string(),
+                    ..Default::default()
+                })
+                .execute(&mut db.airport_connection)
+                .unwrap();
+        }
+        assert!(db.get_iata_codes().unwrap().is_empty());
+
+        let imported = db
+            .import_iata_codes(&parse_airport_csv(AIRPORTS_CSV).unwrap())
+            .unwrap();
+
+        // Heathrow is not in the database.
+        assert_eq!(imported, 2);
+        assert_eq!(airport_by_code(&mut db, "jfk").unwrap().ICAO, "KJFK");
+        assert_eq!(airport_by_code(&mut db, "AMS").unwrap().ICAO, "EHAM");
+        assert!(matches!(
+            airport_by_code(&mut db, "LHR"),
+            Err(Error::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::models::Aircraft;
+use crate::modules::routes::{GeoArea, RouteConstraints};
+
+/// Europe west of the Urals.
+const EUROPE: GeoArea = GeoArea {
+    min_latitude: 36.0,
+    max_latitude: 62.0,
+    min_longitude: -10.0,
+    max_longitude: 30.0,
+};
+/// The east and centre of the United States and Canada.
+const NORTH_AMERICA_EAST: GeoArea = GeoArea {
+    min_latitude: 25.0,
+    max_latitude: 50.0,
+    min_longitude: -100.0,
+    max_longitude: -60.0,
+};
+/// The west coast of the United States and Canada.
+const NORTH_AMERICA_WEST: GeoArea = GeoArea {
+    min_latitude: 32.0,
+    max_latitude: 50.0,
+    min_longitude: -125.0,
+    max_longitude: -115.0,
+};
+/// China, Korea and Japan.
+const EAST_ASIA: GeoArea = GeoArea {
+    min_latitude: 20.0,
+    max_latitude: 45.0,
+    min_longitude: 105.0,
+    max_longitude: 146.0,
+};
+
+/// A curated kind of long haul flight between two regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LongHaulMode {
+    Transatlantic,
+    Transpacific,
+    Polar,
+}
+
+impl LongHaulMode {
+    /// Returns the name of the mode.
+    pub fn label(&self) -> &'static str {
+        match self {
+            LongHaulMode::Transatlantic => "Transatlantic",
+            LongHaulMode::Transpacific => "Transpacific",
+            LongHaulMode::Polar => "Polar",
+        }
+    }
+
+    /// Returns the flights the mode generates.
+    pub fn description(&self) -> &'static str {
+        match self {
+            LongHaulMode::Transatlantic => "Flights between Europe and North America",
+            LongHaulMode::Transpacific => {
+                "Flights between the North American west coast and East Asia"
+            }
+            LongHaulMode::Polar => "Flights between North America and East Asia across the Arctic",
+        }
+    }
+
+    /// Returns the two regions the flights go between.
+    fn regions(&self) -> (GeoArea, GeoArea) {
+        match self {
+            LongHaulMode::Transatlantic => (EUROPE, NORTH_AMERICA_EAST),
+            LongHaulMode::Transpacific => (NORTH_AMERICA_WEST, EAST_ASIA),
+            LongHaulMode::Polar => (NORTH_AMERICA_EAST, EAST_ASIA),
+        }
+    }
+
+    /// Returns the shortest flight of the mode in nautical miles.
+    pub fn min_distance_nm(&self) -> u32 {
+        match self {
+            LongHaulMode::Transatlantic => 2500,
+            LongHaulMode::Transpacific => 4000,
+            LongHaulMode::Polar => 5500,
+        }
+    }
+
+    /// Returns the constraints of the flights in one direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The constraints from the settings, such as the range reserve.
+    /// * `outbound` - Whether to fly from the first region to the second.
+    pub fn constraints(&self, base: RouteConstraints, outbound: bool) -> RouteConstraints {
+        let (first, second) = self.regions();
+        let (departure_area, area) = if outbound {
+            (first, second)
+        } else {
+            (second, first)
+        };
+
+        RouteConstraints {
+            min_distance_nm: base.min_distance_nm.max(self.min_distance_nm()),
+            area: Some(area),
+            departure_area: Some(departure_area),
+            // Flights close to the pole rather than along the Pacific rim.
+            min_path_latitude: (*self == LongHaulMode::Polar).then_some(70.0),
+            // The regions already fix the direction and the continents.
+            direction: None,
+            region: None,
+            ..base
+        }
+    }
+
+    /// Returns the aircraft with enough range for the shortest flight of the mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `all_aircraft` - The aircraft to choose from.
+    /// * `base` - The constraints from the settings, whose range reserve is applied.
+    pub fn suitable_aircraft(
+        &self,
+        all_aircraft: &[Arc<Aircraft>],
+        base: &RouteConstraints,
+    ) -> Vec<Arc<Aircraft>> {
+        all_aircraft
+            .iter()
+            .filter(|aircraft| base.max_distance_nm(aircraft) >= f64::from(self.min_distance_nm()))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Airport, Runway};
+    use crate::modules::routes::RouteGenerator;
+
+    fn airport(id: i32, icao: &str, latitude: f64, longitude: f64) -> Airport {
+        Airport {
+            ID: id,
+            ICAO: icao.to_string(),
+            Latitude: latitude,
+            Longtitude: longitude,
+            ..Default::default()
+        }
+    }
+
+    fn runway(airport: &Airport) -> Runway {
+        Runway {
+            ID: airport.ID,
+            AirportID: airport.ID,
+            Ident: "09".to_string(),
+            TrueHeading: 90.0,
+            Length: 12000,
+            Width: 150,
+            Surface: "ASP".to_string(),
+            Latitude: airport.Latitude,
+            Longtitude: airport.Longtitude,
+            Elevation: 0,
+        }
+    }
+
+    fn long_range_aircraft() -> Arc<Aircraft> {
+        Arc::new(Aircraft {
+            id: 1,
+            manufacturer: "Boeing".to_string(),
+            variant: "777-200LR".to_string(),
+            icao_code: "B77L".to_string(),
+            flown: 0,
+            aircraft_range: 8500,
+            category: "A".to_string(),
+            cruise_speed: 490,
+            date_flown: None,
+            takeoff_distance: None,
+            service_ceiling: None,
+            helicopter: false,
+            amphibian: false,
+        })
+    }
+
+    #[test]
+    fn every_mode_generates_routes_both_ways() {
+        let airports = vec![
+            airport(1, "EGLL", 51.47, -0.45),
+            airport(2, "EDDF", 50.03, 8.56),
+            airport(3, "KJFK", 40.64, -73.78),
+            airport(4, "KORD", 41.97, -87.90),
+            airport(5, "KSFO", 37.62, -122.37),
+            airport(6, "KSEA", 47.45, -122.31),
+            airport(7, "RJAA", 35.76, 140.39),
+            airport(8, "ZBAA", 40.08, 116.58),
+        ];
+        let runways = airports.iter().map(runway).collect();
+        let generator = RouteGenerator::new(airports, runways);
+        let aircraft = vec![long_range_aircraft()];
+
+        for mode in [
+            LongHaulMode::Transatlantic,
+            LongHaulMode::Transpacific,
+            LongHaulMode::Polar,
+        ] {
+            for outbound in [true, false] {
+                let constraints = mode.constraints(RouteConstraints::default(), outbound);
+                let routes = generator.generate_random_routes(&aircraft, 10, &constraints, &[]);
+                assert!(
+                    !routes.is_empty(),
+                    "{} generated no routes (outbound: {outbound})",
+                    mode.label()
+                );
+            }
+        }
+    }
+}
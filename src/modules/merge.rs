@@ -0,0 +1,523 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::Integer;
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::errors::ValidationError;
+use crate::models::{Airport, AirportIataCode, DataSource, Frequency, Runway};
+use crate::modules::airac::AiracCycle;
+use crate::modules::data_source::{airport_source_ids, insert_data_source, DATA_SOURCE_SCHEMA};
+use crate::modules::frequency::{airport_frequencies, insert_frequency, FREQUENCY_SCHEMA};
+use crate::modules::iata::{get_iata_codes, insert_iata_code, IATA_SCHEMA};
+use crate::modules::routes::airport_distance_nm;
+use crate::modules::trim::AIRPORT_SCHEMA;
+use crate::traits::DataSourceOperations;
+
+/// The default distance below which two airports are treated as the same airport.
+pub const DEFAULT_TOLERANCE_NM: f64 = 0.05;
+/// Rows inserted per statement, to stay below the SQLite limit on bound parameters.
+const INSERT_CHUNK_SIZE: usize = 500;
+
+/// Which airport to keep when several sources contain the same airport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the airport from the source given first.
+    First,
+    /// Keep the airport with the most runways, then the one with the longest runway.
+    MostRunways,
+}
+
+impl MergeStrategy {
+    pub const NAMES: [&str; 2] = ["first", "most-runways"];
+
+    /// Parses a strategy from its command line name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - `first` or `most-runways`.
+    pub fn parse(name: &str) -> Result<MergeStrategy, ValidationError> {
+        match name {
+            "first" => Ok(MergeStrategy::First),
+            "most-runways" => Ok(MergeStrategy::MostRunways),
+            _ => Err(ValidationError::InvalidData(format!(
+                "Unknown merge strategy {}, expected one of {}",
+                name,
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+
+    /// Returns whether the candidate should replace the airport kept so far.
+    fn prefers(&self, candidate: &[Runway], kept: &[Runway]) -> bool {
+        let score = |runways: &[Runway]| {
+            (
+                runways.len(),
+                runways.iter().map(|runway| runway.Length).max(),
+            )
+        };
+
+        match self {
+            MergeStrategy::First => false,
+            MergeStrategy::MostRunways => score(candidate) > score(kept),
+        }
+    }
+}
+
+/// An airport found in more than one source, or twice in the same source.
+pub struct Duplicate {
+    /// The ICAO code of the airport that was kept.
+    pub kept: String,
+    /// The ICAO code of the airport that was dropped.
+    pub dropped: String,
+}
+
+/// The outcome of merging airport databases.
+pub struct MergeSummary {
+    /// The number of airports written.
+    pub airports: usize,
+    /// The number of runways written.
+    pub runways: usize,
+    /// The duplicates that were merged.
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// Combines several airport databases into a new one, merging airports that
+/// have the same ICAO code or lie within the tolerance of each other.
+///
+/// Airports and runways get new IDs, as the IDs of different sources overlap.
+///
+/// # Arguments
+///
+/// * `sources` - The airport databases, in order of preference.
+/// * `output` - The database to create, which must not exist yet.
+/// * `strategy` - Which airport to keep of each duplicate.
+/// * `tolerance_nm` - The distance below which two airports are the same airport.
+pub fn merge_airport_databases(
+    sources: &[&Path],
+    output: &Path,
+    strategy: MergeStrategy,
+    tolerance_nm: f64,
+) -> Result<MergeSummary, Error> {
+    if output.exists() {
+        return Err(Error::QueryBuilderError(
+            format!("{} already exists", output.display()).into(),
+        ));
+    }
+
+    let mut kept: Vec<MergedAirport> = Vec::new();
+    let mut by_icao: HashMap<String, usize> = HashMap::new();
+    let mut positions: RTree<GeomWithData<[f64; 2], usize>> = RTree::new();
+    let mut duplicates = Vec::new();
+    let mut source_databases = Vec::with_capacity(sources.len());
+    let mut airac_cycle: Option<AiracCycle> = None;
+
+    for (input, source) in sources.iter().enumerate() {
+        let database = load_airport_database(source)?;
+        source_databases.push((source, database.data_sources));
+        // The merged data is only as current as its oldest part.
+        airac_cycle = match (airac_cycle, database.airac_cycle) {
+            (Some(kept), Some(cycle)) => Some(kept.min(cycle)),
+            (kept, cycle) => kept.or(cycle),
+        };
+
+        for candidate in database.airports {
+            let candidate = MergedAirport { input, ..candidate };
+            let icao = candidate.airport.ICAO.to_uppercase();
+
+            let existing = by_icao
+                .get(&icao)
+                .filter(|_| !icao.is_empty())
+                .copied()
+                .or_else(|| {
+                    let airport = &candidate.airport;
+                    // The index measures in degrees, and a degree of longitude shrinks
+                    // towards the poles.
+                    let radius =
+                        tolerance_nm / 60.0 / airport.Latitude.to_radians().cos().max(0.01);
+                    positions
+                        .locate_within_distance(
+                            [airport.Latitude, airport.Longtitude],
+                            radius * radius,
+                        )
+                        .map(|position| position.data)
+                        .find(|&index| {
+                            airport_distance_nm(&kept[index].airport, airport) <= tolerance_nm
+                        })
+                });
+
+            let index = match existing {
+                Some(index) => {
+                    let replace = strategy.prefers(&candidate.runways, &kept[index].runways);
+                    let (kept_airport, dropped_airport) = if replace {
+                        (&candidate.airport, &kept[index].airport)
+                    } else {
+                        (&kept[index].airport, &candidate.airport)
+                    };
+                    duplicates.push(Duplicate {
+                        kept: kept_airport.ICAO.clone(),
+                        dropped: dropped_airport.ICAO.clone(),
+                    });
+
+                    if !replace {
+                        continue;
+                    }
+                    kept[index] = candidate;
+                    index
+                }
+                None => {
+                    kept.push(candidate);
+                    kept.len() - 1
+                }
+            };
+
+            let airport = &kept[index].airport;
+            if !icao.is_empty() {
+                by_icao.insert(icao, index);
+            }
+            positions.insert(GeomWithData::new(
+                [airport.Latitude, airport.Longtitude],
+                index,
+            ));
+        }
+    }
+
+    let written = write_airport_database(output, kept, &source_databases).and_then(|counts| {
+        if let Some(cycle) = airac_cycle {
+            let mut conn = SqliteConnection::establish(&output.to_string_lossy())
+                .map_err(|e| Error::QueryBuilderError(e.into()))?;
+            conn.set_airac_cycle(cycle)?;
+        }
+        Ok(counts)
+    });
+    let (airports, runways) = match written {
+        Ok(counts) => counts,
+        Err(e) => {
+            // A half written database would be mistaken for a merged one.
+            if let Err(remove_error) = std::fs::remove_file(output) {
+                log::warn!("Failed to remove {}: {}", output.display(), remove_error);
+            }
+            return Err(e);
+        }
+    };
+
+    Ok(MergeSummary {
+        airports,
+        runways,
+        duplicates,
+    })
+}
+
+/// An airport taken from one of the merged databases.
+struct MergedAirport {
+    airport: Airport,
+    runways: Vec<Runway>,
+    frequencies: Vec<Frequency>,
+    /// The IATA code of the airport.
+    iata: Option<String>,
+    /// The index of the database the airport was taken from.
+    input: usize,
+    /// The data source recorded for the airport in that database.
+    source_id: Option<i32>,
+}
+
+/// The contents of one of the merged databases.
+struct SourceDatabase {
+    airports: Vec<MergedAirport>,
+    /// The data sources recorded in the database.
+    data_sources: Vec<DataSource>,
+    /// The AIRAC cycle the database is tagged with.
+    airac_cycle: Option<AiracCycle>,
+}
+
+/// Loads the airports of a database together with their runways, frequencies,
+/// IATA codes and data sources.
+fn load_airport_database(path: &Path) -> Result<SourceDatabase, Error> {
+    use crate::schema::{Airports, Runways};
+
+    if !path.exists() {
+        return Err(Error::QueryBuilderError(
+            format!("{} does not exist", path.display()).into(),
+        ));
+    }
+
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| Error::QueryBuilderError(e.into()))?;
+    let airports = Airports::table
+        .order(Airports::ID)
+        .load::<Airport>(&mut conn)?;
+
+    let mut runways: HashMap<i32, Vec<Runway>> = HashMap::new();
+    for runway in Runways::table.load::<Runway>(&mut conn)? {
+        runways.entry(runway.AirportID).or_default().push(runway);
+    }
+
+    let data_sources = conn.get_data_sources()?;
+    let airport_sources = airport_source_ids(&mut conn)?;
+    let mut frequencies = airport_frequencies(&mut conn)?;
+    let mut iata_codes: HashMap<String, String> = get_iata_codes(&mut conn)?
+        .into_iter()
+        .map(|code| (code.icao, code.iata))
+        .collect();
+    let airac_cycle = conn.get_airac_cycle()?;
+
+    Ok(SourceDatabase {
+        airports: airports
+            .into_iter()
+            .map(|airport| MergedAirport {
+                runways: runways.remove(&airport.ID).unwrap_or_default(),
+                frequencies: frequencies.remove(&airport.ID).unwrap_or_default(),
+                iata: iata_codes.remove(&airport.ICAO),
+                source_id: airport_sources.get(&airport.ID).copied(),
+                input: 0,
+                airport,
+            })
+            .collect(),
+        data_sources,
+        airac_cycle,
+    })
+}
+
+/// Creates an airport database, numbering the airports and runways from 1 and
+/// recording the data source of every airport.
+///
+/// # Arguments
+///
+/// * `path` - The database to create.
+/// * `airports` - The airports to write.
+/// * `source_databases` - The merged databases and the data sources recorded in them.
+///
+/// # Returns
+///
+/// The number of airports and runways written.
+fn write_airport_database(
+    path: &Path,
+    airports: Vec<MergedAirport>,
+    source_databases: &[(&&Path, Vec<DataSource>)],
+) -> Result<(usize, usize), Error> {
+    use crate::schema::{Airports, Runways};
+
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| Error::QueryBuilderError(e.into()))?;
+    conn.batch_execute(AIRPORT_SCHEMA)?;
+    conn.batch_execute(DATA_SOURCE_SCHEMA)?;
+    conn.batch_execute(FREQUENCY_SCHEMA)?;
+    conn.batch_execute(IATA_SCHEMA)?;
+
+    conn.transaction(|conn| {
+        // Maps the input database and its data source ID to the new data source ID.
+        // Sources are copied when first used, leaving out those of dropped airports.
+        let mut source_ids: HashMap<(usize, Option<i32>), i32> = HashMap::new();
+
+        let mut all_airports = Vec::with_capacity(airports.len());
+        let mut all_runways = Vec::new();
+        let mut all_frequencies = Vec::new();
+        let mut iata_codes = Vec::new();
+        let mut airport_sources = Vec::with_capacity(airports.len());
+        for (airport_id, merged) in (1..).zip(airports) {
+            let key = (merged.input, merged.source_id);
+            let source_id = match source_ids.get(&key) {
+                Some(id) => *id,
+                None => {
+                    let (source_path, data_sources) = &source_databases[merged.input];
+                    let id = match data_sources
+                        .iter()
+                        .find(|source| Some(source.id) == merged.source_id)
+                    {
+                        Some(source) => {
+                            insert_data_source(conn, &source.name, &source.version, &source.date)?
+                        }
+                        None => {
+                            // Airports without a recorded source are attributed to their file.
+                            let name = source_path.file_stem().map_or_else(
+                                || source_path.display().to_string(),
+                                |stem| stem.to_string_lossy().to_string(),
+                            );
+                            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                            insert_data_source(conn, &name, "unknown", &today)?
+                        }
+                    };
+                    source_ids.insert(key, id);
+                    id
+                }
+            };
+            airport_sources.push((airport_id, source_id));
+
+            if let Some(iata) = merged.iata {
+                iata_codes.push(AirportIataCode {
+                    icao: merged.airport.ICAO.clone(),
+                    iata,
+                });
+            }
+            all_airports.push(Airport {
+                ID: airport_id,
+                ..merged.airport
+            });
+            all_runways.extend(merged.runways.into_iter().map(|runway| Runway {
+                AirportID: airport_id,
+                ..runway
+            }));
+            all_frequencies.extend(
+                merged
+                    .frequencies
+                    .into_iter()
+                    .map(|frequency| (airport_id, frequency)),
+            );
+        }
+        for (runway_id, runway) in (1..).zip(&mut all_runways) {
+            runway.ID = runway_id;
+        }
+
+        for chunk in all_airports.chunks(INSERT_CHUNK_SIZE) {
+            diesel::insert_into(Airports::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+        for chunk in all_runways.chunks(INSERT_CHUNK_SIZE) {
+            diesel::insert_into(Runways::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+        for (airport_id, source_id) in airport_sources {
+            diesel::sql_query("INSERT INTO airport_sources (airport_id, source_id) VALUES (?, ?)")
+                .bind::<Integer, _>(airport_id)
+                .bind::<Integer, _>(source_id)
+                .execute(conn)?;
+        }
+        for (airport_id, frequency) in &all_frequencies {
+            insert_frequency(conn, *airport_id, frequency)?;
+        }
+        for code in &iata_codes {
+            insert_iata_code(conn, code)?;
+        }
+
+        Ok((all_airports.len(), all_runways.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Returns a path in the temporary directory that doesn't exist yet.
+    fn temporary_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "flight-planner-merge-{}-{}.db3",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Creates an airport database with airports given as their ICAO code,
+    /// position and runway lengths.
+    fn create_database(path: &Path, airports: &[(&str, f64, f64, &[i32])]) {
+        use crate::schema::{Airports, Runways};
+
+        let mut conn = SqliteConnection::establish(&path.to_string_lossy()).unwrap();
+        conn.batch_execute(AIRPORT_SCHEMA).unwrap();
+        let mut runway_id = 1;
+        for (airport_id, (icao, latitude, longitude, lengths)) in (1..).zip(airports) {
+            diesel::insert_into(Airports::table)
+                .values(Airport {
+                    ID: airport_id,
+                    Name: icao.to_string(),
+                    ICAO: icao.to_string(),
+                    Latitude: *latitude,
+                    Longtitude: *longitude,
+                    ..Default::default()
+                })
+                .execute(&mut conn)
+                .unwrap();
+            for length in *lengths {
+                diesel::insert_into(Runways::table)
+                    .values(Runway {
+                        ID: runway_id,
+                        AirportID: airport_id,
+                        Ident: "09".to_string(),
+                        TrueHeading: 90.0,
+                        Length: *length,
+                        Width: 150,
+                        Surface: "ASP".to_string(),
+                        Latitude: *latitude,
+                        Longtitude: *longitude,
+                        Elevation: 0,
+                    })
+                    .execute(&mut conn)
+                    .unwrap();
+                runway_id += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn merges_duplicates_and_renumbers() {
+        use crate::schema::{Airports, Runways};
+
+        let first = temporary_path("first");
+        let second = temporary_path("second");
+        let output = temporary_path("output");
+        create_database(
+            &first,
+            &[
+                ("EHAM", 52.31, 4.76, &[10000, 11000]),
+                ("EGLL", 51.47, -0.45, &[12000]),
+                ("XAAA", 10.0, 10.0, &[3000]),
+            ],
+        );
+        create_database(
+            &second,
+            &[
+                // The same code with more runways replaces the first.
+                ("EHAM", 52.31, 4.76, &[10000, 11000, 12000]),
+                // Another code within the tolerance with fewer runways is dropped.
+                ("EGL2", 51.4701, -0.4501, &[9000]),
+                ("KJFK", 40.64, -73.78, &[14000]),
+            ],
+        );
+
+        let summary = merge_airport_databases(
+            &[&first, &second],
+            &output,
+            MergeStrategy::MostRunways,
+            DEFAULT_TOLERANCE_NM,
+        )
+        .unwrap();
+
+        let duplicates: Vec<(&str, &str)> = summary
+            .duplicates
+            .iter()
+            .map(|duplicate| (duplicate.kept.as_str(), duplicate.dropped.as_str()))
+            .collect();
+        assert_eq!(duplicates, [("EHAM", "EHAM"), ("EGLL", "EGL2")]);
+        assert_eq!((summary.airports, summary.runways), (4, 6));
+
+        let mut conn = SqliteConnection::establish(&output.to_string_lossy()).unwrap();
+        let airports: Vec<(i32, String)> = Airports::table
+            .order(Airports::ID)
+            .select((Airports::ID, Airports::ICAO))
+            .load(&mut conn)
+            .unwrap();
+        let codes: Vec<(i32, &str)> = airports
+            .iter()
+            .map(|(id, icao)| (*id, icao.as_str()))
+            .collect();
+        assert_eq!(codes, [(1, "EHAM"), (2, "EGLL"), (3, "XAAA"), (4, "KJFK")]);
+        let runways: Vec<(i32, i32)> = Runways::table
+            .order(Runways::ID)
+            .select((Runways::ID, Runways::AirportID))
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(runways, [(1, 1), (2, 1), (3, 1), (4, 2), (5, 3), (6, 4)]);
+
+        drop(conn);
+        for path in [first, second, output] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
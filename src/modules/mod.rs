@@ -1,4 +1,25 @@
+pub mod achievements;
+pub mod airac;
 pub mod aircraft;
 pub mod airport;
+pub mod callsign;
+pub mod checksum;
+pub mod data_source;
+pub mod export;
+pub mod frequency;
 pub mod history;
+pub mod history_export;
+pub mod iata;
+pub mod long_haul;
+pub mod merge;
+pub mod region;
+pub mod report;
+pub mod routes;
 pub mod runway;
+pub mod seasons;
+pub mod simbrief;
+pub mod simulator;
+pub mod solar;
+pub mod sync;
+pub mod trim;
+pub mod validation;
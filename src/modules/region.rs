@@ -0,0 +1,317 @@
+/// A continent, judged by the first letter of the ICAO code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Continent {
+    Africa,
+    Asia,
+    Europe,
+    NorthAmerica,
+    Oceania,
+    SouthAmerica,
+}
+
+impl Continent {
+    /// Every continent, in the order they are listed.
+    pub const ALL: [Continent; 6] = [
+        Continent::Africa,
+        Continent::Asia,
+        Continent::Europe,
+        Continent::NorthAmerica,
+        Continent::Oceania,
+        Continent::SouthAmerica,
+    ];
+
+    /// Returns the name shown in the user interface.
+    pub fn name(self) -> &'static str {
+        match self {
+            Continent::Africa => "Africa",
+            Continent::Asia => "Asia",
+            Continent::Europe => "Europe",
+            Continent::NorthAmerica => "North America",
+            Continent::Oceania => "Oceania",
+            Continent::SouthAmerica => "South America",
+        }
+    }
+
+    /// Returns the name used on the command line, such as `north-america`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Continent::Africa => "africa",
+            Continent::Asia => "asia",
+            Continent::Europe => "europe",
+            Continent::NorthAmerica => "north-america",
+            Continent::Oceania => "oceania",
+            Continent::SouthAmerica => "south-america",
+        }
+    }
+
+    /// Returns the first letters of the ICAO codes on the continent.
+    pub fn prefixes(self) -> &'static [&'static str] {
+        match self {
+            Continent::Africa => &["D", "F", "G", "H"],
+            Continent::Asia => &["O", "R", "U", "V", "W", "Z"],
+            Continent::Europe => &["B", "E", "L"],
+            Continent::NorthAmerica => &["C", "K", "M", "P", "T"],
+            Continent::Oceania => &["A", "N", "Y"],
+            Continent::SouthAmerica => &["S"],
+        }
+    }
+
+    /// Finds a continent by its command line name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - For example `europe`.
+    pub fn from_key(key: &str) -> Option<Continent> {
+        Continent::ALL
+            .into_iter()
+            .find(|continent| continent.key().eq_ignore_ascii_case(key))
+    }
+}
+
+/// A country, known by the ICAO prefixes of its airports.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Country {
+    /// The ISO 3166 alpha-2 code.
+    pub code: &'static str,
+    /// The English name.
+    pub name: &'static str,
+    /// The ICAO prefixes of its airports. A longer prefix of another country
+    /// wins, so Russia can claim `U` while Ukraine claims `UK`.
+    prefixes: &'static [&'static str],
+}
+
+impl Country {
+    /// Returns the continent of the country, judged by its first ICAO prefix.
+    pub fn continent(&self) -> Option<Continent> {
+        let prefix = self.prefixes.first()?;
+        Continent::ALL.into_iter().find(|continent| {
+            continent
+                .prefixes()
+                .iter()
+                .any(|letter| prefix.starts_with(letter))
+        })
+    }
+}
+
+/// Shorthand for the entries of [`COUNTRIES`].
+const fn country(
+    code: &'static str,
+    name: &'static str,
+    prefixes: &'static [&'static str],
+) -> Country {
+    Country {
+        code,
+        name,
+        prefixes,
+    }
+}
+
+/// The countries airports are matched to, grouped by continent and sorted by name.
+pub static COUNTRIES: &[Country] = &[
+    // Africa
+    country("DZ", "Algeria", &["DA"]),
+    country("BW", "Botswana", &["FB"]),
+    country("CM", "Cameroon", &["FK"]),
+    country("CV", "Cape Verde", &["GV"]),
+    country("EG", "Egypt", &["HE"]),
+    country("ET", "Ethiopia", &["HA"]),
+    country("GH", "Ghana", &["DG"]),
+    country("CI", "Ivory Coast", &["DI"]),
+    country("KE", "Kenya", &["HK"]),
+    country("LY", "Libya", &["HL"]),
+    country("MG", "Madagascar", &["FM"]),
+    country("MU", "Mauritius", &["FI"]),
+    country("MA", "Morocco", &["GM"]),
+    country("MZ", "Mozambique", &["FQ"]),
+    country("NA", "Namibia", &["FY"]),
+    country("NG", "Nigeria", &["DN"]),
+    country("RW", "Rwanda", &["HR"]),
+    country("SN", "Senegal", &["GO"]),
+    country("SC", "Seychelles", &["FS"]),
+    country("ZA", "South Africa", &["FA"]),
+    country("SD", "Sudan", &["HS"]),
+    country("TZ", "Tanzania", &["HT"]),
+    country("TN", "Tunisia", &["DT"]),
+    country("UG", "Uganda", &["HU"]),
+    country("ZM", "Zambia", &["FL"]),
+    country("ZW", "Zimbabwe", &["FV"]),
+    // Asia
+    country("AF", "Afghanistan", &["OA"]),
+    country("AM", "Armenia", &["UD"]),
+    country("AZ", "Azerbaijan", &["UB"]),
+    country("BH", "Bahrain", &["OB"]),
+    country("BD", "Bangladesh", &["VG"]),
+    country("BN", "Brunei", &["WBS"]),
+    country("KH", "Cambodia", &["VD"]),
+    country("CN", "China", &["Z"]),
+    country("GE", "Georgia", &["UG"]),
+    country("HK", "Hong Kong", &["VH"]),
+    country("IN", "India", &["VA", "VE", "VI", "VO"]),
+    country("ID", "Indonesia", &["WA", "WI", "WQ", "WR"]),
+    country("IR", "Iran", &["OI"]),
+    country("IQ", "Iraq", &["OR"]),
+    country("JP", "Japan", &["RJ", "RO"]),
+    country("JO", "Jordan", &["OJ"]),
+    country("KZ", "Kazakhstan", &["UA"]),
+    country("KW", "Kuwait", &["OK"]),
+    country("KG", "Kyrgyzstan", &["UC"]),
+    country("LA", "Laos", &["VL"]),
+    country("LB", "Lebanon", &["OL"]),
+    country("MO", "Macau", &["VM"]),
+    country("MY", "Malaysia", &["WB", "WM"]),
+    country("MV", "Maldives", &["VR"]),
+    country("MN", "Mongolia", &["ZM"]),
+    country("MM", "Myanmar", &["VY"]),
+    country("NP", "Nepal", &["VN"]),
+    country("KP", "North Korea", &["ZK"]),
+    country("OM", "Oman", &["OO"]),
+    country("PK", "Pakistan", &["OP"]),
+    country("PH", "Philippines", &["RP"]),
+    country("QA", "Qatar", &["OT"]),
+    country("RU", "Russia", &["U"]),
+    country("SA", "Saudi Arabia", &["OE"]),
+    country("SG", "Singapore", &["WS"]),
+    country("KR", "South Korea", &["RK"]),
+    country("LK", "Sri Lanka", &["VC"]),
+    country("SY", "Syria", &["OS"]),
+    country("TW", "Taiwan", &["RC"]),
+    country("TJ", "Tajikistan", &["UTD"]),
+    country("TH", "Thailand", &["VT"]),
+    country("TM", "Turkmenistan", &["UTA"]),
+    country("AE", "United Arab Emirates", &["OM"]),
+    country("UZ", "Uzbekistan", &["UTK", "UTN", "UTS", "UTT"]),
+    country("VN", "Vietnam", &["VV"]),
+    // Europe
+    country("AL", "Albania", &["LA"]),
+    country("AT", "Austria", &["LO"]),
+    country("BY", "Belarus", &["UM"]),
+    country("BE", "Belgium", &["EB"]),
+    country("BA", "Bosnia and Herzegovina", &["LQ"]),
+    country("BG", "Bulgaria", &["LB"]),
+    country("HR", "Croatia", &["LD"]),
+    country("CY", "Cyprus", &["LC"]),
+    country("CZ", "Czechia", &["LK"]),
+    country("DK", "Denmark", &["EK"]),
+    country("EE", "Estonia", &["EE"]),
+    country("FI", "Finland", &["EF"]),
+    country("FR", "France", &["LF"]),
+    country("DE", "Germany", &["ED", "ET"]),
+    country("GI", "Gibraltar", &["LX"]),
+    country("GR", "Greece", &["LG"]),
+    country("GL", "Greenland", &["BG"]),
+    country("HU", "Hungary", &["LH"]),
+    country("IS", "Iceland", &["BI"]),
+    country("IE", "Ireland", &["EI"]),
+    country("IL", "Israel", &["LL"]),
+    country("IT", "Italy", &["LI"]),
+    country("XK", "Kosovo", &["BK"]),
+    country("LV", "Latvia", &["EV"]),
+    country("LT", "Lithuania", &["EY"]),
+    country("LU", "Luxembourg", &["EL"]),
+    country("MT", "Malta", &["LM"]),
+    country("MD", "Moldova", &["LU"]),
+    country("NL", "Netherlands", &["EH"]),
+    country("MK", "North Macedonia", &["LW"]),
+    country("NO", "Norway", &["EN"]),
+    country("PL", "Poland", &["EP"]),
+    country("PT", "Portugal", &["LP"]),
+    country("RO", "Romania", &["LR"]),
+    country("RS", "Serbia", &["LY"]),
+    country("SK", "Slovakia", &["LZ"]),
+    country("SI", "Slovenia", &["LJ"]),
+    country("ES", "Spain", &["LE", "GC"]),
+    country("SE", "Sweden", &["ES"]),
+    country("CH", "Switzerland", &["LS"]),
+    country("TR", "Turkey", &["LT"]),
+    country("UA", "Ukraine", &["UK"]),
+    country("GB", "United Kingdom", &["EG"]),
+    // North America
+    country("BS", "Bahamas", &["MY"]),
+    country("CA", "Canada", &["C"]),
+    country("CR", "Costa Rica", &["MR"]),
+    country("CU", "Cuba", &["MU"]),
+    country("DO", "Dominican Republic", &["MD"]),
+    country("JM", "Jamaica", &["MK"]),
+    country("MX", "Mexico", &["MM"]),
+    country("PA", "Panama", &["MP"]),
+    country(
+        "US",
+        "United States",
+        &["K", "PA", "PF", "PH", "PO", "PP", "TJ"],
+    ),
+    // Oceania
+    country("AU", "Australia", &["Y"]),
+    country("FJ", "Fiji", &["NF"]),
+    country("NZ", "New Zealand", &["NZ"]),
+    country("PG", "Papua New Guinea", &["AY"]),
+    // South America
+    country("AR", "Argentina", &["SA"]),
+    country("BR", "Brazil", &["SB", "SD", "SI", "SJ", "SN", "SS", "SW"]),
+    country("CL", "Chile", &["SC"]),
+    country("CO", "Colombia", &["SK"]),
+    country("PE", "Peru", &["SP"]),
+];
+
+/// Returns the country an airport lies in, judged by its ICAO code.
+///
+/// # Arguments
+///
+/// * `icao` - The ICAO code of the airport.
+///
+/// # Returns
+///
+/// The country with the longest matching prefix, or `None` if none matches.
+pub fn country_of(icao: &str) -> Option<&'static Country> {
+    COUNTRIES
+        .iter()
+        .filter_map(|country| {
+            country
+                .prefixes
+                .iter()
+                .filter(|prefix| icao.starts_with(*prefix))
+                .map(|prefix| prefix.len())
+                .max()
+                .map(|len| (len, country))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, country)| country)
+}
+
+/// A continent or country generated routes are kept in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Continent(Continent),
+    Country(&'static Country),
+}
+
+impl Region {
+    /// Returns the name shown in the user interface.
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Continent(continent) => continent.name(),
+            Region::Country(country) => country.name,
+        }
+    }
+
+    /// Returns whether an airport lies in the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `icao` - The ICAO code of the airport.
+    pub fn contains(self, icao: &str) -> bool {
+        match self {
+            Region::Continent(continent) => continent
+                .prefixes()
+                .iter()
+                .any(|prefix| icao.starts_with(prefix)),
+            // Checking the own prefixes first skips the full lookup for most airports.
+            Region::Country(country) => {
+                country
+                    .prefixes
+                    .iter()
+                    .any(|prefix| icao.starts_with(prefix))
+                    && country_of(icao) == Some(country)
+            }
+        }
+    }
+}
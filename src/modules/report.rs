@@ -0,0 +1,375 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::models::{Aircraft, Airport, History};
+use crate::modules::export::escape_xml;
+use crate::modules::routes::airport_distance_nm;
+
+/// The height of the bars in the monthly chart, in pixels.
+const CHART_HEIGHT: usize = 120;
+/// The width taken by every month in the monthly chart, in pixels.
+const CHART_MONTH_WIDTH: usize = 36;
+
+/// The file format of a statistics report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Markdown, ReportFormat::Html];
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Html => "HTML",
+        }
+    }
+
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// An aircraft or airport and how often it was flown, for the records of a report.
+struct Count {
+    name: String,
+    flights: usize,
+}
+
+/// The flights of a period seen from the home base.
+struct HomeStats {
+    /// The ICAO code of the home base.
+    icao: String,
+    /// The number of flights departing from the home base.
+    flights_from_home: usize,
+    /// The airports visited away from home and their distance from home, farthest first.
+    distances: Vec<(String, f64)>,
+}
+
+/// Statistics of the flights in a period.
+pub struct FlightReport {
+    /// The first day of the period.
+    start: NaiveDate,
+    /// The last day of the period.
+    end: NaiveDate,
+    /// The number of flights.
+    flights: usize,
+    /// The total great circle distance, leaving out flights with unknown airports.
+    distance_nm: f64,
+    /// The number of different airports departed from or arrived at.
+    airports: usize,
+    /// The number of different aircraft flown.
+    aircraft: usize,
+    /// The longest flight and its distance.
+    longest_flight: Option<(String, f64)>,
+    /// The aircraft flown most often.
+    most_flown_aircraft: Option<Count>,
+    /// The airport visited most often.
+    busiest_airport: Option<Count>,
+    /// The number of flights in every month of the period, oldest first.
+    flights_per_month: Vec<(NaiveDate, usize)>,
+    /// The flights seen from the home base, if one is set.
+    home: Option<HomeStats>,
+}
+
+impl FlightReport {
+    /// Computes the statistics of the flights in a period.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - All flown flights.
+    /// * `airports` - All airports, to measure the flights.
+    /// * `all_aircraft` - All aircraft, to name the aircraft flown.
+    /// * `start` - The first day of the period.
+    /// * `end` - The last day of the period.
+    /// * `home_base` - The home base, to count the flights from home and
+    ///   measure the visited airports from.
+    pub fn compute(
+        history: &[History],
+        airports: &[Arc<Airport>],
+        all_aircraft: &[Arc<Aircraft>],
+        start: NaiveDate,
+        end: NaiveDate,
+        home_base: Option<&Airport>,
+    ) -> FlightReport {
+        let by_icao: HashMap<String, &Airport> = airports
+            .iter()
+            .map(|airport| (airport.ICAO.to_uppercase(), airport.as_ref()))
+            .collect();
+        let flights: Vec<&History> = history
+            .iter()
+            .filter(|flight| {
+                NaiveDate::parse_from_str(&flight.date, "%Y-%m-%d")
+                    .is_ok_and(|date| (start..=end).contains(&date))
+            })
+            .collect();
+
+        let mut distance_nm = 0.0;
+        let mut longest_flight: Option<(String, f64)> = None;
+        let mut airport_visits: HashMap<String, usize> = HashMap::new();
+        let mut aircraft_flights: HashMap<i32, usize> = HashMap::new();
+        for flight in &flights {
+            *aircraft_flights.entry(flight.aircraft).or_default() += 1;
+            for icao in [&flight.departure_icao, &flight.arrival_icao] {
+                *airport_visits.entry(icao.to_uppercase()).or_default() += 1;
+            }
+
+            let departure = by_icao.get(&flight.departure_icao.to_uppercase());
+            let arrival = by_icao.get(&flight.arrival_icao.to_uppercase());
+            if let (Some(departure), Some(arrival)) = (departure, arrival) {
+                let distance = airport_distance_nm(departure, arrival);
+                distance_nm += distance;
+                if longest_flight
+                    .as_ref()
+                    .is_none_or(|(_, longest)| distance > *longest)
+                {
+                    longest_flight =
+                        Some((format!("{} to {}", departure.ICAO, arrival.ICAO), distance));
+                }
+            }
+        }
+
+        let most_flown_aircraft = most_frequent(&aircraft_flights).map(|(id, flights)| {
+            let name = all_aircraft
+                .iter()
+                .find(|aircraft| aircraft.id == *id)
+                .map_or_else(
+                    || format!("Aircraft {}", id),
+                    |aircraft| format!("{} {}", aircraft.manufacturer, aircraft.variant),
+                );
+            Count { name, flights }
+        });
+        let busiest_airport = most_frequent(&airport_visits).map(|(icao, flights)| Count {
+            name: icao.clone(),
+            flights,
+        });
+
+        let mut flights_per_month = Vec::new();
+        let mut month = start.with_day(1).unwrap_or(start);
+        while month <= end {
+            let count = flights
+                .iter()
+                .filter(|flight| flight.date.starts_with(&month.format("%Y-%m-").to_string()))
+                .count();
+            flights_per_month.push((month, count));
+            month = month
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(NaiveDate::MAX);
+        }
+
+        let home = home_base.map(|home| {
+            let mut distances: Vec<(String, f64)> = airport_visits
+                .keys()
+                .filter(|icao| !icao.eq_ignore_ascii_case(&home.ICAO))
+                .filter_map(|icao| by_icao.get(icao))
+                .map(|airport| (airport.ICAO.clone(), airport_distance_nm(home, airport)))
+                .collect();
+            distances.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            HomeStats {
+                icao: home.ICAO.clone(),
+                flights_from_home: flights
+                    .iter()
+                    .filter(|flight| flight.departure_icao.eq_ignore_ascii_case(&home.ICAO))
+                    .count(),
+                distances,
+            }
+        });
+
+        FlightReport {
+            start,
+            end,
+            flights: flights.len(),
+            distance_nm,
+            airports: airport_visits.len(),
+            aircraft: flights
+                .iter()
+                .map(|flight| flight.aircraft)
+                .collect::<HashSet<_>>()
+                .len(),
+            longest_flight,
+            most_flown_aircraft,
+            busiest_airport,
+            flights_per_month,
+            home,
+        }
+    }
+
+    /// Renders the report as a document in the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the document.
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.render_markdown(),
+            ReportFormat::Html => self.render_html(),
+        }
+    }
+
+    /// Returns the totals and records as label and value pairs.
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![
+            ("Flights", self.flights.to_string()),
+            ("Distance", format!("{:.0} NM", self.distance_nm)),
+            ("Airports", self.airports.to_string()),
+            ("Aircraft", self.aircraft.to_string()),
+        ];
+        if let Some((flight, distance)) = &self.longest_flight {
+            rows.push(("Longest flight", format!("{} ({:.0} NM)", flight, distance)));
+        }
+        if let Some(count) = &self.most_flown_aircraft {
+            rows.push((
+                "Most flown aircraft",
+                format!("{} ({} flights)", count.name, count.flights),
+            ));
+        }
+        if let Some(count) = &self.busiest_airport {
+            rows.push((
+                "Most visited airport",
+                format!("{} ({} visits)", count.name, count.flights),
+            ));
+        }
+        if let Some(home) = &self.home {
+            rows.push(("Home base", home.icao.clone()));
+            rows.push(("Flights from home", home.flights_from_home.to_string()));
+        }
+
+        rows
+    }
+
+    fn title(&self) -> String {
+        format!("Flight report {} to {}", self.start, self.end)
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n| | |\n|---|---|\n", self.title());
+        for (label, value) in self.rows() {
+            let _ = writeln!(markdown, "| {} | {} |", label, value.replace('|', "\\|"));
+        }
+        // Markdown allows HTML blocks, so the chart is embedded as is.
+        let _ = write!(
+            markdown,
+            "\n## Flights per month\n\n{}\n",
+            self.monthly_chart_svg()
+        );
+        if let Some(home) = self.home.as_ref().filter(|home| !home.distances.is_empty()) {
+            let _ = write!(
+                markdown,
+                "\n## Distance from {}\n\n| Airport | Distance |\n|---|---|\n",
+                home.icao
+            );
+            for (icao, distance) in &home.distances {
+                let _ = writeln!(markdown, "| {} | {:.0} NM |", icao, distance);
+            }
+        }
+
+        markdown
+    }
+
+    fn render_html(&self) -> String {
+        let mut rows = String::new();
+        for (label, value) in self.rows() {
+            let _ = writeln!(
+                rows,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                label,
+                escape_xml(&value)
+            );
+        }
+
+        let mut home_distances = String::new();
+        if let Some(home) = self.home.as_ref().filter(|home| !home.distances.is_empty()) {
+            let _ = writeln!(
+                home_distances,
+                "<h2>Distance from {}</h2>\n<table>",
+                escape_xml(&home.icao)
+            );
+            for (icao, distance) in &home.distances {
+                let _ = writeln!(
+                    home_distances,
+                    "<tr><th>{}</th><td>{:.0} NM</td></tr>",
+                    escape_xml(icao),
+                    distance
+                );
+            }
+            home_distances.push_str("</table>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body {{ font-family: sans-serif; }} th {{ text-align: left; padding-right: 1em; }}</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n<table>\n{rows}</table>\n\
+             <h2>Flights per month</h2>\n{chart}\n{home_distances}</body>\n</html>\n",
+            title = self.title(),
+            rows = rows,
+            chart = self.monthly_chart_svg(),
+            home_distances = home_distances,
+        )
+    }
+
+    /// Draws the flights per month as a bar chart.
+    fn monthly_chart_svg(&self) -> String {
+        let most = self
+            .flights_per_month
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let width = self.flights_per_month.len() * CHART_MONTH_WIDTH;
+        let height = CHART_HEIGHT + 40;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"10\">\n"
+        );
+        for (index, (month, count)) in self.flights_per_month.iter().enumerate() {
+            let bar_height = count * CHART_HEIGHT / most;
+            let x = index * CHART_MONTH_WIDTH;
+            let center = x + CHART_MONTH_WIDTH / 2;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#4a7ebb\"/>",
+                x + 4,
+                15 + CHART_HEIGHT - bar_height,
+                CHART_MONTH_WIDTH - 8,
+                bar_height
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                center,
+                12 + CHART_HEIGHT - bar_height,
+                count
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                center,
+                height - 10,
+                month.format("%b %y")
+            );
+        }
+        svg.push_str("</svg>");
+
+        svg
+    }
+}
+
+/// Returns the key counted most often, breaking ties by the smallest key.
+fn most_frequent<K: Ord>(counts: &HashMap<K, usize>) -> Option<(&K, usize)> {
+    counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(key, count)| (key, *count))
+}
@@ -0,0 +1,1527 @@
+use crate::models::{Aircraft, Airport, Runway};
+use crate::modules::airport::{
+    get_airport_with_suitable_runway_where, get_destination_airport_with_suitable_runway_fast,
+};
+use crate::modules::region::Region;
+use crate::modules::runway::{can_use_runway, longest_land_runway, AirportKind};
+use crate::modules::solar::is_daylight;
+use chrono::{DateTime, TimeDelta, Utc};
+use geo::{Bearing, Distance, Haversine, InterpolatePoint};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+const M_TO_FT: f64 = 3.28084;
+const M_TO_NM: f64 = 0.00053995680345572;
+/// The mean radius of the earth in meters, as used by the haversine formula.
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+/// The lowest suggested cruise altitude in feet.
+const MIN_CRUISE_ALTITUDE_FT: i32 = 3000;
+/// The share of range gained with no payload, or lost with twice the half load.
+const PAYLOAD_RANGE_FACTOR: f64 = 0.4;
+/// The altitude from which altitudes are shown as flight levels.
+const TRANSITION_ALTITUDE_FT: i32 = 18000;
+/// The longest range of gliders, ultralights and other light aircraft.
+const LIGHT_AIRCRAFT_MAX_RANGE_NM: i32 = 300;
+/// The shortest leg of light aircraft, ruling out hops to the next airfield.
+const LIGHT_AIRCRAFT_MIN_LEG_NM: f64 = 10.0;
+/// The land runway length from which an airport counts as a major airport.
+const MAJOR_AIRPORT_RUNWAY_FT: i32 = 11000;
+/// The cruise speed below which the speed is taken to be a placeholder.
+const MIN_CRUISE_SPEED_KT: i32 = 20;
+
+/// A structure representing a flight route.
+#[derive(Clone)]
+pub struct Route {
+    /// The departure airport.
+    pub departure: Arc<Airport>,
+    /// The destination airport.
+    pub destination: Arc<Airport>,
+    /// The aircraft used for the route.
+    pub aircraft: Arc<Aircraft>,
+    /// The departure runways.
+    pub departure_runway: Arc<Vec<Runway>>,
+    /// The destination runways.
+    pub destination_runway: Arc<Vec<Runway>>,
+}
+
+impl Route {
+    /// Returns the great-circle distance between departure and destination in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        airport_distance_nm(&self.departure, &self.destination).round()
+    }
+
+    /// Returns the estimated time en route in hours at the aircraft's cruise speed.
+    ///
+    /// The exact distance is used, as rounding matters on the short legs of
+    /// slow aircraft. Cruise speeds below 20 knots are treated as unknown.
+    pub fn ete_hours(&self) -> Option<f64> {
+        ete_hours(&self.aircraft, &self.departure, &self.destination)
+    }
+
+    /// Returns the estimated block time in hours, from leaving the gate to
+    /// arriving at the gate.
+    ///
+    /// # Arguments
+    ///
+    /// * `overhead_minutes` - The time spent taxiing, climbing and descending
+    ///   on top of the time en route.
+    pub fn block_time_hours(&self, overhead_minutes: u32) -> Option<f64> {
+        self.ete_hours()
+            .map(|ete| ete + f64::from(overhead_minutes) / 60.0)
+    }
+
+    /// Returns whether the route arrives at the gate while the sun is up at
+    /// the destination, `None` if the time en route is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `off_blocks` - The time the aircraft leaves the gate.
+    /// * `overhead_minutes` - The time spent taxiing, climbing and descending
+    ///   on top of the time en route.
+    pub fn arrives_in_daylight(
+        &self,
+        off_blocks: DateTime<Utc>,
+        overhead_minutes: u32,
+    ) -> Option<bool> {
+        let block_time = self.block_time_hours(overhead_minutes)?;
+        let arrival = off_blocks + TimeDelta::seconds((block_time * 3600.0) as i64);
+        Some(is_daylight(
+            self.destination.Latitude,
+            self.destination.Longtitude,
+            arrival,
+        ))
+    }
+
+    /// Suggests a cruise altitude in feet.
+    ///
+    /// The altitude grows with the distance (about 1,000 ft per 10 NM), is kept
+    /// below the aircraft's service ceiling and follows the hemispheric rule:
+    /// odd thousands eastbound and even thousands westbound. The true course is
+    /// used, as magnetic variation is not known.
+    pub fn suggested_altitude_ft(&self) -> i32 {
+        let ceiling = self
+            .aircraft
+            .service_ceiling
+            .unwrap_or_else(|| estimated_ceiling_ft(self.aircraft.cruise_speed))
+            .max(MIN_CRUISE_ALTITUDE_FT);
+        let target = ((self.distance_nm() * 100.0) as i32).clamp(MIN_CRUISE_ALTITUDE_FT, ceiling);
+
+        let eastbound = airport_bearing(&self.departure, &self.destination) < 180.0;
+        let mut thousands = target / 1000;
+        if (thousands % 2 == 1) != eastbound {
+            thousands -= 1;
+        }
+        if thousands * 1000 < MIN_CRUISE_ALTITUDE_FT {
+            thousands += 2;
+        }
+        // The ceiling wins over the lowest altitude, as on westbound flights
+        // of aircraft that can't climb to 4,000 ft.
+        if thousands * 1000 > ceiling {
+            thousands -= 2;
+        }
+
+        thousands * 1000
+    }
+
+    /// Checks whether an aircraft has the range and suitable runways for this route.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft to check.
+    pub fn is_suitable_for(&self, aircraft: &Aircraft) -> bool {
+        self.issues_for(aircraft).is_empty()
+    }
+
+    /// Returns the reasons an aircraft can't fly this route, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft to check.
+    pub fn issues_for(&self, aircraft: &Aircraft) -> Vec<RouteIssue> {
+        let mut issues = Vec::new();
+
+        if self.departure.ID == self.destination.ID {
+            issues.push(RouteIssue::SameAirport);
+        }
+
+        let distance = self.distance_nm();
+        if distance > aircraft.aircraft_range as f64 {
+            issues.push(RouteIssue::OutOfRange {
+                distance_nm: distance,
+                range_nm: aircraft.aircraft_range,
+            });
+        }
+
+        // Airports without known runways only rule out aircraft with a known takeoff distance.
+        let runway_fits = |runways: &[Runway]| {
+            runways
+                .iter()
+                .any(|runway| can_use_runway(aircraft, runway))
+                || (runways.is_empty() && aircraft.takeoff_distance.is_none())
+        };
+        if !runway_fits(&self.departure_runway) {
+            issues.push(RouteIssue::NoSuitableDepartureRunway);
+        }
+        if !runway_fits(&self.destination_runway) {
+            issues.push(RouteIssue::NoSuitableDestinationRunway);
+        }
+
+        issues
+    }
+
+    /// Returns a copy of the route flown with a different aircraft.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft to fly the route with.
+    pub fn with_aircraft(&self, aircraft: Arc<Aircraft>) -> Route {
+        Route {
+            aircraft,
+            ..self.clone()
+        }
+    }
+}
+
+/// A reason an aircraft can't fly a route.
+#[derive(Debug, PartialEq)]
+pub enum RouteIssue {
+    /// Departure and destination are the same airport.
+    SameAirport,
+    /// The route is longer than the aircraft's range.
+    OutOfRange { distance_nm: f64, range_nm: i32 },
+    /// No departure runway suits the aircraft, being too short, on water or a helipad.
+    NoSuitableDepartureRunway,
+    /// No destination runway suits the aircraft, being too short, on water or a helipad.
+    NoSuitableDestinationRunway,
+}
+
+impl fmt::Display for RouteIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteIssue::SameAirport => write!(f, "Departure and destination are the same airport"),
+            RouteIssue::OutOfRange {
+                distance_nm,
+                range_nm,
+            } => write!(
+                f,
+                "Distance of {:.0} NM exceeds the aircraft range of {} NM",
+                distance_nm, range_nm
+            ),
+            RouteIssue::NoSuitableDepartureRunway => {
+                write!(f, "No departure runway is suitable for the aircraft")
+            }
+            RouteIssue::NoSuitableDestinationRunway => {
+                write!(f, "No destination runway is suitable for the aircraft")
+            }
+        }
+    }
+}
+
+/// Formats a duration in hours as hours and minutes, e.g. `1h 05m`.
+///
+/// # Arguments
+///
+/// * `hours` - The duration in hours.
+pub fn format_duration_hours(hours: f64) -> String {
+    let total_minutes = (hours * 60.0).round() as i64;
+    format!("{}h {:02}m", total_minutes / 60, total_minutes % 60)
+}
+
+/// A fuel and payload load for estimating the range of an aircraft.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AircraftLoad {
+    /// The fuel on board as a share of full tanks, in percent.
+    pub fuel_percent: f64,
+    /// The payload as a share of the maximum payload, in percent.
+    pub payload_percent: f64,
+}
+
+impl Default for AircraftLoad {
+    /// Full tanks and half the maximum payload, the load the published range is taken for.
+    fn default() -> Self {
+        AircraftLoad {
+            fuel_percent: 100.0,
+            payload_percent: 50.0,
+        }
+    }
+}
+
+impl AircraftLoad {
+    /// Estimates the range of an aircraft with this load in nautical miles.
+    ///
+    /// The range scales with the fuel on board, and every percent of payload
+    /// above or below the half load costs or adds 0.4 percent of range. This
+    /// is a rough estimate rather than a performance calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft to estimate the range of.
+    pub fn effective_range_nm(&self, aircraft: &Aircraft) -> f64 {
+        let fuel = self.fuel_percent.clamp(0.0, 100.0) / 100.0;
+        let payload = self.payload_percent.clamp(0.0, 100.0) / 100.0;
+        aircraft.aircraft_range as f64 * fuel * (1.0 + PAYLOAD_RANGE_FACTOR * (0.5 - payload))
+    }
+}
+
+/// Formats an altitude in feet, as a flight level from the transition altitude up.
+///
+/// # Arguments
+///
+/// * `altitude_ft` - The altitude in feet.
+pub fn format_altitude(altitude_ft: i32) -> String {
+    if altitude_ft >= TRANSITION_ALTITUDE_FT {
+        format!("FL{:03}", altitude_ft / 100)
+    } else if altitude_ft >= 1000 {
+        format!("{},{:03} ft", altitude_ft / 1000, altitude_ft % 1000)
+    } else {
+        format!("{} ft", altitude_ft)
+    }
+}
+
+/// Estimates the service ceiling of an aircraft without one from its cruise speed.
+fn estimated_ceiling_ft(cruise_speed: i32) -> i32 {
+    match cruise_speed {
+        ..200 => 12000,
+        200..300 => 28000,
+        _ => 41000,
+    }
+}
+
+/// A spatial index object for airports.
+#[derive(PartialEq)]
+pub struct SpatialAirport {
+    pub airport: Arc<Airport>,
+}
+
+/// Implement the RTreeObject trait for SpatialAirport for the RTree index.
+impl RTreeObject for SpatialAirport {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let point = [self.airport.Latitude, self.airport.Longtitude];
+        AABB::from_point(point)
+    }
+}
+
+impl PointDistance for SpatialAirport {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.envelope().distance_2(point)
+    }
+}
+
+/// Returns the great circle distance between two airports in nautical miles.
+///
+/// # Arguments
+///
+/// * `from` - The first airport.
+/// * `to` - The second airport.
+pub fn airport_distance_nm(from: &Airport, to: &Airport) -> f64 {
+    let point1 = geo::Point::new(from.Longtitude, from.Latitude);
+    let point2 = geo::Point::new(to.Longtitude, to.Latitude);
+    Haversine::distance(point1, point2) * M_TO_NM
+}
+
+/// Limits on the length of generated routes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RouteConstraints {
+    /// The share of the aircraft's range kept in reserve, in percent.
+    pub range_reserve_percent: u32,
+    /// The shortest route to generate in nautical miles.
+    pub min_distance_nm: u32,
+    /// The longest route to generate in nautical miles, if shorter than the aircraft range.
+    pub max_distance_nm: Option<u32>,
+    /// The compass sector the destination has to lie in, seen from the departure.
+    pub direction: Option<CompassSector>,
+    /// The area the destination has to lie in.
+    pub area: Option<GeoArea>,
+    /// The area the departure has to lie in.
+    pub departure_area: Option<GeoArea>,
+    /// The continent or country both ends of the route have to lie in.
+    pub region: Option<Region>,
+    /// The latitude, north or south, the great circle route has to reach.
+    pub min_path_latitude: Option<f64>,
+    /// Whether to keep light aircraft away from major airports.
+    pub avoid_major_airports: bool,
+    /// When set, routes have to arrive in daylight, counting the time en
+    /// route from this moment.
+    pub daylight_arrivals: Option<DateTime<Utc>>,
+}
+
+impl RouteConstraints {
+    /// Returns the longest route the aircraft may fly, after keeping the reserve
+    /// and applying the maximum distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft flying the route.
+    pub fn max_distance_nm(&self, aircraft: &Aircraft) -> f64 {
+        let usable = 1.0 - f64::from(self.range_reserve_percent.min(100)) / 100.0;
+        let range_nm = aircraft.aircraft_range as f64 * usable;
+        match self.max_distance_nm {
+            Some(max_distance_nm) => range_nm.min(f64::from(max_distance_nm)),
+            None => range_nm,
+        }
+    }
+
+    /// Returns the shortest route the aircraft may fly.
+    ///
+    /// Light aircraft fly at least 10 NM, but never more than half their range,
+    /// so a minimum leg set for larger aircraft still leaves them routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft flying the route.
+    pub fn min_distance_nm(&self, aircraft: &Aircraft) -> f64 {
+        let min_distance_nm = f64::from(self.min_distance_nm);
+        if !is_light_aircraft(aircraft) {
+            return min_distance_nm;
+        }
+
+        min_distance_nm
+            .max(LIGHT_AIRCRAFT_MIN_LEG_NM)
+            .min(self.max_distance_nm(aircraft) / 2.0)
+    }
+
+    /// Returns whether a route of the given length fits the constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft flying the route.
+    /// * `distance_nm` - The length of the route.
+    pub fn allows(&self, aircraft: &Aircraft, distance_nm: f64) -> bool {
+        distance_nm >= self.min_distance_nm(aircraft)
+            && distance_nm <= self.max_distance_nm(aircraft)
+    }
+
+    /// Returns whether the aircraft may use an airport with the given runways.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft flying the route.
+    /// * `runways` - The runways of the departure or destination.
+    pub fn allows_runways(&self, aircraft: &Aircraft, runways: &[Runway]) -> bool {
+        !(self.avoid_major_airports
+            && is_light_aircraft(aircraft)
+            && longest_land_runway(runways).is_some_and(|length| length >= MAJOR_AIRPORT_RUNWAY_FT))
+    }
+
+    /// Returns whether a destination at the given bearing fits the direction constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearing` - The initial bearing from the departure in degrees.
+    pub fn allows_bearing(&self, bearing: f64) -> bool {
+        self.direction.is_none_or(|sector| sector.contains(bearing))
+    }
+
+    /// Returns whether a destination fits the area and region constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The destination.
+    pub fn allows_position(&self, airport: &Airport) -> bool {
+        self.area
+            .is_none_or(|area| area.contains(airport.Latitude, airport.Longtitude))
+            && self.allows_region(airport)
+    }
+
+    /// Returns whether an airport fits the region constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The departure or destination.
+    pub fn allows_region(&self, airport: &Airport) -> bool {
+        self.region
+            .is_none_or(|region| region.contains(&airport.ICAO))
+    }
+
+    /// Returns whether the route arrives while the sun is up at the
+    /// destination, if it has to. Routes of aircraft without a known cruise
+    /// speed are allowed, as their arrival time is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft flying the route.
+    /// * `departure` - The departure airport.
+    /// * `destination` - The destination airport.
+    pub fn allows_arrival(
+        &self,
+        aircraft: &Aircraft,
+        departure: &Airport,
+        destination: &Airport,
+    ) -> bool {
+        let (Some(start), Some(ete)) = (
+            self.daylight_arrivals,
+            ete_hours(aircraft, departure, destination),
+        ) else {
+            return true;
+        };
+
+        let arrival = start + TimeDelta::seconds((ete * 3600.0) as i64);
+        is_daylight(destination.Latitude, destination.Longtitude, arrival)
+    }
+
+    /// Returns whether the great circle route between the airports fits the
+    /// path latitude constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `departure` - The departure airport.
+    /// * `destination` - The destination airport.
+    pub fn allows_path(&self, departure: &Airport, destination: &Airport) -> bool {
+        self.min_path_latitude
+            .is_none_or(|latitude| max_path_latitude(departure, destination) >= latitude.abs())
+    }
+}
+
+/// Returns whether the aircraft is a glider, ultralight or other light aircraft,
+/// judged by its range.
+///
+/// # Arguments
+///
+/// * `aircraft` - The aircraft to check.
+pub fn is_light_aircraft(aircraft: &Aircraft) -> bool {
+    !aircraft.helicopter && aircraft.aircraft_range <= LIGHT_AIRCRAFT_MAX_RANGE_NM
+}
+
+/// An area bounded by latitudes and longitudes, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoArea {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl GeoArea {
+    /// Returns whether a position lies in the area.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - The latitude in degrees.
+    /// * `longitude` - The longitude in degrees.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&latitude)
+            && (self.min_longitude..=self.max_longitude).contains(&longitude)
+    }
+
+    /// Returns the area widened by a distance on every side, as spatial index
+    /// envelopes. The widened area is split in two where it crosses the
+    /// antimeridian, and spans every longitude where it reaches a pole.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin_nm` - The distance to widen the area by in nautical miles.
+    pub(crate) fn envelopes_around(&self, margin_nm: f64) -> Vec<AABB<[f64; 2]>> {
+        let margin_latitude = margin_nm / 60.0;
+        let min_latitude = (self.min_latitude - margin_latitude).max(-90.0);
+        let max_latitude = (self.max_latitude + margin_latitude).min(90.0);
+        let envelope = |min_longitude: f64, max_longitude: f64| {
+            AABB::from_corners([min_latitude, min_longitude], [max_latitude, max_longitude])
+        };
+
+        // A degree of longitude shrinks towards the poles, so the margin in
+        // longitude is widest at the latitude closest to a pole.
+        let widest_latitude = self.min_latitude.abs().max(self.max_latitude.abs());
+        if widest_latitude + margin_latitude >= 90.0 {
+            return vec![envelope(-180.0, 180.0)];
+        }
+        let margin_longitude = (margin_latitude.to_radians().sin()
+            / widest_latitude.to_radians().cos())
+        .asin()
+        .to_degrees();
+
+        let min_longitude = self.min_longitude - margin_longitude;
+        let max_longitude = self.max_longitude + margin_longitude;
+        if max_longitude - min_longitude >= 360.0 {
+            vec![envelope(-180.0, 180.0)]
+        } else if min_longitude < -180.0 {
+            vec![
+                envelope(min_longitude + 360.0, 180.0),
+                envelope(-180.0, max_longitude),
+            ]
+        } else if max_longitude > 180.0 {
+            vec![
+                envelope(min_longitude, 180.0),
+                envelope(-180.0, max_longitude - 360.0),
+            ]
+        } else {
+            vec![envelope(min_longitude, max_longitude)]
+        }
+    }
+}
+
+/// An airport near the path of a route, as returned by
+/// [`RouteGenerator::enroute_alternates`].
+pub struct EnrouteAlternate {
+    pub airport: Arc<Airport>,
+    /// The distance from the departure, measured along the path, in nautical miles.
+    pub along_track_nm: f64,
+    /// The distance off the path in nautical miles.
+    pub cross_track_nm: f64,
+}
+
+/// A stretch of a route without an airport in reach, as returned by
+/// [`RouteGenerator::coverage_gaps`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoverageGap {
+    /// Where the stretch starts, measured along the path from the departure, in nautical miles.
+    pub start_nm: f64,
+    /// Where the stretch ends, measured along the path from the departure, in nautical miles.
+    pub end_nm: f64,
+}
+
+/// Limits on the airports picked by [`RouteGenerator::random_airport`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AirportConstraints {
+    /// The ICAO prefixes the airport code has to start with, any region if empty.
+    pub icao_prefixes: Vec<String>,
+    /// The shortest length in feet the longest land runway may have.
+    pub min_runway_ft: u32,
+    /// The kind the airport has to be, any kind if `None`.
+    pub kind: Option<AirportKind>,
+}
+
+/// One of the eight 45 degree sectors of the compass.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompassSector {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl CompassSector {
+    /// The sectors clockwise from north.
+    pub const ALL: [CompassSector; 8] = [
+        CompassSector::North,
+        CompassSector::NorthEast,
+        CompassSector::East,
+        CompassSector::SouthEast,
+        CompassSector::South,
+        CompassSector::SouthWest,
+        CompassSector::West,
+        CompassSector::NorthWest,
+    ];
+
+    /// Returns the abbreviation of the sector, e.g. `NE`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            CompassSector::North => "N",
+            CompassSector::NorthEast => "NE",
+            CompassSector::East => "E",
+            CompassSector::SouthEast => "SE",
+            CompassSector::South => "S",
+            CompassSector::SouthWest => "SW",
+            CompassSector::West => "W",
+            CompassSector::NorthWest => "NW",
+        }
+    }
+
+    /// Returns the bearing in the middle of the sector in degrees.
+    pub fn center_bearing(&self) -> f64 {
+        let index = Self::ALL
+            .iter()
+            .position(|sector| sector == self)
+            .unwrap_or_default();
+        index as f64 * 45.0
+    }
+
+    /// Returns whether a bearing lies within the sector.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearing` - The bearing in degrees.
+    pub fn contains(&self, bearing: f64) -> bool {
+        let offset = (bearing - self.center_bearing() + 180.0).rem_euclid(360.0) - 180.0;
+        offset.abs() <= 22.5
+    }
+}
+
+/// Returns the estimated time en route in hours at the aircraft's cruise speed.
+///
+/// Cruise speeds below 20 knots are treated as unknown.
+///
+/// # Arguments
+///
+/// * `aircraft` - The aircraft flying the route.
+/// * `departure` - The departure airport.
+/// * `destination` - The destination airport.
+pub fn ete_hours(aircraft: &Aircraft, departure: &Airport, destination: &Airport) -> Option<f64> {
+    if aircraft.cruise_speed < MIN_CRUISE_SPEED_KT {
+        return None;
+    }
+
+    Some(airport_distance_nm(departure, destination) / aircraft.cruise_speed as f64)
+}
+
+/// Returns the initial great circle bearing from one airport to another in degrees.
+///
+/// # Arguments
+///
+/// * `from` - The airport to measure from.
+/// * `to` - The airport to measure to.
+pub fn airport_bearing(from: &Airport, to: &Airport) -> f64 {
+    let point1 = geo::Point::new(from.Longtitude, from.Latitude);
+    let point2 = geo::Point::new(to.Longtitude, to.Latitude);
+    Haversine::bearing(point1, point2).rem_euclid(360.0)
+}
+
+/// Returns the highest latitude, north or south, on the great circle route between two airports.
+///
+/// # Arguments
+///
+/// * `from` - The departure airport.
+/// * `to` - The destination airport.
+pub fn max_path_latitude(from: &Airport, to: &Airport) -> f64 {
+    /// Points sampled along the route, enough to find the highest latitude
+    /// within a fraction of a degree on the longest routes.
+    const SAMPLES: u32 = 64;
+
+    let start = geo::Point::new(from.Longtitude, from.Latitude);
+    let end = geo::Point::new(to.Longtitude, to.Latitude);
+    (0..=SAMPLES)
+        .map(|sample| {
+            Haversine::point_at_ratio_between(start, end, f64::from(sample) / f64::from(SAMPLES))
+                .y()
+                .abs()
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Generates random routes from in-memory airport and runway data.
+pub struct RouteGenerator {
+    /// All available airports.
+    pub all_airports: Vec<Arc<Airport>>,
+    /// A map of all runways, keyed by airport ID.
+    pub all_runways: HashMap<i32, Arc<Vec<Runway>>>,
+    /// Spatial index of airports for efficient queries.
+    pub spatial_airports: RTree<SpatialAirport>,
+    /// The longest land runway length in feet, keyed by airport ID.
+    pub longest_runways: HashMap<i32, i32>,
+    /// Airports that have land runways, sorted by longest runway (longest first).
+    pub sorted_airports: Vec<Arc<Airport>>,
+}
+
+impl RouteGenerator {
+    /// Creates a new route generator, building the runway map and spatial index.
+    ///
+    /// # Arguments
+    ///
+    /// * `airports` - All airports to generate routes between.
+    /// * `runways` - All runways belonging to those airports.
+    pub fn new(airports: Vec<Airport>, runways: Vec<Runway>) -> Self {
+        let mut runway_map: HashMap<i32, Vec<Runway>> = HashMap::new();
+        for runway in runways {
+            runway_map.entry(runway.AirportID).or_default().push(runway);
+        }
+
+        let all_runways: HashMap<i32, Arc<Vec<Runway>>> = runway_map
+            .into_iter()
+            .map(|(id, runways)| (id, Arc::new(runways)))
+            .collect();
+
+        let all_airports: Vec<Arc<Airport>> = airports.into_iter().map(Arc::new).collect();
+
+        let spatial_airports = RTree::bulk_load(
+            all_airports
+                .iter()
+                .map(|airport| SpatialAirport {
+                    airport: Arc::clone(airport),
+                })
+                .collect(),
+        );
+
+        let longest_runways: HashMap<i32, i32> = all_runways
+            .iter()
+            .filter_map(|(airport_id, runways)| {
+                let longest = longest_land_runway(runways)?;
+                Some((*airport_id, longest))
+            })
+            .collect();
+
+        let mut sorted_airports: Vec<Arc<Airport>> = all_airports
+            .iter()
+            .filter(|airport| longest_runways.contains_key(&airport.ID))
+            .cloned()
+            .collect();
+        sorted_airports
+            .sort_by_key(|airport| (std::cmp::Reverse(longest_runways[&airport.ID]), airport.ID));
+
+        RouteGenerator {
+            all_airports,
+            all_runways,
+            spatial_airports,
+            longest_runways,
+            sorted_airports,
+        }
+    }
+
+    /// Returns the airports with a runway the aircraft can depart from.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft that has to depart.
+    fn departure_candidates(&self, aircraft: &Aircraft) -> Cow<'_, [Arc<Airport>]> {
+        // Helipads and water runways are left out of the airports sorted by land runway.
+        if aircraft.helicopter || aircraft.amphibian {
+            return Cow::Owned(
+                self.all_airports
+                    .iter()
+                    .filter(|airport| self.can_depart_from(aircraft, airport))
+                    .cloned()
+                    .collect(),
+            );
+        }
+
+        let Some(takeoff_distance) = aircraft.takeoff_distance else {
+            return Cow::Borrowed(&self.sorted_airports);
+        };
+
+        let takeoff_distance_ft = takeoff_distance as f64 * M_TO_FT;
+        let end = self.sorted_airports.partition_point(|airport| {
+            self.longest_runways[&airport.ID] as f64 >= takeoff_distance_ft
+        });
+
+        Cow::Borrowed(&self.sorted_airports[..end])
+    }
+
+    /// Returns whether the airport has a runway the aircraft can depart from.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft` - The aircraft that has to depart.
+    /// * `airport` - The airport to depart from.
+    fn can_depart_from(&self, aircraft: &Aircraft, airport: &Airport) -> bool {
+        self.all_runways.get(&airport.ID).is_some_and(|runways| {
+            runways
+                .iter()
+                .any(|runway| can_use_runway(aircraft, runway))
+        })
+    }
+
+    /// Generates random routes for the given aircraft list.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft_list` - A slice of aircraft to generate routes for.
+    /// * `amount` - The number of routes to generate.
+    /// * `constraints` - Limits on the length of the routes.
+    /// * `hubs` - Airports most departures are drawn from, such as the hubs of a
+    ///   virtual airline. Hubs without a long enough runway are skipped.
+    pub fn generate_random_routes(
+        &self,
+        aircraft_list: &[Arc<Aircraft>],
+        amount: usize,
+        constraints: &RouteConstraints,
+        hubs: &[Arc<Airport>],
+    ) -> Vec<Route> {
+        /// Departures tried per route before giving up, as a minimum leg length
+        /// may rule out every destination of remote airports.
+        const MAX_DEPARTURE_ATTEMPTS: usize = 100;
+        /// The chance of departing from a hub, leaving room for the occasional
+        /// flight elsewhere.
+        const HUB_DEPARTURE_CHANCE: f64 = 0.75;
+
+        let start_time = Instant::now();
+
+        let routes: Vec<Route> = (0..amount)
+            .into_par_iter()
+            .filter_map(|_| {
+                let mut rand = rand::thread_rng();
+                let aircraft = aircraft_list.choose(&mut rand)?;
+                if constraints.min_distance_nm(aircraft) > constraints.max_distance_nm(aircraft) {
+                    return None;
+                }
+                // Only departures within range of the area can reach it.
+                let envelopes = match (constraints.departure_area, constraints.area) {
+                    (Some(departure_area), _) => Some(departure_area.envelopes_around(0.0)),
+                    (None, Some(area)) => {
+                        Some(area.envelopes_around(constraints.max_distance_nm(aircraft)))
+                    }
+                    (None, None) => None,
+                };
+                let area_departures: Option<Vec<Arc<Airport>>> = envelopes.map(|envelopes| {
+                    envelopes
+                        .iter()
+                        .flat_map(|envelope| self.spatial_airports.locate_in_envelope(envelope))
+                        .filter(|spatial| self.can_depart_from(aircraft, &spatial.airport))
+                        .map(|spatial| Arc::clone(&spatial.airport))
+                        .collect()
+                });
+                let mut candidates = match area_departures {
+                    Some(area_departures) => Cow::Owned(area_departures),
+                    None => self.departure_candidates(aircraft),
+                };
+                if constraints.region.is_some() {
+                    candidates = Cow::Owned(
+                        candidates
+                            .iter()
+                            .filter(|airport| constraints.allows_region(airport))
+                            .cloned()
+                            .collect(),
+                    );
+                }
+                let usable_hubs: Vec<&Arc<Airport>> = hubs
+                    .iter()
+                    .filter(|hub| {
+                        self.can_depart_from(aircraft, hub) && constraints.allows_region(hub)
+                    })
+                    .collect();
+
+                for _ in 0..MAX_DEPARTURE_ATTEMPTS {
+                    let departure = match usable_hubs.choose(&mut rand) {
+                        Some(hub) if rand.gen_bool(HUB_DEPARTURE_CHANCE) => *hub,
+                        _ => candidates.choose(&mut rand)?,
+                    };
+                    let departure_runways = self.all_runways.get(&departure.ID)?;
+                    if !constraints.allows_runways(aircraft, departure_runways) {
+                        continue;
+                    }
+
+                    if let Ok(destination) = get_destination_airport_with_suitable_runway_fast(
+                        aircraft,
+                        departure,
+                        &self.spatial_airports,
+                        &self.all_runways,
+                        constraints,
+                    ) {
+                        let departure_runways = Arc::clone(departure_runways);
+                        let destination_runways = self.all_runways.get(&destination.ID)?;
+                        let destination_runways = Arc::clone(destination_runways);
+                        return Some(Route {
+                            departure: Arc::clone(departure),
+                            destination,
+                            aircraft: Arc::clone(aircraft),
+                            departure_runway: departure_runways,
+                            destination_runway: destination_runways,
+                        });
+                    }
+                }
+
+                None
+            })
+            .collect();
+
+        let duration = start_time.elapsed();
+        log::info!("Generated {} routes in {:?}", routes.len(), duration);
+
+        routes
+    }
+
+    /// Generates random routes arriving at a fixed destination.
+    ///
+    /// The direction and area constraints only limit destinations, so they are
+    /// not applied to the departures. The region limits both ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `aircraft_list` - A slice of aircraft to generate routes for.
+    /// * `destination` - The airport every route arrives at.
+    /// * `amount` - The number of routes to generate.
+    /// * `constraints` - Limits on the length of the routes.
+    ///
+    /// # Returns
+    ///
+    /// The routes, none if the destination is outside the region, or an error if
+    /// none of the aircraft can land at the destination.
+    pub fn generate_routes_to(
+        &self,
+        aircraft_list: &[Arc<Aircraft>],
+        destination: &Arc<Airport>,
+        amount: usize,
+        constraints: &RouteConstraints,
+    ) -> Result<Vec<Route>, String> {
+        if !constraints.allows_region(destination) {
+            return Ok(Vec::new());
+        }
+
+        let departure_constraints = RouteConstraints {
+            direction: None,
+            area: None,
+            departure_area: None,
+            // The arrival is checked at the destination while departures are searched.
+            daylight_arrivals: None,
+            ..*constraints
+        };
+        let destination_runways = self
+            .all_runways
+            .get(&destination.ID)
+            .ok_or_else(|| format!("{} has no runways", destination.ICAO))?;
+        let suitable_aircraft: Vec<&Arc<Aircraft>> = aircraft_list
+            .iter()
+            .filter(|aircraft| {
+                self.can_depart_from(aircraft, destination)
+                    && constraints.allows_runways(aircraft, destination_runways)
+                    && constraints.min_distance_nm(aircraft)
+                        <= constraints.max_distance_nm(aircraft)
+            })
+            .collect();
+        if suitable_aircraft.is_empty() {
+            return Err(format!(
+                "None of the aircraft can land at {}",
+                destination.ICAO
+            ));
+        }
+
+        let routes: Vec<Route> = (0..amount)
+            .into_par_iter()
+            .filter_map(|_| {
+                let aircraft = suitable_aircraft.choose(&mut rand::thread_rng())?;
+                // Distances and runways are symmetric, so departures are searched from the destination.
+                let departure = get_airport_with_suitable_runway_where(
+                    aircraft,
+                    destination,
+                    &self.spatial_airports,
+                    &self.all_runways,
+                    &departure_constraints,
+                    |departure| constraints.allows_arrival(aircraft, departure, destination),
+                )
+                .ok()?;
+
+                Some(self.create_route(departure, Arc::clone(destination), Arc::clone(aircraft)))
+            })
+            .collect();
+
+        Ok(routes)
+    }
+
+    /// Generates a route with the same departure and aircraft but a different,
+    /// randomly chosen destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to replace the destination of.
+    /// * `constraints` - Limits on the length and direction of the route.
+    ///
+    /// # Returns
+    ///
+    /// The new route, or `None` if no other destination is suitable.
+    pub fn reroll_destination(
+        &self,
+        route: &Route,
+        constraints: &RouteConstraints,
+    ) -> Option<Route> {
+        /// Destinations drawn before giving up, as the current one may be drawn again.
+        const MAX_ATTEMPTS: usize = 10;
+
+        (0..MAX_ATTEMPTS).find_map(|_| {
+            let destination = get_destination_airport_with_suitable_runway_fast(
+                &route.aircraft,
+                &route.departure,
+                &self.spatial_airports,
+                &self.all_runways,
+                constraints,
+            )
+            .ok()?;
+
+            (destination.ID != route.destination.ID).then(|| {
+                self.create_route(
+                    Arc::clone(&route.departure),
+                    destination,
+                    Arc::clone(&route.aircraft),
+                )
+            })
+        })
+    }
+
+    /// Picks a random airport that meets the constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `constraints` - The region, runway length and kind the airport must have.
+    ///
+    /// # Returns
+    ///
+    /// The airport, or `None` if no airport meets the constraints.
+    pub fn random_airport(&self, constraints: &AirportConstraints) -> Option<&Arc<Airport>> {
+        let candidates: Vec<&Arc<Airport>> = self
+            .all_airports
+            .iter()
+            .filter(|airport| {
+                constraints.icao_prefixes.is_empty()
+                    || constraints
+                        .icao_prefixes
+                        .iter()
+                        .any(|prefix| airport.ICAO.starts_with(prefix.as_str()))
+            })
+            .filter(|airport| {
+                constraints.min_runway_ft == 0
+                    || self
+                        .longest_runways
+                        .get(&airport.ID)
+                        .is_some_and(|&length| length >= constraints.min_runway_ft as i32)
+            })
+            .filter(|airport| {
+                constraints.kind.is_none_or(|kind| {
+                    let runways = self.all_runways.get(&airport.ID);
+                    AirportKind::of(runways.map_or(&[][..], |runways| runways.as_slice())) == kind
+                })
+            })
+            .collect();
+
+        candidates.choose(&mut rand::thread_rng()).copied()
+    }
+
+    /// Finds an airport by its ICAO code.
+    ///
+    /// # Arguments
+    ///
+    /// * `icao` - The ICAO code, matched case-insensitively.
+    pub fn find_airport_by_icao(&self, icao: &str) -> Option<&Arc<Airport>> {
+        self.all_airports
+            .iter()
+            .find(|airport| airport.ICAO.eq_ignore_ascii_case(icao))
+    }
+
+    /// Returns the airports closest to an airport, nearest first, including the airport itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to search around.
+    /// * `amount` - The number of airports to return.
+    pub fn nearest_airports(&self, airport: &Airport, amount: usize) -> Vec<Arc<Airport>> {
+        // The index measures in degrees, so take extra candidates and sort them by real distance.
+        let mut nearest: Vec<Arc<Airport>> = self
+            .spatial_airports
+            .nearest_neighbor_iter(&[airport.Latitude, airport.Longtitude])
+            .take(amount * 4)
+            .map(|spatial| Arc::clone(&spatial.airport))
+            .collect();
+
+        nearest.sort_by(|a, b| {
+            airport_distance_nm(airport, a).total_cmp(&airport_distance_nm(airport, b))
+        });
+        nearest.truncate(amount);
+        nearest
+    }
+
+    /// Returns the airports within a distance of the great circle path of a
+    /// route, ordered along the path, as fields to divert to on the way. Only
+    /// airports with a runway the aircraft of the route can use are included,
+    /// and the departure and destination are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to search along.
+    /// * `corridor_nm` - The largest distance off the path in nautical miles.
+    pub fn enroute_alternates(&self, route: &Route, corridor_nm: f64) -> Vec<EnrouteAlternate> {
+        let start = geo::Point::new(route.departure.Longtitude, route.departure.Latitude);
+        let end = geo::Point::new(route.destination.Longtitude, route.destination.Latitude);
+        let route_nm = airport_distance_nm(&route.departure, &route.destination);
+        let corridor_nm = corridor_nm.max(1.0);
+
+        // Boxes around points sampled along the path cover the corridor, as
+        // each reaches half the spacing past its point on top of the corridor.
+        let samples = (route_nm / corridor_nm).ceil().max(1.0) as u32;
+        let margin_nm = corridor_nm + route_nm / f64::from(samples) / 2.0;
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        for sample in 0..=samples {
+            let point = Haversine::point_at_ratio_between(
+                start,
+                end,
+                f64::from(sample) / f64::from(samples),
+            );
+            let area = GeoArea {
+                min_latitude: point.y(),
+                max_latitude: point.y(),
+                min_longitude: point.x(),
+                max_longitude: point.x(),
+            };
+            for spatial in area
+                .envelopes_around(margin_nm)
+                .iter()
+                .flat_map(|envelope| self.spatial_airports.locate_in_envelope(envelope))
+            {
+                if seen.insert(spatial.airport.ID) {
+                    candidates.push(&spatial.airport);
+                }
+            }
+        }
+
+        let route_bearing = Haversine::bearing(start, end).to_radians();
+        let mut alternates: Vec<EnrouteAlternate> = candidates
+            .into_iter()
+            .filter(|airport| {
+                airport.ID != route.departure.ID
+                    && airport.ID != route.destination.ID
+                    && self.all_runways.get(&airport.ID).is_some_and(|runways| {
+                        runways
+                            .iter()
+                            .any(|runway| can_use_runway(&route.aircraft, runway))
+                    })
+            })
+            .filter_map(|airport| {
+                let point = geo::Point::new(airport.Longtitude, airport.Latitude);
+                let distance = Haversine::distance(start, point) / EARTH_RADIUS_M;
+                let bearing = Haversine::bearing(start, point).to_radians() - route_bearing;
+                let cross_track = (distance.sin() * bearing.sin()).asin();
+                let along_track = (distance.cos() / cross_track.cos()).clamp(-1.0, 1.0).acos()
+                    * bearing.cos().signum();
+
+                let cross_track_nm = cross_track.abs() * EARTH_RADIUS_M * M_TO_NM;
+                let along_track_nm = along_track * EARTH_RADIUS_M * M_TO_NM;
+                (cross_track_nm <= corridor_nm && (0.0..=route_nm).contains(&along_track_nm)).then(
+                    || EnrouteAlternate {
+                        airport: Arc::clone(airport),
+                        along_track_nm,
+                        cross_track_nm,
+                    },
+                )
+            })
+            .collect();
+
+        alternates.sort_by(|a, b| a.along_track_nm.total_cmp(&b.along_track_nm));
+        alternates
+    }
+
+    /// Returns the stretches of a route farther than the diversion distance
+    /// from every airport the aircraft can use, as checked for twin engine
+    /// aircraft on long flights over water.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route to check.
+    /// * `diversion_nm` - The farthest the aircraft may be from an airport in nautical miles.
+    ///
+    /// # Returns
+    ///
+    /// The stretches without an airport in reach, ordered along the path,
+    /// empty if the whole route is covered.
+    pub fn coverage_gaps(&self, route: &Route, diversion_nm: f64) -> Vec<CoverageGap> {
+        /// The spacing of the points checked along the path in nautical miles.
+        const STEP_NM: f64 = 10.0;
+
+        let alternates = self.enroute_alternates(route, diversion_nm);
+        let start = geo::Point::new(route.departure.Longtitude, route.departure.Latitude);
+        let end = geo::Point::new(route.destination.Longtitude, route.destination.Latitude);
+        let route_nm = airport_distance_nm(&route.departure, &route.destination);
+        let samples = (route_nm / STEP_NM).ceil().max(1.0) as u32;
+
+        let mut gaps: Vec<CoverageGap> = Vec::new();
+        let mut in_gap = false;
+        for sample in 0..=samples {
+            let ratio = f64::from(sample) / f64::from(samples);
+            let along_nm = route_nm * ratio;
+            let point = Haversine::point_at_ratio_between(start, end, ratio);
+            let in_reach = |airport: &Airport| {
+                let position = geo::Point::new(airport.Longtitude, airport.Latitude);
+                Haversine::distance(point, position) * M_TO_NM <= diversion_nm
+            };
+
+            // Airports farther along or back than the diversion distance are out of reach.
+            let first = alternates
+                .partition_point(|alternate| alternate.along_track_nm < along_nm - diversion_nm);
+            let covered = in_reach(&route.departure)
+                || in_reach(&route.destination)
+                || alternates[first..]
+                    .iter()
+                    .take_while(|alternate| alternate.along_track_nm <= along_nm + diversion_nm)
+                    .any(|alternate| in_reach(&alternate.airport));
+
+            match gaps.last_mut() {
+                _ if covered => in_gap = false,
+                Some(gap) if in_gap => gap.end_nm = along_nm,
+                _ => {
+                    gaps.push(CoverageGap {
+                        start_nm: along_nm,
+                        end_nm: along_nm,
+                    });
+                    in_gap = true;
+                }
+            }
+        }
+
+        gaps
+    }
+
+    /// Creates a route between two airports, attaching their runways.
+    ///
+    /// # Arguments
+    ///
+    /// * `departure` - The departure airport.
+    /// * `destination` - The destination airport.
+    /// * `aircraft` - The aircraft to fly the route with.
+    pub fn create_route(
+        &self,
+        departure: Arc<Airport>,
+        destination: Arc<Airport>,
+        aircraft: Arc<Aircraft>,
+    ) -> Route {
+        let runways = |airport: &Airport| {
+            self.all_runways
+                .get(&airport.ID)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        Route {
+            departure_runway: runways(&departure),
+            destination_runway: runways(&destination),
+            departure,
+            destination,
+            aircraft,
+        }
+    }
+
+    /// Inserts a single airport and its runways without rebuilding the index.
+    ///
+    /// An existing airport with the same ID is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport` - The airport to insert.
+    /// * `runways` - The runways belonging to the airport.
+    pub fn insert_airport(&mut self, airport: Airport, runways: Vec<Runway>) {
+        self.remove_airport(airport.ID);
+
+        let airport = Arc::new(airport);
+        if let Some(longest) = longest_land_runway(&runways) {
+            let position = self.sorted_airports.partition_point(|other| {
+                (self.longest_runways[&other.ID], -other.ID) >= (longest, -airport.ID)
+            });
+            self.sorted_airports.insert(position, Arc::clone(&airport));
+            self.longest_runways.insert(airport.ID, longest);
+        }
+        if !runways.is_empty() {
+            self.all_runways.insert(airport.ID, Arc::new(runways));
+        }
+        self.spatial_airports.insert(SpatialAirport {
+            airport: Arc::clone(&airport),
+        });
+        self.all_airports.push(airport);
+    }
+
+    /// Removes a single airport and its runways without rebuilding the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `airport_id` - The ID of the airport to remove.
+    pub fn remove_airport(&mut self, airport_id: i32) -> Option<Arc<Airport>> {
+        let position = self
+            .all_airports
+            .iter()
+            .position(|airport| airport.ID == airport_id)?;
+        let airport = self.all_airports.swap_remove(position);
+
+        self.all_runways.remove(&airport_id);
+        if let Some(&longest) = self.longest_runways.get(&airport_id) {
+            // Found by the order `insert_airport` keeps: longest runway first, then by ID.
+            let position = self.sorted_airports.partition_point(|other| {
+                (self.longest_runways[&other.ID], -other.ID) > (longest, -airport_id)
+            });
+            debug_assert_eq!(self.sorted_airports[position].ID, airport_id);
+            self.sorted_airports.remove(position);
+            self.longest_runways.remove(&airport_id);
+        }
+        self.spatial_airports.remove(&SpatialAirport {
+            airport: Arc::clone(&airport),
+        });
+
+        Some(airport)
+    }
+
+    /// Brings the generator in line with a fresh copy of the airport data.
+    ///
+    /// Only airports that were added, removed or changed (including their
+    /// runways) are touched, so small edits don't require a full rebuild.
+    ///
+    /// # Arguments
+    ///
+    /// * `airports` - The current airports.
+    /// * `runways` - The current runways.
+    ///
+    /// # Returns
+    ///
+    /// The number of airports that were inserted, updated or removed.
+    pub fn apply_airport_changes(&mut self, airports: Vec<Airport>, runways: Vec<Runway>) -> usize {
+        let mut runway_map: HashMap<i32, Vec<Runway>> = HashMap::new();
+        for runway in runways {
+            runway_map.entry(runway.AirportID).or_default().push(runway);
+        }
+
+        let existing: HashMap<i32, Arc<Airport>> = self
+            .all_airports
+            .iter()
+            .map(|airport| (airport.ID, Arc::clone(airport)))
+            .collect();
+        let current_ids: HashSet<i32> = airports.iter().map(|airport| airport.ID).collect();
+        let mut changes = 0;
+
+        let removed: Vec<i32> = existing
+            .keys()
+            .filter(|id| !current_ids.contains(id))
+            .copied()
+            .collect();
+        for airport_id in removed {
+            self.remove_airport(airport_id);
+            changes += 1;
+        }
+
+        for airport in airports {
+            let runways = runway_map.remove(&airport.ID).unwrap_or_default();
+            let unchanged = existing.get(&airport.ID).is_some_and(|old| {
+                **old == airport
+                    && self
+                        .all_runways
+                        .get(&airport.ID)
+                        .map_or(runways.is_empty(), |old_runways| **old_runways == runways)
+            });
+
+            if !unchanged {
+                self.insert_airport(airport, runways);
+                changes += 1;
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn airport(id: i32, icao: &str, latitude: f64, longitude: f64) -> Airport {
+        Airport {
+            ID: id,
+            ICAO: icao.to_string(),
+            Latitude: latitude,
+            Longtitude: longitude,
+            ..Default::default()
+        }
+    }
+
+    fn runway(airport: &Airport) -> Runway {
+        Runway {
+            ID: airport.ID,
+            AirportID: airport.ID,
+            Ident: "09".to_string(),
+            TrueHeading: 90.0,
+            Length: 8000,
+            Width: 150,
+            Surface: "ASP".to_string(),
+            Latitude: airport.Latitude,
+            Longtitude: airport.Longtitude,
+            Elevation: 0,
+        }
+    }
+
+    fn aircraft() -> Arc<Aircraft> {
+        Arc::new(Aircraft {
+            id: 1,
+            manufacturer: "Airbus".to_string(),
+            variant: "A330-300".to_string(),
+            icao_code: "A333".to_string(),
+            flown: 0,
+            aircraft_range: 6000,
+            category: "A".to_string(),
+            cruise_speed: 470,
+            date_flown: None,
+            takeoff_distance: None,
+            service_ceiling: None,
+            helicopter: false,
+            amphibian: false,
+        })
+    }
+
+    #[test]
+    fn alternates_across_the_antimeridian_cover_the_route() {
+        // The route runs just west of the antimeridian and the only airport
+        // in reach of its middle lies just east of it.
+        let airports = vec![
+            airport(1, "DEP1", -2.0, 179.8),
+            airport(2, "ARR1", 2.0, 179.8),
+            airport(3, "ALT1", 0.0, -179.8),
+        ];
+        let runways = airports.iter().map(runway).collect();
+        let generator = RouteGenerator::new(airports, runways);
+        let route = generator.create_route(
+            Arc::clone(&generator.all_airports[0]),
+            Arc::clone(&generator.all_airports[1]),
+            aircraft(),
+        );
+
+        let alternates = generator.enroute_alternates(&route, 100.0);
+        assert_eq!(alternates.len(), 1);
+        assert_eq!(alternates[0].airport.ICAO, "ALT1");
+        assert_eq!(generator.coverage_gaps(&route, 100.0), Vec::new());
+    }
+
+    #[test]
+    fn airport_changes_keep_the_runway_order() {
+        let airports: Vec<Airport> = (1..=20)
+            .map(|id| airport(id, &format!("A{:03}", id), 50.0, f64::from(id) / 10.0))
+            .collect();
+        let runways_of = |airports: &[Airport], length: &dyn Fn(i32) -> i32| -> Vec<Runway> {
+            airports
+                .iter()
+                .map(|airport| Runway {
+                    Length: length(airport.ID),
+                    ..runway(airport)
+                })
+                .collect()
+        };
+        let mut generator =
+            RouteGenerator::new(airports.clone(), runways_of(&airports, &|id| id % 4 * 1000));
+
+        // Drop every third airport and lengthen the runways of the even ones.
+        let changed: Vec<Airport> = airports.into_iter().filter(|a| a.ID % 3 != 0).collect();
+        let changed_length = |id: i32| id % 4 * 1000 + if id % 2 == 0 { 500 } else { 0 };
+        let changes =
+            generator.apply_airport_changes(changed.clone(), runways_of(&changed, &changed_length));
+        let rebuilt = RouteGenerator::new(changed.clone(), runways_of(&changed, &changed_length));
+
+        let ids = |generator: &RouteGenerator| -> Vec<i32> {
+            generator.sorted_airports.iter().map(|a| a.ID).collect()
+        };
+        assert_eq!(changes, 6 + 7);
+        assert_eq!(ids(&generator), ids(&rebuilt));
+    }
+
+    #[test]
+    fn routes_to_a_destination_keep_the_region_and_daylight() {
+        // From the near departure the route lands before sunrise, from the
+        // far one after it.
+        let airports = vec![
+            airport(1, "DDST", 0.0, 0.0),
+            airport(2, "DNEA", 0.0, 2.0),
+            airport(3, "DFAR", 0.0, 45.0),
+        ];
+        let runways = airports.iter().map(runway).collect();
+        let generator = RouteGenerator::new(airports, runways);
+        let destination = Arc::clone(generator.find_airport_by_icao("DDST").unwrap());
+        let constraints = RouteConstraints {
+            daylight_arrivals: Some("2024-03-20T01:00:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+
+        let routes = generator
+            .generate_routes_to(&[aircraft()], &destination, 20, &constraints)
+            .unwrap();
+        assert_eq!(routes.len(), 20);
+        assert!(routes.iter().all(|route| route.departure.ICAO == "DFAR"));
+
+        let europe = RouteConstraints {
+            region: Some(Region::Continent(crate::modules::region::Continent::Europe)),
+            ..Default::default()
+        };
+        let routes = generator
+            .generate_routes_to(&[aircraft()], &destination, 20, &europe)
+            .unwrap();
+        assert!(routes.is_empty());
+    }
+}
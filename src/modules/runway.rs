@@ -3,6 +3,121 @@ use diesel::RunQueryDsl;
 use crate::models::*;
 use crate::DatabasePool;
 
+const M_TO_FT: f64 = 3.28084;
+
+/// What a runway can be used by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunwayKind {
+    /// A runway on land for fixed-wing aircraft.
+    Land,
+    /// A water runway for floatplanes and amphibians.
+    Water,
+    /// A helipad, named like `H1`.
+    Helipad,
+}
+
+impl RunwayKind {
+    /// Classifies a runway by its surface and identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `runway` - The runway to classify.
+    pub fn of(runway: &Runway) -> RunwayKind {
+        let helipad_number = runway.Ident.strip_prefix('H');
+        if runway.Surface.eq_ignore_ascii_case("W") {
+            RunwayKind::Water
+        } else if helipad_number.is_some_and(|number| number.chars().all(|c| c.is_ascii_digit())) {
+            RunwayKind::Helipad
+        } else {
+            RunwayKind::Land
+        }
+    }
+}
+
+/// The kind of airport, derived from its runways.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AirportKind {
+    Airport,
+    /// An airport with only helipads.
+    Heliport,
+    /// An airport with only water runways.
+    SeaplaneBase,
+}
+
+impl AirportKind {
+    pub const ALL: [AirportKind; 3] = [
+        AirportKind::Airport,
+        AirportKind::Heliport,
+        AirportKind::SeaplaneBase,
+    ];
+
+    /// Classifies an airport by its runways.
+    ///
+    /// # Arguments
+    ///
+    /// * `runways` - The runways of the airport.
+    pub fn of(runways: &[Runway]) -> AirportKind {
+        let only = |kind: RunwayKind| {
+            !runways.is_empty() && runways.iter().all(|runway| RunwayKind::of(runway) == kind)
+        };
+
+        if only(RunwayKind::Helipad) {
+            AirportKind::Heliport
+        } else if only(RunwayKind::Water) {
+            AirportKind::SeaplaneBase
+        } else {
+            AirportKind::Airport
+        }
+    }
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AirportKind::Airport => "Airport",
+            AirportKind::Heliport => "Heliport",
+            AirportKind::SeaplaneBase => "Seaplane base",
+        }
+    }
+}
+
+/// Returns the length in feet of the longest land runway, which fixed-wing
+/// aircraft can use.
+///
+/// # Arguments
+///
+/// * `runways` - The runways of an airport.
+pub fn longest_land_runway(runways: &[Runway]) -> Option<i32> {
+    runways
+        .iter()
+        .filter(|runway| RunwayKind::of(runway) == RunwayKind::Land)
+        .map(|runway| runway.Length)
+        .max()
+}
+
+/// Returns whether an aircraft can take off from and land on a runway.
+///
+/// Helicopters use helipads and land runways of any length. Amphibians also
+/// use water runways. The takeoff distance applies to runways of fixed-wing
+/// aircraft, on land and on water.
+///
+/// # Arguments
+///
+/// * `aircraft` - The aircraft to check.
+/// * `runway` - The runway to check.
+pub fn can_use_runway(aircraft: &Aircraft, runway: &Runway) -> bool {
+    let long_enough = || {
+        aircraft.takeoff_distance.is_none_or(|takeoff_distance| {
+            runway.Length as f64 >= takeoff_distance as f64 * M_TO_FT
+        })
+    };
+
+    match RunwayKind::of(runway) {
+        RunwayKind::Helipad => aircraft.helicopter,
+        RunwayKind::Land => aircraft.helicopter || long_enough(),
+        RunwayKind::Water => aircraft.amphibian && long_enough(),
+    }
+}
+
 impl DatabasePool {
     pub fn get_runways(&self) -> Result<Vec<Runway>, diesel::result::Error> {
         use crate::schema::Runways::dsl::*;
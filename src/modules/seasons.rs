@@ -0,0 +1,133 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::modules::routes::GeoArea;
+
+/// A destination area that is at its best during part of the year.
+pub struct SeasonalPreset {
+    /// The label of the suggestion.
+    pub name: &'static str,
+    /// Why the area is suggested.
+    pub description: &'static str,
+    /// The first month of the season, 1 for January.
+    first_month: u32,
+    /// The last month of the season, which comes before the first when the
+    /// season runs over the new year.
+    last_month: u32,
+    /// The area the destinations are generated in.
+    pub area: GeoArea,
+}
+
+impl SeasonalPreset {
+    /// Returns whether the date falls in the season.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date to check, usually today.
+    pub fn is_in_season(&self, date: NaiveDate) -> bool {
+        let month = date.month();
+        if self.first_month <= self.last_month {
+            (self.first_month..=self.last_month).contains(&month)
+        } else {
+            month >= self.first_month || month <= self.last_month
+        }
+    }
+}
+
+/// The seasonal suggestions, in the order they are shown.
+pub const SEASONAL_PRESETS: [SeasonalPreset; 7] = [
+    SeasonalPreset {
+        name: "Alpine skiing",
+        description: "Ski season in the Alps",
+        first_month: 12,
+        last_month: 4,
+        area: GeoArea {
+            min_latitude: 44.0,
+            max_latitude: 48.0,
+            min_longitude: 5.5,
+            max_longitude: 16.0,
+        },
+    },
+    SeasonalPreset {
+        name: "Rocky Mountains",
+        description: "Ski season in the Rocky Mountains",
+        first_month: 12,
+        last_month: 3,
+        area: GeoArea {
+            min_latitude: 37.0,
+            max_latitude: 51.5,
+            min_longitude: -119.0,
+            max_longitude: -104.5,
+        },
+    },
+    SeasonalPreset {
+        name: "Southern Alps",
+        description: "Ski season on the South Island of New Zealand",
+        first_month: 6,
+        last_month: 9,
+        area: GeoArea {
+            min_latitude: -46.5,
+            max_latitude: -41.5,
+            min_longitude: 166.5,
+            max_longitude: 174.5,
+        },
+    },
+    SeasonalPreset {
+        name: "Greek islands",
+        description: "Summer on the islands of the Aegean",
+        first_month: 6,
+        last_month: 9,
+        area: GeoArea {
+            min_latitude: 35.0,
+            max_latitude: 40.0,
+            min_longitude: 23.0,
+            max_longitude: 28.5,
+        },
+    },
+    SeasonalPreset {
+        name: "Balearic Islands",
+        description: "Summer on Mallorca, Menorca and Ibiza",
+        first_month: 6,
+        last_month: 9,
+        area: GeoArea {
+            min_latitude: 38.5,
+            max_latitude: 40.2,
+            min_longitude: 1.0,
+            max_longitude: 4.5,
+        },
+    },
+    SeasonalPreset {
+        name: "Caribbean",
+        description: "Winter sun in the Caribbean",
+        first_month: 12,
+        last_month: 4,
+        area: GeoArea {
+            min_latitude: 10.0,
+            max_latitude: 27.0,
+            min_longitude: -86.0,
+            max_longitude: -59.0,
+        },
+    },
+    SeasonalPreset {
+        name: "Northern lights",
+        description: "Long nights above the Arctic Circle in Scandinavia",
+        first_month: 10,
+        last_month: 3,
+        area: GeoArea {
+            min_latitude: 66.5,
+            max_latitude: 71.5,
+            min_longitude: 12.0,
+            max_longitude: 30.0,
+        },
+    },
+];
+
+/// Returns the presets in season on a date.
+///
+/// # Arguments
+///
+/// * `date` - The date to check, usually today.
+pub fn presets_in_season(date: NaiveDate) -> impl Iterator<Item = &'static SeasonalPreset> {
+    SEASONAL_PRESETS
+        .iter()
+        .filter(move |preset| preset.is_in_season(date))
+}
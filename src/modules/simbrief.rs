@@ -0,0 +1,51 @@
+use crate::models::AircraftSimTitle;
+use crate::modules::routes::Route;
+use crate::modules::simulator::describe_sim_titles;
+
+/// The SimBrief page that prefills a new flight plan from its query parameters.
+const DISPATCH_URL: &str = "https://dispatch.simbrief.com/options/custom";
+
+/// Returns the SimBrief dispatch URL that starts a flight plan for the route.
+///
+/// The departure, destination, aircraft type, callsign and suggested cruise
+/// altitude are filled in, and the simulator titles of the aircraft are put
+/// in the remarks; SimBrief asks for everything else.
+///
+/// # Arguments
+///
+/// * `route` - The route to plan.
+/// * `callsign` - The callsign of the flight.
+/// * `sim_titles` - The simulator titles of the aircraft.
+pub fn dispatch_url(route: &Route, callsign: &str, sim_titles: &[AircraftSimTitle]) -> String {
+    let mut parameters = vec![
+        ("orig", route.departure.ICAO.clone()),
+        ("dest", route.destination.ICAO.clone()),
+        ("type", route.aircraft.icao_code.clone()),
+        ("callsign", callsign.to_string()),
+        ("fl", route.suggested_altitude_ft().to_string()),
+    ];
+    if let Some(titles) = describe_sim_titles(sim_titles, route.aircraft.id) {
+        parameters.push(("manualrmk", format!("Simulator aircraft {}", titles)));
+    }
+
+    let query: Vec<String> = parameters
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, encode_query_value(value)))
+        .collect();
+    format!("{}?{}", DISPATCH_URL, query.join("&"))
+}
+
+/// Percent-encodes every character of a query value that is not unreserved.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
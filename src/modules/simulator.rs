@@ -0,0 +1,240 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+
+use crate::models::*;
+use crate::schema::aircraft_sim_titles::dsl::*;
+use crate::traits::SimulatorMappingOperations;
+use crate::DatabaseConnections;
+use crate::DatabasePool;
+
+/// A flight simulator whose aircraft titles can be mapped to aircraft records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Simulator {
+    /// Microsoft Flight Simulator, matched on the `title` in `aircraft.cfg`.
+    Msfs,
+    /// X-Plane, matched on the `.acf` file name.
+    XPlane,
+}
+
+impl Simulator {
+    pub const ALL: [Simulator; 2] = [Simulator::Msfs, Simulator::XPlane];
+
+    /// Returns the value stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Simulator::Msfs => "msfs",
+            Simulator::XPlane => "xplane",
+        }
+    }
+
+    /// Returns the name shown to the user.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Simulator::Msfs => "MSFS",
+            Simulator::XPlane => "X-Plane",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::aircraft_sim_titles)]
+struct AircraftSimTitleForm<'a> {
+    aircraft: i32,
+    simulator: &'a str,
+    title: &'a str,
+}
+
+impl SimulatorMappingOperations for DatabaseConnections {
+    fn add_sim_title(
+        &mut self,
+        aircraft_record: &Aircraft,
+        sim: &str,
+        sim_title: &str,
+    ) -> Result<(), Error> {
+        add_sim_title(
+            &mut self.aircraft_connection,
+            aircraft_record,
+            sim,
+            sim_title,
+        )
+    }
+
+    fn get_sim_titles(&mut self) -> Result<Vec<AircraftSimTitle>, Error> {
+        let records: Vec<AircraftSimTitle> = aircraft_sim_titles
+            .order(title)
+            .load(&mut self.aircraft_connection)?;
+
+        Ok(records)
+    }
+
+    fn delete_sim_title(&mut self, sim_title_id: i32) -> Result<(), Error> {
+        diesel::delete(aircraft_sim_titles.find(sim_title_id))
+            .execute(&mut self.aircraft_connection)?;
+
+        Ok(())
+    }
+}
+
+impl SimulatorMappingOperations for DatabasePool {
+    fn add_sim_title(
+        &mut self,
+        aircraft_record: &Aircraft,
+        sim: &str,
+        sim_title: &str,
+    ) -> Result<(), Error> {
+        add_sim_title(
+            &mut self.aircraft_pool.get().unwrap(),
+            aircraft_record,
+            sim,
+            sim_title,
+        )
+    }
+
+    fn get_sim_titles(&mut self) -> Result<Vec<AircraftSimTitle>, Error> {
+        let conn = &mut self.aircraft_pool.get().unwrap();
+        let records: Vec<AircraftSimTitle> = aircraft_sim_titles.order(title).load(conn)?;
+
+        Ok(records)
+    }
+
+    fn delete_sim_title(&mut self, sim_title_id: i32) -> Result<(), Error> {
+        let conn = &mut self.aircraft_pool.get().unwrap();
+        diesel::delete(aircraft_sim_titles.find(sim_title_id)).execute(conn)?;
+
+        Ok(())
+    }
+}
+
+fn add_sim_title(
+    conn: &mut SqliteConnection,
+    aircraft_record: &Aircraft,
+    sim: &str,
+    sim_title: &str,
+) -> Result<(), Error> {
+    let record = AircraftSimTitleForm {
+        aircraft: aircraft_record.id,
+        simulator: sim,
+        title: sim_title.trim(),
+    };
+
+    diesel::insert_into(aircraft_sim_titles)
+        .values(&record)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Finds the aircraft ID mapped to a title reported by the simulator.
+///
+/// An exact (case-insensitive) match wins. Otherwise the longest mapped title
+/// contained in the reported title is used, so `Cessna Skyhawk Asobo` still
+/// matches a mapping for `Cessna Skyhawk` when a livery name is appended.
+///
+/// # Arguments
+///
+/// * `mappings` - The known simulator title mappings.
+/// * `sim` - The simulator reporting the title.
+/// * `reported_title` - The title reported by the simulator.
+pub fn find_aircraft_for_sim_title(
+    mappings: &[AircraftSimTitle],
+    sim: Simulator,
+    reported_title: &str,
+) -> Option<i32> {
+    let reported_title = reported_title.trim().to_lowercase();
+    if reported_title.is_empty() {
+        return None;
+    }
+
+    let candidates = mappings
+        .iter()
+        .filter(|mapping| mapping.simulator == sim.as_str());
+
+    let mut best_match: Option<&AircraftSimTitle> = None;
+    for mapping in candidates {
+        let mapped_title = mapping.title.to_lowercase();
+        if mapped_title == reported_title {
+            return Some(mapping.aircraft);
+        }
+
+        if reported_title.contains(&mapped_title)
+            && best_match.is_none_or(|best| mapping.title.len() > best.title.len())
+        {
+            best_match = Some(mapping);
+        }
+    }
+
+    best_match.map(|mapping| mapping.aircraft)
+}
+
+/// Returns the simulator titles mapped to an aircraft, one per simulator, so
+/// exports can name the aircraft as the simulator does.
+///
+/// When several titles are mapped for a simulator, such as one per livery,
+/// the first in the order of `mappings` is used.
+///
+/// # Arguments
+///
+/// * `mappings` - The known simulator title mappings.
+/// * `aircraft_id` - The aircraft to look up.
+pub fn sim_titles_for_aircraft(
+    mappings: &[AircraftSimTitle],
+    aircraft_id: i32,
+) -> Vec<(Simulator, &str)> {
+    Simulator::ALL
+        .into_iter()
+        .filter_map(|sim| {
+            mappings
+                .iter()
+                .find(|mapping| {
+                    mapping.aircraft == aircraft_id && mapping.simulator == sim.as_str()
+                })
+                .map(|mapping| (sim, mapping.title.as_str()))
+        })
+        .collect()
+}
+
+/// Describes the simulator titles mapped to an aircraft, such as
+/// `MSFS: PMDG 737-800, X-Plane: B738`, or `None` if there are none.
+///
+/// # Arguments
+///
+/// * `mappings` - The known simulator title mappings.
+/// * `aircraft_id` - The aircraft to describe.
+pub fn describe_sim_titles(mappings: &[AircraftSimTitle], aircraft_id: i32) -> Option<String> {
+    let titles: Vec<String> = sim_titles_for_aircraft(mappings, aircraft_id)
+        .into_iter()
+        .map(|(sim, sim_title)| format!("{}: {}", sim.display_name(), sim_title))
+        .collect();
+
+    (!titles.is_empty()).then(|| titles.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_sim_titles, AircraftSimTitle, Simulator};
+
+    fn mapping(aircraft: i32, simulator: Simulator, title: &str) -> AircraftSimTitle {
+        AircraftSimTitle {
+            id: 0,
+            aircraft,
+            simulator: simulator.as_str().to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn describes_the_first_title_per_simulator() {
+        let mappings = [
+            mapping(1, Simulator::XPlane, "Zibo 737-800"),
+            mapping(2, Simulator::Msfs, "FBW A320neo"),
+            mapping(1, Simulator::Msfs, "PMDG 737-800"),
+            mapping(1, Simulator::Msfs, "PMDG 737-800 KLM"),
+        ];
+
+        assert_eq!(
+            describe_sim_titles(&mappings, 1).as_deref(),
+            Some("MSFS: PMDG 737-800, X-Plane: Zibo 737-800")
+        );
+        assert_eq!(describe_sim_titles(&mappings, 3), None);
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, Timelike, Utc};
+use std::f64::consts::PI;
+
+/// The elevation of the centre of the sun at sunrise and sunset in degrees,
+/// below the horizon because of refraction and the size of the disc.
+const SUNRISE_ELEVATION_DEG: f64 = -0.833;
+
+/// The sunrise and sunset of a day at a place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SunTimes {
+    /// The sun rises and sets.
+    Times {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// The sun stays above the horizon all day.
+    PolarDay,
+    /// The sun stays below the horizon all day.
+    PolarNight,
+}
+
+/// Returns the declination of the sun in radians and the equation of time in
+/// minutes, using the approximation of the NOAA solar calculator.
+///
+/// # Arguments
+///
+/// * `day_of_year` - The day of the year, 1 for the first of January.
+/// * `hour` - The hour of the day in UTC, with fractions.
+fn sun_position(day_of_year: u32, hour: f64) -> (f64, f64) {
+    let gamma = 2.0 * PI / 365.0 * (f64::from(day_of_year) - 1.0 + (hour - 12.0) / 24.0);
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    (declination, equation_of_time)
+}
+
+/// Returns the elevation of the sun above the horizon in degrees, negative
+/// when it is below.
+///
+/// # Arguments
+///
+/// * `latitude` - The latitude of the place in degrees.
+/// * `longitude` - The longitude of the place in degrees, east positive.
+/// * `time` - The moment to compute the elevation for.
+pub fn sun_elevation_deg(latitude: f64, longitude: f64, time: DateTime<Utc>) -> f64 {
+    let hour = f64::from(time.num_seconds_from_midnight()) / 3600.0;
+    let (declination, equation_of_time) = sun_position(time.ordinal(), hour);
+
+    let solar_minutes = hour * 60.0 + equation_of_time + 4.0 * longitude;
+    let hour_angle = (solar_minutes / 4.0 - 180.0).to_radians();
+    let latitude = latitude.to_radians();
+    let cos_zenith =
+        latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos();
+
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Returns whether the sun is up at a place, counting from sunrise to sunset.
+///
+/// # Arguments
+///
+/// * `latitude` - The latitude of the place in degrees.
+/// * `longitude` - The longitude of the place in degrees, east positive.
+/// * `time` - The moment to check.
+pub fn is_daylight(latitude: f64, longitude: f64, time: DateTime<Utc>) -> bool {
+    sun_elevation_deg(latitude, longitude, time) > SUNRISE_ELEVATION_DEG
+}
+
+/// Returns the sunrise and sunset at a place on a day.
+///
+/// # Arguments
+///
+/// * `latitude` - The latitude of the place in degrees.
+/// * `longitude` - The longitude of the place in degrees, east positive.
+/// * `date` - The day, in UTC.
+pub fn sun_times(latitude: f64, longitude: f64, date: NaiveDate) -> SunTimes {
+    let (declination, equation_of_time) = sun_position(date.ordinal(), 12.0);
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (90.0 - SUNRISE_ELEVATION_DEG).to_radians().cos()
+        / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle > 1.0 {
+        return SunTimes::PolarNight;
+    }
+    if cos_hour_angle < -1.0 {
+        return SunTimes::PolarDay;
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let midnight = date.and_time(NaiveTime::MIN).and_utc();
+    let at_minutes = |minutes: f64| midnight + TimeDelta::seconds((minutes * 60.0).round() as i64);
+    SunTimes::Times {
+        sunrise: at_minutes(720.0 - 4.0 * (longitude + hour_angle) - equation_of_time),
+        sunset: at_minutes(720.0 - 4.0 * (longitude - hour_angle) - equation_of_time),
+    }
+}
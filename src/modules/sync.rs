@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::modules::checksum::file_checksum;
+
+/// Which copy wins when a file changed both locally and in the sync folder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeepSide {
+    Local,
+    Remote,
+}
+
+/// What syncing did with a file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SyncOutcome {
+    /// The file exists in neither place.
+    Missing,
+    /// Both copies were already the same.
+    Unchanged,
+    /// The local copy was written to the sync folder.
+    Pushed,
+    /// The copy in the sync folder replaced the local one.
+    Pulled,
+    /// Both copies changed since the last sync, so neither was touched.
+    Conflict,
+}
+
+impl SyncOutcome {
+    /// Returns a short description of the outcome.
+    pub fn description(&self) -> &'static str {
+        match self {
+            SyncOutcome::Missing => "missing in both places",
+            SyncOutcome::Unchanged => "up to date",
+            SyncOutcome::Pushed => "copied to the sync folder",
+            SyncOutcome::Pulled => "copied from the sync folder",
+            SyncOutcome::Conflict => "changed in both places since the last sync",
+        }
+    }
+}
+
+/// The checksums of the files as they were after the last sync, which tell
+/// which side changed a file since.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SyncState {
+    /// Checksums in hexadecimal, keyed by file name. TOML has no unsigned integers.
+    checksums: HashMap<String, String>,
+}
+
+impl SyncState {
+    fn load(path: &Path) -> SyncState {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = toml::to_string(self).map_err(io::Error::other)?;
+        std::fs::write(path, contents)
+    }
+
+    fn get(&self, name: &str) -> Option<u64> {
+        self.checksums
+            .get(name)
+            .and_then(|checksum| u64::from_str_radix(checksum, 16).ok())
+    }
+
+    fn set(&mut self, name: &str, checksum: u64) {
+        self.checksums
+            .insert(name.to_string(), format!("{:016x}", checksum));
+    }
+}
+
+/// Mirrors files between the working directory and a sync folder, such as a
+/// Dropbox or OneDrive folder shared by several computers.
+///
+/// A file changed on one side only is copied to the other side. A file changed
+/// on both sides since the last sync is a conflict and is left alone, unless
+/// `keep` says which side wins.
+///
+/// # Arguments
+///
+/// * `folder` - The sync folder.
+/// * `files` - The names of the files to mirror, relative to both directories.
+/// * `state_path` - The file recording the checksums after the last sync.
+/// * `keep` - The side that wins a conflict, if any.
+///
+/// # Returns
+///
+/// The outcome for every file, in the order of `files`.
+pub fn sync_files(
+    folder: &Path,
+    files: &[&str],
+    state_path: &Path,
+    keep: Option<KeepSide>,
+) -> io::Result<Vec<SyncOutcome>> {
+    if !folder.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Sync folder {} does not exist", folder.display()),
+        ));
+    }
+
+    let mut state = SyncState::load(state_path);
+    // Every file is read before any is copied, so a file that can't be read
+    // stops the sync before it changes anything.
+    let mut outcomes = Vec::new();
+    for name in files {
+        let local_checksum = checksum_if_exists(Path::new(name))?;
+        let remote_checksum = checksum_if_exists(&folder.join(name))?;
+        let last_synced = state.get(name);
+
+        let outcome = match (local_checksum, remote_checksum) {
+            (None, None) => SyncOutcome::Missing,
+            (Some(local), Some(remote)) if local == remote => SyncOutcome::Unchanged,
+            (Some(_), None) => SyncOutcome::Pushed,
+            (None, Some(_)) => SyncOutcome::Pulled,
+            (Some(local), Some(_)) if last_synced == Some(local) => SyncOutcome::Pulled,
+            (Some(_), Some(remote)) if last_synced == Some(remote) => SyncOutcome::Pushed,
+            (Some(_), Some(_)) => match keep {
+                Some(KeepSide::Local) => SyncOutcome::Pushed,
+                Some(KeepSide::Remote) => SyncOutcome::Pulled,
+                None => SyncOutcome::Conflict,
+            },
+        };
+        outcomes.push(outcome);
+    }
+
+    for (name, outcome) in files.iter().zip(&outcomes) {
+        let local = PathBuf::from(name);
+        let remote = folder.join(name);
+        match outcome {
+            SyncOutcome::Pushed => copy_replacing(&local, &remote)?,
+            SyncOutcome::Pulled => copy_replacing(&remote, &local)?,
+            SyncOutcome::Missing | SyncOutcome::Unchanged | SyncOutcome::Conflict => {}
+        }
+        if *outcome != SyncOutcome::Conflict {
+            if let Some(checksum) = checksum_if_exists(&local)? {
+                state.set(name, checksum);
+            }
+        }
+    }
+    state.save(state_path)?;
+
+    Ok(outcomes)
+}
+
+/// Returns the checksum of a file, or `None` if it does not exist.
+fn checksum_if_exists(path: &Path) -> io::Result<Option<u64>> {
+    match file_checksum(path) {
+        Ok(checksum) => Ok(Some(checksum)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Copies a file over another through a temporary file next to the target,
+/// so an interrupted copy never leaves a half written target.
+fn copy_replacing(from: &Path, to: &Path) -> io::Result<()> {
+    let mut temporary = to.as_os_str().to_owned();
+    temporary.push(".sync-tmp");
+    std::fs::copy(from, &temporary)?;
+    std::fs::rename(&temporary, to)
+}
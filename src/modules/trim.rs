@@ -0,0 +1,146 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use std::path::Path;
+
+use crate::errors::ValidationError;
+use crate::modules::data_source::{table_exists, DATA_SOURCE_SCHEMA};
+use crate::modules::frequency::FREQUENCY_SCHEMA;
+use crate::modules::iata::IATA_SCHEMA;
+use crate::modules::region::Continent;
+
+pub const AIRPORT_SCHEMA: &str = include_str!("../../migrations_airport_database/setup/up.sql");
+
+const AIRPORT_COLUMNS: &str = "ID, Name, ICAO, PrimaryID, Latitude, Longtitude, Elevation, \
+    TransitionAltitude, TransitionLevel, SpeedLimit, SpeedLimitAltitude";
+const RUNWAY_COLUMNS: &str = "ID, AirportID, Ident, TrueHeading, Length, Width, Surface, \
+    Latitude, Longtitude, Elevation";
+
+/// Parses a comma separated list of continents and ICAO prefixes into ICAO prefixes.
+///
+/// # Arguments
+///
+/// * `regions` - For example `europe,KS,CY`.
+pub fn parse_regions(regions: &str) -> Result<Vec<String>, ValidationError> {
+    let mut prefixes = Vec::new();
+
+    for region in regions.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        if let Some(continent) = Continent::from_key(region) {
+            prefixes.extend(continent.prefixes().iter().map(|letter| letter.to_string()));
+        } else if region.len() <= 4 && region.chars().all(|c| c.is_ascii_alphanumeric()) {
+            prefixes.push(region.to_uppercase());
+        } else {
+            return Err(ValidationError::InvalidData(format!(
+                "Unknown region: {}",
+                region
+            )));
+        }
+    }
+
+    if prefixes.is_empty() {
+        return Err(ValidationError::InvalidData("No regions given".to_string()));
+    }
+
+    Ok(prefixes)
+}
+
+/// Creates a copy of the airport database containing only airports whose ICAO
+/// code starts with one of the prefixes, together with their runways, their
+/// frequencies, their IATA codes and the data sources recorded for them.
+///
+/// # Arguments
+///
+/// * `source` - The full airport database.
+/// * `output` - The trimmed database to create, which must not exist yet.
+/// * `prefixes` - ICAO prefixes as returned by [`parse_regions`].
+///
+/// # Returns
+///
+/// The number of airports and runways copied.
+pub fn trim_airport_database(
+    source: &Path,
+    output: &Path,
+    prefixes: &[String],
+) -> Result<(usize, usize), Error> {
+    if output.exists() {
+        return Err(Error::QueryBuilderError(
+            format!("{} already exists", output.display()).into(),
+        ));
+    }
+
+    let mut conn = SqliteConnection::establish(&output.to_string_lossy())
+        .map_err(|e| Error::QueryBuilderError(e.into()))?;
+
+    conn.batch_execute(AIRPORT_SCHEMA)?;
+    conn.batch_execute(DATA_SOURCE_SCHEMA)?;
+    diesel::sql_query(format!(
+        "ATTACH DATABASE '{}' AS source",
+        source.to_string_lossy().replace('\'', "''")
+    ))
+    .execute(&mut conn)?;
+
+    let filter = prefixes
+        .iter()
+        .map(|prefix| format!("ICAO LIKE '{}%'", prefix))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    conn.transaction(|conn| {
+        let airports = diesel::sql_query(format!(
+            "INSERT INTO main.airports ({columns}) \
+             SELECT {columns} FROM source.Airports WHERE {filter}",
+            columns = AIRPORT_COLUMNS,
+        ))
+        .execute(conn)?;
+
+        let runways = diesel::sql_query(format!(
+            "INSERT INTO main.runways ({columns}) \
+             SELECT {columns} FROM source.Runways \
+             WHERE AirportID IN (SELECT ID FROM main.airports)",
+            columns = RUNWAY_COLUMNS,
+        ))
+        .execute(conn)?;
+
+        if table_exists(conn, "source", "airport_sources")? {
+            diesel::sql_query(
+                "INSERT INTO main.airport_sources (airport_id, source_id) \
+                 SELECT airport_id, source_id FROM source.airport_sources \
+                 WHERE airport_id IN (SELECT ID FROM main.airports)",
+            )
+            .execute(conn)?;
+        }
+        if table_exists(conn, "source", "frequencies")? {
+            conn.batch_execute(FREQUENCY_SCHEMA)?;
+            diesel::sql_query(
+                "INSERT INTO main.frequencies (airport_id, type, description, frequency_khz) \
+                 SELECT airport_id, type, description, frequency_khz FROM source.frequencies \
+                 WHERE airport_id IN (SELECT ID FROM main.airports)",
+            )
+            .execute(conn)?;
+        }
+        if table_exists(conn, "source", "iata_codes")? {
+            conn.batch_execute(IATA_SCHEMA)?;
+            diesel::sql_query(
+                "INSERT INTO main.iata_codes (icao, iata) \
+                 SELECT icao, iata FROM source.iata_codes \
+                 WHERE icao IN (SELECT ICAO FROM main.airports)",
+            )
+            .execute(conn)?;
+        }
+        if table_exists(conn, "source", "data_sources")? {
+            diesel::sql_query(
+                "INSERT INTO main.data_sources (id, name, version, date) \
+                 SELECT id, name, version, date FROM source.data_sources",
+            )
+            .execute(conn)?;
+        }
+        if table_exists(conn, "source", "airac_cycle")? {
+            diesel::sql_query(
+                "INSERT INTO main.airac_cycle (id, cycle) SELECT id, cycle FROM source.airac_cycle",
+            )
+            .execute(conn)?;
+        }
+
+        Ok((airports, runways))
+    })
+}
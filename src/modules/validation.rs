@@ -0,0 +1,142 @@
+use chrono::NaiveDate;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The format dates are entered and stored in.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The problems found in the fields of a form, so each can be shown next to its field.
+#[derive(Debug, Default)]
+pub struct FieldErrors {
+    errors: Vec<(&'static str, String)>,
+}
+
+impl FieldErrors {
+    /// Records the error of a parsed field, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the field.
+    /// * `result` - The value of the field, or why it is not valid.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if it is not valid.
+    pub fn check<T>(&mut self, field: &'static str, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(message) => {
+                self.add(field, message);
+                None
+            }
+        }
+    }
+
+    /// Records an error of a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the field.
+    /// * `message` - Why the field is not valid.
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.errors.push((field, message.into()));
+    }
+
+    /// Returns the first error of a field, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the field.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, message)| message.as_str())
+    }
+
+    /// Returns whether no field has an error.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Parses a date entered as `YYYY-MM-DD`.
+///
+/// # Arguments
+///
+/// * `text` - The date as entered.
+pub fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(text.trim(), DATE_FORMAT)
+        .map_err(|_| "Enter the date as YYYY-MM-DD".to_string())
+}
+
+/// Parses the date of a flight, which cannot lie in the future.
+///
+/// # Arguments
+///
+/// * `text` - The date as entered.
+/// * `today` - The current date.
+pub fn parse_flight_date(text: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let date = parse_date(text)?;
+    if date > today {
+        return Err("The date is in the future".to_string());
+    }
+
+    Ok(date)
+}
+
+/// Parses a period of two dates, the end on or after the start.
+///
+/// # Arguments
+///
+/// * `start` - The first day as entered, reported as the `start` field.
+/// * `end` - The last day as entered, reported as the `end` field.
+///
+/// # Returns
+///
+/// The first and last day, or the errors of both fields.
+pub fn parse_period(start: &str, end: &str) -> Result<(NaiveDate, NaiveDate), FieldErrors> {
+    let mut errors = FieldErrors::default();
+    let start = errors.check("start", parse_date(start));
+    let end = errors.check("end", parse_date(end));
+
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end => Ok((start, end)),
+        (Some(_), Some(_)) => {
+            errors.add("end", "The end is before the start");
+            Err(errors)
+        }
+        _ => Err(errors),
+    }
+}
+
+/// Checks the format of an airport code, returning it in upper case.
+///
+/// # Arguments
+///
+/// * `text` - The code as entered.
+pub fn parse_airport_code(text: &str) -> Result<String, String> {
+    let code = text.trim();
+    if !(3..=4).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Enter a 4 letter ICAO or 3 letter IATA code".to_string());
+    }
+
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Parses a number that has to be at least a minimum.
+///
+/// # Arguments
+///
+/// * `text` - The number as entered.
+/// * `min` - The smallest allowed value.
+pub fn parse_number<T>(text: &str, min: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Display,
+{
+    text.trim()
+        .parse::<T>()
+        .ok()
+        .filter(|value| *value >= min)
+        .ok_or_else(|| format!("Expected a number of at least {}", min))
+}
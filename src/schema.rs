@@ -10,6 +10,25 @@ diesel::table! {
         cruise_speed -> Integer,
         date_flown -> Nullable<Text>,
         takeoff_distance -> Nullable<Integer>,
+        service_ceiling -> Nullable<Integer>,
+        helicopter -> Bool,
+        amphibian -> Bool,
+    }
+}
+
+diesel::table! {
+    aircraft_sim_titles (id) {
+        id -> Integer,
+        aircraft -> Integer,
+        simulator -> Text,
+        title -> Text,
+    }
+}
+
+diesel::table! {
+    airport_icao_aliases (alias) {
+        alias -> Text,
+        icao -> Text,
     }
 }
 
@@ -20,6 +39,9 @@ diesel::table! {
         arrival_icao -> Text,
         aircraft -> Integer,
         date -> Text,
+        notes -> Nullable<Text>,
+        planned_arrival_icao -> Nullable<Text>,
+        airline -> Nullable<Text>,
     }
 }
 
@@ -1,14 +1,19 @@
 use crate::models::*;
+use crate::modules::airac::AiracCycle;
+use crate::modules::frequency::FrequencyRecord;
+use crate::modules::history::HistoryMergeSummary;
+use crate::modules::history_export::HistoryExportFormat;
 use diesel::result::Error;
+use std::path::Path;
 
 pub trait AircraftOperations {
     fn get_not_flown_count(&mut self) -> Result<i32, Error>;
     fn random_not_flown_aircraft(&mut self) -> Result<Aircraft, Error>;
     fn get_all_aircraft(&mut self) -> Result<Vec<Aircraft>, Error>;
-    fn update_aircraft(&mut self, record: &Aircraft) -> Result<(), Error>;
     fn random_aircraft(&mut self) -> Result<Aircraft, Error>;
     fn get_aircraft_by_id(&mut self, aircraft_id: i32) -> Result<Aircraft, Error>;
     fn mark_all_aircraft_not_flown(&mut self) -> Result<(), Error>;
+    fn recount_flights(&mut self) -> Result<usize, Error>;
 }
 
 pub trait AirportOperations: AircraftOperations {
@@ -36,6 +41,11 @@ pub trait AirportOperations: AircraftOperations {
         max_distance_nm: i32,
     ) -> Result<Airport, Error>;
     fn get_airports(&mut self) -> Result<Vec<Airport>, Error>;
+    fn get_airport_by_icao(&mut self, icao: &str) -> Result<Airport, Error>;
+    fn get_iata_codes(&mut self) -> Result<Vec<AirportIataCode>, Error>;
+    fn import_iata_codes(&mut self, records: &[AirportIataCode]) -> Result<usize, Error>;
+    fn get_airport_aliases(&mut self) -> Result<Vec<AirportAlias>, Error>;
+    fn add_airport_alias(&mut self, alias: &AirportAlias) -> Result<(), Error>;
 }
 
 pub trait HistoryOperations {
@@ -45,7 +55,55 @@ pub trait HistoryOperations {
         arrival: &Airport,
         aircraft_record: &Aircraft,
     ) -> Result<(), Error>;
+    fn add_to_history_on(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        date: &str,
+        details: FlightDetails,
+    ) -> Result<(), Error>;
     fn get_history(&mut self) -> Result<Vec<History>, Error>;
+    fn find_history_entry(
+        &mut self,
+        departure: &Airport,
+        arrival: &Airport,
+        aircraft_record: &Aircraft,
+        date: &str,
+    ) -> Result<Option<History>, Error>;
+    fn merge_history(&mut self, other: &Path) -> Result<HistoryMergeSummary, Error>;
+    fn export_history(&mut self, format: HistoryExportFormat) -> Result<String, Error>;
 }
 
-pub trait DatabaseOperations: AircraftOperations + AirportOperations + HistoryOperations {}
+pub trait SimulatorMappingOperations {
+    fn add_sim_title(
+        &mut self,
+        aircraft_record: &Aircraft,
+        simulator: &str,
+        title: &str,
+    ) -> Result<(), Error>;
+    fn get_sim_titles(&mut self) -> Result<Vec<AircraftSimTitle>, Error>;
+    fn delete_sim_title(&mut self, sim_title_id: i32) -> Result<(), Error>;
+}
+
+pub trait DataSourceOperations {
+    fn get_data_sources(&mut self) -> Result<Vec<DataSource>, Error>;
+    fn add_data_source(&mut self, name: &str, version: &str, date: &str) -> Result<usize, Error>;
+    fn get_airac_cycle(&mut self) -> Result<Option<AiracCycle>, Error>;
+    fn set_airac_cycle(&mut self, cycle: AiracCycle) -> Result<(), Error>;
+}
+
+pub trait FrequencyOperations {
+    fn get_frequencies(&mut self, airport: &Airport) -> Result<Vec<Frequency>, Error>;
+    fn import_frequencies(&mut self, records: &[FrequencyRecord]) -> Result<usize, Error>;
+}
+
+pub trait DatabaseOperations:
+    AircraftOperations
+    + AirportOperations
+    + HistoryOperations
+    + SimulatorMappingOperations
+    + DataSourceOperations
+    + FrequencyOperations
+{
+}